@@ -0,0 +1,108 @@
+use crate::binance::Candle;
+use crate::timeframe::TimeFrame;
+use rust_decimal::Decimal;
+
+/// Relative difference tolerated between derived and exchange volumes; they
+/// are summed as floating point so the last digits rarely agree exactly
+const VOLUME_TOLERANCE: f64 = 1e-6;
+
+/// Stored timeframes a higher timeframe can be built from, finest first.
+/// Empty if the timeframe is only ever fetched from the exchange.
+pub fn rollup_sources(target: TimeFrame) -> &'static [TimeFrame] {
+    match target {
+        TimeFrame::M15 | TimeFrame::H1 | TimeFrame::H4 | TimeFrame::D1 => &[TimeFrame::M1, TimeFrame::M5],
+        _ => &[],
+    }
+}
+
+/// Combine `source` candles (oldest first) into `target` candles aligned to
+/// UTC boundaries.
+///
+/// A bar is only produced when every source candle inside it is present, so
+/// a gap never shows up as a wrong high, low or volume. The exception is the
+/// bar containing `now_ms`, which is still forming and is built from whatever
+/// has been stored so far.
+pub fn rollup(candles: &[Candle], source: TimeFrame, target: TimeFrame, now_ms: u64) -> Vec<Candle> {
+    let per_bar = (target.duration_ms() / source.duration_ms()) as usize;
+    let forming = target.bar_start(now_ms);
+
+    let mut bars = Vec::new();
+    let mut current: Option<(Candle, usize)> = None;
+    for candle in candles {
+        let start = target.bar_start(candle.timestamp);
+        match &mut current {
+            Some((bar, count)) if bar.timestamp == start => {
+                bar.high = bar.high.max(candle.high);
+                bar.low = bar.low.min(candle.low);
+                bar.close = candle.close;
+                bar.volume += candle.volume;
+                *count += 1;
+            }
+            _ => {
+                if let Some((bar, count)) = current.take()
+                    && (count == per_bar || bar.timestamp == forming)
+                {
+                    bars.push(bar);
+                }
+                current = Some((Candle { timestamp: start, ..candle.clone() }, 1));
+            }
+        }
+    }
+    if let Some((bar, count)) = current
+        && (count == per_bar || bar.timestamp == forming)
+    {
+        bars.push(bar);
+    }
+
+    bars
+}
+
+/// Build `bar_ms` long bars from recorded `(timestamp_ms, price)` ticks,
+/// oldest first. Intervals without ticks produce no bar. The ticker only
+/// reports rolling 24h volume, so the bars carry no volume.
+pub fn ticks_to_candles(ticks: &[(u64, Decimal)], bar_ms: u64) -> Vec<Candle> {
+    let mut bars: Vec<Candle> = Vec::new();
+    for &(timestamp, price) in ticks {
+        let start = timestamp - timestamp % bar_ms;
+        match bars.last_mut() {
+            Some(bar) if bar.timestamp == start => {
+                bar.high = bar.high.max(price);
+                bar.low = bar.low.min(price);
+                bar.close = price;
+            }
+            _ => bars.push(Candle { open: price, high: price, low: price, close: price, volume: 0.0, timestamp: start }),
+        }
+    }
+    bars
+}
+
+/// A derived bar that disagrees with the one the exchange reported
+#[derive(Debug, Clone)]
+pub struct CandleMismatch {
+    pub timestamp: u64,
+    pub derived: Candle,
+    pub exchange: Candle,
+}
+
+/// Compare derived bars against exchange bars for the same timeframe. Bars
+/// present on only one side are skipped.
+pub fn compare_with_exchange(derived: &[Candle], exchange: &[Candle]) -> Vec<CandleMismatch> {
+    derived
+        .iter()
+        .filter_map(|ours| {
+            let theirs = exchange.iter().find(|c| c.timestamp == ours.timestamp)?;
+            let prices_match = ours.open == theirs.open
+                && ours.high == theirs.high
+                && ours.low == theirs.low
+                && ours.close == theirs.close;
+            let volume_matches =
+                (ours.volume - theirs.volume).abs() <= VOLUME_TOLERANCE * ours.volume.abs().max(theirs.volume.abs());
+
+            (!prices_match || !volume_matches).then(|| CandleMismatch {
+                timestamp: ours.timestamp,
+                derived: ours.clone(),
+                exchange: theirs.clone(),
+            })
+        })
+        .collect()
+}
@@ -1,8 +1,22 @@
 use crate::binance::{PriceInfo, Candle};
-use crate::config::Config;
+use crate::config::{ChartSource, Config};
+use crate::error::{CoinPeekError, SymbolResult};
+use crate::exchange::MarketDataSource;
+use crate::history::{self, PricePoint};
+use crate::indicators;
+use crate::rules::AlertRule;
+use crate::symbols::SymbolRegistry;
+use crate::timeframe::TimeFrame;
 #[cfg(not(target_arch = "wasm32"))]
 use crate::database::Database;
 use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::FromPrimitive;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// How long a footer status message stays visible
+const STATUS_MESSAGE_SECONDS: i64 = 10;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ErrorType {
@@ -20,6 +34,25 @@ pub enum ErrorSeverity {
     Info,        // Minor issues, mostly informational
 }
 
+/// Category a data-layer error is listed under in the error panel
+pub fn error_type(error: &CoinPeekError) -> ErrorType {
+    match error {
+        CoinPeekError::Network(_) => ErrorType::Network,
+        CoinPeekError::HttpStatus { .. } | CoinPeekError::RateLimited { .. } | CoinPeekError::Unsupported(_) => ErrorType::Api,
+        CoinPeekError::Decode(_) | CoinPeekError::InvalidSymbol { .. } => ErrorType::Validation,
+        CoinPeekError::Database(_) | CoinPeekError::Io(_) => ErrorType::Database,
+    }
+}
+
+/// How serious a data-layer error is for the app
+pub fn error_severity(error: &CoinPeekError) -> ErrorSeverity {
+    match error {
+        CoinPeekError::Database(_) => ErrorSeverity::Critical,
+        CoinPeekError::RateLimited { .. } => ErrorSeverity::Info,
+        _ => ErrorSeverity::Warning,
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct AppError {
     pub error_type: ErrorType,
@@ -54,21 +87,92 @@ pub struct SortConfig {
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum FilterType {
-    PriceRange { min: Option<f64>, max: Option<f64> },
+    PriceRange { min: Option<Decimal>, max: Option<Decimal> },
     ChangePercentRange { min: Option<f64>, max: Option<f64> },
     VolumeRange { min: Option<f64>, max: Option<f64> },
     SymbolSearch(String),
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum AlertCondition {
-    PriceAbove(f64),        // Alert when price > threshold
-    PriceBelow(f64),        // Alert when price < threshold
+    PriceAbove(Decimal),    // Alert when price > threshold
+    PriceBelow(Decimal),    // Alert when price < threshold
     PercentChangeAbove(f64), // Alert when % change > threshold (positive)
     PercentChangeBelow(f64), // Alert when % change < threshold (negative)
     VolumeSpike(f64),       // Alert when volume > threshold
+    // Alert when price rises to `level`; re-arms once it falls `hysteresis_percent` below
+    CrossesAbove { level: Decimal, hysteresis_percent: f64 },
+    // Alert when price falls to `level`; re-arms once it rises `hysteresis_percent` above
+    CrossesBelow { level: Decimal, hysteresis_percent: f64 },
+    // Alert when price is `percent` above the window's low (or, if negative, below its high)
+    PercentMove { percent: f64, window_minutes: u32 },
+    // Alert when price is `amount` above the window's low (or, if negative, below its high)
+    PriceMove { amount: Decimal, window_minutes: u32 },
+    // Alert when price leaves the range it traded in over the window
+    RangeBreakout { window_minutes: u32 },
+    NewHigh { window_minutes: u32 },  // Alert when price tops the window's high
+    NewLow { window_minutes: u32 },   // Alert when price drops under the window's low
+    // Alert while the `period` RSI of the timeframe's candles is above/below `level`
+    RsiAbove { level: f64, period: u32, timeframe: TimeFrame },
+    RsiBelow { level: f64, period: u32, timeframe: TimeFrame },
+    // Alert when the `fast` close moving average crosses above/below the `slow` one
+    GoldenCross { fast: u32, slow: u32, timeframe: TimeFrame },
+    DeathCross { fast: u32, slow: u32, timeframe: TimeFrame },
+    // Alert when price crosses out of the `period` Bollinger bands `std_devs` wide
+    AboveUpperBand { period: u32, std_devs: f64, timeframe: TimeFrame },
+    BelowLowerBand { period: u32, std_devs: f64, timeframe: TimeFrame },
+    // Alert while the current candle's volume is `multiple` times the average of the `period` before it
+    VolumeAboveAverage { multiple: f64, period: u32, timeframe: TimeFrame },
+    // Alert while a rule over one or more symbols holds, e.g. "ETHUSDT / BTCUSDT > 0.05"
+    Rule(AlertRule),
+}
+
+/// Whether an alert keeps firing or switches itself off after the first time
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum AlertMode {
+    #[default]
+    Recurring,
+    Once,
+}
+
+impl AlertMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertMode::Recurring => "recurring",
+            AlertMode::Once => "once",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<AlertMode> {
+        match name {
+            "recurring" => Some(AlertMode::Recurring),
+            "once" => Some(AlertMode::Once),
+            _ => None,
+        }
+    }
 }
 
+/// Minutes an alert stays quiet after firing unless set otherwise
+pub const DEFAULT_ALERT_COOLDOWN_MINUTES: u32 = 60;
+
+/// Window the alert editor suggests for windowed alerts
+const DEFAULT_ALERT_WINDOW_MINUTES: u32 = 15;
+
+/// Longest window a windowed alert may look back over
+pub const MAX_ALERT_WINDOW_MINUTES: u32 = 24 * 60;
+
+/// Candle timeframe the alert editor suggests for indicator alerts
+const DEFAULT_INDICATOR_TIMEFRAME: TimeFrame = TimeFrame::H1;
+
+/// Longest indicator period an alert may use, so the candles it needs stay
+/// a quick load
+pub const MAX_INDICATOR_PERIOD: u32 = 200;
+
+/// Shortest wait between reloads of an indicator series' candles while its
+/// bar is still open
+const INDICATOR_REFRESH_SECONDS: i64 = 60;
+
 #[derive(Debug, Clone)]
 pub struct PriceAlert {
     pub id: u32,
@@ -79,6 +183,621 @@ pub struct PriceAlert {
     pub last_triggered: Option<DateTime<Utc>>,
     pub trigger_count: u32,
     pub message: Option<String>, // Custom alert message
+    pub cooldown_minutes: u32,   // Minimum time between notifications
+    pub mode: AlertMode,
+    pub armed: Option<bool>,     // Whether a crossing alert can fire; None until the first price is seen
+    pub sinks: Vec<String>,      // Notification sinks it's sent to; empty for the configured defaults
+}
+
+impl PriceAlert {
+    /// Advance a crossing alert's state, returning true on a crossing.
+    /// `beyond` is whether the price is at or past the level and `rearm`
+    /// whether it has moved back past the hysteresis band. The first price
+    /// seen only sets the state, so an alert created on the far side of its
+    /// level waits for the price to come back first.
+    fn cross(&mut self, beyond: bool, rearm: bool) -> bool {
+        match self.armed {
+            None => {
+                self.armed = Some(!beyond);
+                false
+            }
+            Some(true) if beyond => {
+                self.armed = Some(false);
+                true
+            }
+            Some(false) if rearm => {
+                self.armed = Some(true);
+                false
+            }
+            _ => false,
+        }
+    }
+}
+
+/// One firing of an alert, kept as alert history
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertTrigger {
+    pub alert_id: u32,
+    pub symbol: String,
+    pub price: Decimal,             // Price when the alert fired
+    pub message: String,
+    pub triggered_at: DateTime<Utc>,
+}
+
+/// Kinds of condition the alert editor offers, one per `AlertCondition` variant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConditionKind {
+    PriceAbove,
+    PriceBelow,
+    CrossesAbove,
+    CrossesBelow,
+    PercentChangeAbove,
+    PercentChangeBelow,
+    VolumeSpike,
+    PercentMove,
+    PriceMove,
+    RangeBreakout,
+    NewHigh,
+    NewLow,
+    RsiAbove,
+    RsiBelow,
+    GoldenCross,
+    DeathCross,
+    AboveUpperBand,
+    BelowLowerBand,
+    VolumeAboveAverage,
+    Rule,
+}
+
+impl ConditionKind {
+    /// Every kind in the order the editor cycles through them
+    pub const ALL: [ConditionKind; 20] = [
+        ConditionKind::PriceAbove,
+        ConditionKind::PriceBelow,
+        ConditionKind::CrossesAbove,
+        ConditionKind::CrossesBelow,
+        ConditionKind::PercentChangeAbove,
+        ConditionKind::PercentChangeBelow,
+        ConditionKind::VolumeSpike,
+        ConditionKind::PercentMove,
+        ConditionKind::PriceMove,
+        ConditionKind::RangeBreakout,
+        ConditionKind::NewHigh,
+        ConditionKind::NewLow,
+        ConditionKind::RsiAbove,
+        ConditionKind::RsiBelow,
+        ConditionKind::GoldenCross,
+        ConditionKind::DeathCross,
+        ConditionKind::AboveUpperBand,
+        ConditionKind::BelowLowerBand,
+        ConditionKind::VolumeAboveAverage,
+        ConditionKind::Rule,
+    ];
+
+    /// Name shown in the editor
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConditionKind::PriceAbove => "Price above",
+            ConditionKind::PriceBelow => "Price below",
+            ConditionKind::CrossesAbove => "Crosses above",
+            ConditionKind::CrossesBelow => "Crosses below",
+            ConditionKind::PercentChangeAbove => "24h change above",
+            ConditionKind::PercentChangeBelow => "24h change below",
+            ConditionKind::VolumeSpike => "Volume above",
+            ConditionKind::PercentMove => "% move in window",
+            ConditionKind::PriceMove => "$ move in window",
+            ConditionKind::RangeBreakout => "Range breakout",
+            ConditionKind::NewHigh => "New high",
+            ConditionKind::NewLow => "New low",
+            ConditionKind::RsiAbove => "RSI above",
+            ConditionKind::RsiBelow => "RSI below",
+            ConditionKind::GoldenCross => "Golden cross",
+            ConditionKind::DeathCross => "Death cross",
+            ConditionKind::AboveUpperBand => "Above upper band",
+            ConditionKind::BelowLowerBand => "Below lower band",
+            ConditionKind::VolumeAboveAverage => "Volume × average",
+            ConditionKind::Rule => "Rule",
+        }
+    }
+
+    /// The next kind in `ALL`, or the previous one, wrapping around
+    pub fn step(&self, forward: bool) -> ConditionKind {
+        let len = Self::ALL.len();
+        let index = Self::ALL.iter().position(|kind| kind == self).unwrap_or(0);
+        Self::ALL[if forward { (index + 1) % len } else { (index + len - 1) % len }]
+    }
+
+    /// Whether the condition takes a threshold
+    pub fn uses_threshold(&self) -> bool {
+        !matches!(
+            self,
+            ConditionKind::RangeBreakout
+                | ConditionKind::NewHigh
+                | ConditionKind::NewLow
+                | ConditionKind::GoldenCross
+                | ConditionKind::DeathCross
+                | ConditionKind::Rule
+        )
+    }
+
+    /// Whether the condition takes a hysteresis band
+    pub fn uses_hysteresis(&self) -> bool {
+        matches!(self, ConditionKind::CrossesAbove | ConditionKind::CrossesBelow)
+    }
+
+    /// Whether the condition looks back over a window of prices
+    pub fn uses_window(&self) -> bool {
+        matches!(
+            self,
+            ConditionKind::PercentMove
+                | ConditionKind::PriceMove
+                | ConditionKind::RangeBreakout
+                | ConditionKind::NewHigh
+                | ConditionKind::NewLow
+        )
+    }
+
+    /// Whether the condition is computed from candles
+    pub fn uses_indicator(&self) -> bool {
+        matches!(
+            self,
+            ConditionKind::RsiAbove
+                | ConditionKind::RsiBelow
+                | ConditionKind::GoldenCross
+                | ConditionKind::DeathCross
+                | ConditionKind::AboveUpperBand
+                | ConditionKind::BelowLowerBand
+                | ConditionKind::VolumeAboveAverage
+        )
+    }
+
+    /// Whether the condition is a rule, which names its own symbols
+    pub fn uses_rule(&self) -> bool {
+        matches!(self, ConditionKind::Rule)
+    }
+}
+
+/// What the alert editor has typed for a condition. Empty text means the
+/// usual value where a field has one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConditionFields<'a> {
+    pub threshold: &'a str,
+    pub hysteresis: &'a str,   // Percent band a crossing re-arms behind
+    pub window: &'a str,       // Minutes a windowed condition looks back over
+    pub period: &'a str,       // Indicator period, "fast/slow" for average crosses
+    pub timeframe: TimeFrame,  // Candles an indicator condition is computed from
+    pub rule: &'a str,
+}
+
+impl Default for ConditionFields<'_> {
+    fn default() -> Self {
+        Self {
+            threshold: "",
+            hysteresis: "",
+            window: "",
+            period: "",
+            timeframe: DEFAULT_INDICATOR_TIMEFRAME,
+            rule: "",
+        }
+    }
+}
+
+impl AlertCondition {
+    /// The editor's kind for this condition
+    pub fn kind(&self) -> ConditionKind {
+        match self {
+            AlertCondition::PriceAbove(_) => ConditionKind::PriceAbove,
+            AlertCondition::PriceBelow(_) => ConditionKind::PriceBelow,
+            AlertCondition::CrossesAbove { .. } => ConditionKind::CrossesAbove,
+            AlertCondition::CrossesBelow { .. } => ConditionKind::CrossesBelow,
+            AlertCondition::PercentChangeAbove(_) => ConditionKind::PercentChangeAbove,
+            AlertCondition::PercentChangeBelow(_) => ConditionKind::PercentChangeBelow,
+            AlertCondition::VolumeSpike(_) => ConditionKind::VolumeSpike,
+            AlertCondition::PercentMove { .. } => ConditionKind::PercentMove,
+            AlertCondition::PriceMove { .. } => ConditionKind::PriceMove,
+            AlertCondition::RangeBreakout { .. } => ConditionKind::RangeBreakout,
+            AlertCondition::NewHigh { .. } => ConditionKind::NewHigh,
+            AlertCondition::NewLow { .. } => ConditionKind::NewLow,
+            AlertCondition::RsiAbove { .. } => ConditionKind::RsiAbove,
+            AlertCondition::RsiBelow { .. } => ConditionKind::RsiBelow,
+            AlertCondition::GoldenCross { .. } => ConditionKind::GoldenCross,
+            AlertCondition::DeathCross { .. } => ConditionKind::DeathCross,
+            AlertCondition::AboveUpperBand { .. } => ConditionKind::AboveUpperBand,
+            AlertCondition::BelowLowerBand { .. } => ConditionKind::BelowLowerBand,
+            AlertCondition::VolumeAboveAverage { .. } => ConditionKind::VolumeAboveAverage,
+            AlertCondition::Rule(_) => ConditionKind::Rule,
+        }
+    }
+
+    /// Minutes of price history the condition looks back over; `None` for
+    /// conditions that only need the latest price
+    pub fn window_minutes(&self) -> Option<u32> {
+        match self {
+            AlertCondition::PercentMove { window_minutes, .. }
+            | AlertCondition::PriceMove { window_minutes, .. }
+            | AlertCondition::RangeBreakout { window_minutes }
+            | AlertCondition::NewHigh { window_minutes }
+            | AlertCondition::NewLow { window_minutes } => Some(*window_minutes),
+            _ => None,
+        }
+    }
+
+    /// Timeframe of the candles an indicator condition is computed from
+    pub fn indicator_timeframe(&self) -> Option<TimeFrame> {
+        match self {
+            AlertCondition::RsiAbove { timeframe, .. }
+            | AlertCondition::RsiBelow { timeframe, .. }
+            | AlertCondition::GoldenCross { timeframe, .. }
+            | AlertCondition::DeathCross { timeframe, .. }
+            | AlertCondition::AboveUpperBand { timeframe, .. }
+            | AlertCondition::BelowLowerBand { timeframe, .. }
+            | AlertCondition::VolumeAboveAverage { timeframe, .. } => Some(*timeframe),
+            _ => None,
+        }
+    }
+
+    /// Number of the latest candles an indicator condition is computed from;
+    /// zero for conditions that don't use candles
+    pub fn candles_needed(&self) -> usize {
+        match *self {
+            // Wilder's smoothing needs a few periods to settle
+            AlertCondition::RsiAbove { period, .. } | AlertCondition::RsiBelow { period, .. } => 4 * period as usize + 1,
+            AlertCondition::GoldenCross { slow, .. } | AlertCondition::DeathCross { slow, .. } => slow as usize,
+            AlertCondition::AboveUpperBand { period, .. } | AlertCondition::BelowLowerBand { period, .. } => period as usize,
+            // The bar still forming is left out, so one more is needed
+            AlertCondition::VolumeAboveAverage { period, .. } => period as usize + 2,
+            _ => 0,
+        }
+    }
+
+    /// The threshold as the editor shows it
+    pub fn threshold_text(&self) -> String {
+        match self {
+            AlertCondition::PriceAbove(threshold) | AlertCondition::PriceBelow(threshold) => threshold.to_string(),
+            AlertCondition::CrossesAbove { level, .. } | AlertCondition::CrossesBelow { level, .. } => level.to_string(),
+            AlertCondition::PercentChangeAbove(threshold)
+            | AlertCondition::PercentChangeBelow(threshold)
+            | AlertCondition::VolumeSpike(threshold) => threshold.to_string(),
+            AlertCondition::PercentMove { percent, .. } => percent.to_string(),
+            AlertCondition::PriceMove { amount, .. } => amount.to_string(),
+            AlertCondition::RsiAbove { level, .. } | AlertCondition::RsiBelow { level, .. } => level.to_string(),
+            AlertCondition::AboveUpperBand { std_devs, .. } | AlertCondition::BelowLowerBand { std_devs, .. } => {
+                std_devs.to_string()
+            }
+            AlertCondition::VolumeAboveAverage { multiple, .. } => multiple.to_string(),
+            AlertCondition::RangeBreakout { .. }
+            | AlertCondition::NewHigh { .. }
+            | AlertCondition::NewLow { .. }
+            | AlertCondition::GoldenCross { .. }
+            | AlertCondition::DeathCross { .. }
+            | AlertCondition::Rule(_) => String::new(),
+        }
+    }
+
+    /// The rule text of a rule condition
+    pub fn rule_text(&self) -> String {
+        match self {
+            AlertCondition::Rule(rule) => rule.to_string(),
+            _ => String::new(),
+        }
+    }
+
+    /// The indicator period as typed into the alert editor, "fast/slow" for
+    /// moving average crosses
+    pub fn period_text(&self) -> String {
+        match self {
+            AlertCondition::GoldenCross { fast, slow, .. } | AlertCondition::DeathCross { fast, slow, .. } => {
+                format!("{}/{}", fast, slow)
+            }
+            AlertCondition::RsiAbove { period, .. }
+            | AlertCondition::RsiBelow { period, .. }
+            | AlertCondition::AboveUpperBand { period, .. }
+            | AlertCondition::BelowLowerBand { period, .. }
+            | AlertCondition::VolumeAboveAverage { period, .. } => period.to_string(),
+            _ => String::new(),
+        }
+    }
+
+    /// The hysteresis band as the editor shows it; empty for level conditions
+    pub fn hysteresis_text(&self) -> String {
+        match self {
+            AlertCondition::CrossesAbove { hysteresis_percent, .. }
+            | AlertCondition::CrossesBelow { hysteresis_percent, .. } => hysteresis_percent.to_string(),
+            _ => String::new(),
+        }
+    }
+
+    /// Build a condition of `kind` from the editor's fields: a threshold, for
+    /// crossings a hysteresis band in percent (empty means none), for
+    /// windowed conditions the window in minutes, for indicator conditions
+    /// the period and candle timeframe and for rules the rule text. An empty
+    /// period or band width means the usual one: RSI 14, moving averages
+    /// 50/200, 20 candles otherwise and bands 2 standard deviations wide.
+    pub fn from_kind(kind: ConditionKind, fields: &ConditionFields) -> Result<AlertCondition, String> {
+        let ConditionFields { threshold, hysteresis, window, period, timeframe, rule } = *fields;
+        let threshold = threshold.trim();
+        let parse_period = |text: &str| match text.trim().parse::<u32>() {
+            Ok(period) if (1..=MAX_INDICATOR_PERIOD).contains(&period) => Ok(period),
+            _ => Err(format!("Invalid period: '{}'. Use 1 to {} candles", text.trim(), MAX_INDICATOR_PERIOD)),
+        };
+        let period_or = |default: u32| match period.trim() {
+            "" => Ok(default),
+            text => parse_period(text),
+        };
+        let averages = || {
+            let (fast, slow) = match period.trim() {
+                "" => (50, 200),
+                text => {
+                    let (fast, slow) = text
+                        .split_once('/')
+                        .ok_or_else(|| format!("Invalid periods: '{}'. Use fast/slow, e.g. 50/200", text))?;
+                    (parse_period(fast)?, parse_period(slow)?)
+                }
+            };
+            if fast >= slow {
+                return Err("The fast average needs a shorter period than the slow one".to_string());
+            }
+            Ok((fast, slow))
+        };
+        let std_devs = || match threshold {
+            "" => Ok(2.0),
+            text => match text.parse::<f64>() {
+                Ok(std_devs) if std_devs > 0.0 && std_devs.is_finite() => Ok(std_devs),
+                _ => Err(format!("Invalid band width: '{}'. Use standard deviations, e.g. 2", text)),
+            },
+        };
+        let window_minutes = || match window.trim().parse::<u32>() {
+            Ok(minutes) if (1..=MAX_ALERT_WINDOW_MINUTES).contains(&minutes) => Ok(minutes),
+            _ => Err(format!("Invalid window: '{}'. Use 1 to {} minutes", window.trim(), MAX_ALERT_WINDOW_MINUTES)),
+        };
+        let change = |value: Result<f64, String>| match value? {
+            0.0 => Err("The move can't be zero; use a negative value for drops".to_string()),
+            value => Ok(value),
+        };
+        let hysteresis_percent = || match hysteresis.trim().trim_end_matches('%') {
+            "" => Ok(0.0),
+            band => match band.parse::<f64>() {
+                Ok(percent) if (0.0..100.0).contains(&percent) => Ok(percent),
+                _ => Err(format!("Invalid hysteresis: '{}'. Use a percent from 0 to 100", hysteresis.trim())),
+            },
+        };
+        let price = || match threshold.parse::<Decimal>() {
+            Ok(price) if price > Decimal::ZERO => Ok(price),
+            _ => Err(format!("Invalid price: '{}'", threshold)),
+        };
+        let number = || match threshold.trim_end_matches('%').parse::<f64>() {
+            Ok(value) if value.is_finite() => Ok(value),
+            _ => Err(format!("Invalid number: '{}'", threshold)),
+        };
+
+        match kind {
+            ConditionKind::PriceAbove => Ok(AlertCondition::PriceAbove(price()?)),
+            ConditionKind::PriceBelow => Ok(AlertCondition::PriceBelow(price()?)),
+            ConditionKind::CrossesAbove => {
+                Ok(AlertCondition::CrossesAbove { level: price()?, hysteresis_percent: hysteresis_percent()? })
+            }
+            ConditionKind::CrossesBelow => {
+                Ok(AlertCondition::CrossesBelow { level: price()?, hysteresis_percent: hysteresis_percent()? })
+            }
+            ConditionKind::PercentChangeAbove => Ok(AlertCondition::PercentChangeAbove(number()?)),
+            ConditionKind::PercentChangeBelow => Ok(AlertCondition::PercentChangeBelow(number()?)),
+            ConditionKind::VolumeSpike => match number()? {
+                volume if volume >= 0.0 => Ok(AlertCondition::VolumeSpike(volume)),
+                _ => Err("Volume can't be negative".to_string()),
+            },
+            ConditionKind::PercentMove => Ok(AlertCondition::PercentMove { percent: change(number())?, window_minutes: window_minutes()? }),
+            ConditionKind::PriceMove => {
+                let amount = threshold.parse::<Decimal>().map_err(|_| format!("Invalid amount: '{}'", threshold))?;
+                if amount.is_zero() {
+                    return Err("The move can't be zero; use a negative value for drops".to_string());
+                }
+                Ok(AlertCondition::PriceMove { amount, window_minutes: window_minutes()? })
+            }
+            ConditionKind::RangeBreakout => Ok(AlertCondition::RangeBreakout { window_minutes: window_minutes()? }),
+            ConditionKind::NewHigh => Ok(AlertCondition::NewHigh { window_minutes: window_minutes()? }),
+            ConditionKind::NewLow => Ok(AlertCondition::NewLow { window_minutes: window_minutes()? }),
+            ConditionKind::RsiAbove | ConditionKind::RsiBelow => {
+                let level = match number()? {
+                    level if (0.0..=100.0).contains(&level) => level,
+                    _ => return Err("RSI levels run from 0 to 100".to_string()),
+                };
+                let period = period_or(14)?;
+                Ok(match kind {
+                    ConditionKind::RsiAbove => AlertCondition::RsiAbove { level, period, timeframe },
+                    _ => AlertCondition::RsiBelow { level, period, timeframe },
+                })
+            }
+            ConditionKind::GoldenCross => averages().map(|(fast, slow)| AlertCondition::GoldenCross { fast, slow, timeframe }),
+            ConditionKind::DeathCross => averages().map(|(fast, slow)| AlertCondition::DeathCross { fast, slow, timeframe }),
+            ConditionKind::AboveUpperBand => Ok(AlertCondition::AboveUpperBand { period: period_or(20)?, std_devs: std_devs()?, timeframe }),
+            ConditionKind::BelowLowerBand => Ok(AlertCondition::BelowLowerBand { period: period_or(20)?, std_devs: std_devs()?, timeframe }),
+            ConditionKind::VolumeAboveAverage => match number()? {
+                multiple if multiple > 0.0 => Ok(AlertCondition::VolumeAboveAverage { multiple, period: period_or(20)?, timeframe }),
+                _ => Err("The volume multiple must be above zero".to_string()),
+            },
+            ConditionKind::Rule => AlertRule::parse(rule).map(AlertCondition::Rule),
+        }
+    }
+}
+
+/// Fields of the alert editor form, in tab order
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AlertField {
+    Symbol,
+    Condition,
+    Rule,        // Only used by rules
+    Threshold,
+    Hysteresis,  // Only used by crossing conditions
+    Window,      // Only used by windowed conditions
+    Period,      // Only used by indicator conditions
+    Timeframe,   // Only used by indicator conditions
+    Cooldown,
+    Mode,
+    Sinks,
+    Message,
+}
+
+impl AlertField {
+    /// Every field in tab order
+    pub const ALL: [AlertField; 12] = [
+        AlertField::Symbol,
+        AlertField::Condition,
+        AlertField::Rule,
+        AlertField::Threshold,
+        AlertField::Hysteresis,
+        AlertField::Window,
+        AlertField::Period,
+        AlertField::Timeframe,
+        AlertField::Cooldown,
+        AlertField::Mode,
+        AlertField::Sinks,
+        AlertField::Message,
+    ];
+}
+
+/// Form for creating an alert or changing an existing one
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertEditor {
+    pub editing: Option<u32>,     // Id of the alert being changed; None for a new alert
+    pub symbols: Vec<String>,     // Symbols to pick from
+    pub symbol_index: usize,
+    pub condition_kind: ConditionKind,
+    pub rule: String,             // Rule text of a rule alert
+    pub threshold: String,
+    pub hysteresis: String,       // Percent band a crossing alert re-arms behind
+    pub window: String,           // Minutes a windowed alert looks back over
+    pub period: String,           // Indicator period; empty for the usual one
+    pub timeframe: TimeFrame,     // Candles an indicator alert is computed from
+    pub cooldown: String,         // Minutes between notifications
+    pub mode: AlertMode,
+    pub sinks: String,            // Comma-separated notification sinks; empty for the defaults
+    pub message: String,
+    pub field: AlertField,        // Field receiving input
+    pub error: Option<String>,    // Why the last save was rejected
+}
+
+impl AlertEditor {
+    pub fn next_field(&mut self) {
+        self.step_field(1);
+    }
+
+    pub fn previous_field(&mut self) {
+        self.step_field(AlertField::ALL.len() - 1);
+    }
+
+    /// Move `offset` fields forward, wrapping around and skipping fields
+    /// the chosen condition doesn't use
+    fn step_field(&mut self, offset: usize) {
+        let fields = AlertField::ALL;
+        let mut index = fields.iter().position(|&f| f == self.field).unwrap_or(0);
+        loop {
+            index = (index + offset) % fields.len();
+            if self.field_applies(fields[index]) {
+                break;
+            }
+        }
+        self.field = fields[index];
+    }
+
+    /// Whether `field` matters for the chosen condition
+    pub fn field_applies(&self, field: AlertField) -> bool {
+        match field {
+            AlertField::Symbol => !self.condition_kind.uses_rule(),
+            AlertField::Rule => self.condition_kind.uses_rule(),
+            AlertField::Threshold => self.condition_kind.uses_threshold(),
+            AlertField::Hysteresis => self.condition_kind.uses_hysteresis(),
+            AlertField::Window => self.condition_kind.uses_window(),
+            AlertField::Period | AlertField::Timeframe => self.condition_kind.uses_indicator(),
+            _ => true,
+        }
+    }
+
+    /// Step through the choices of the symbol, condition, timeframe or mode field
+    pub fn cycle_choice(&mut self, forward: bool) {
+        let step = |index: usize, len: usize| match forward {
+            true => (index + 1) % len,
+            false => (index + len - 1) % len,
+        };
+        match self.field {
+            AlertField::Symbol if !self.symbols.is_empty() => {
+                self.symbol_index = step(self.symbol_index, self.symbols.len());
+            }
+            AlertField::Condition => self.condition_kind = self.condition_kind.step(forward),
+            AlertField::Timeframe => {
+                let index = TimeFrame::ALL.iter().position(|&t| t == self.timeframe).unwrap_or(0);
+                self.timeframe = TimeFrame::ALL[step(index, TimeFrame::ALL.len())];
+            }
+            AlertField::Mode => {
+                self.mode = match self.mode {
+                    AlertMode::Recurring => AlertMode::Once,
+                    AlertMode::Once => AlertMode::Recurring,
+                };
+            }
+            _ => {}
+        }
+    }
+
+    /// The text field receiving typed input, if the active field is one
+    fn text_field(&mut self) -> Option<&mut String> {
+        match self.field {
+            AlertField::Rule => Some(&mut self.rule),
+            AlertField::Threshold => Some(&mut self.threshold),
+            AlertField::Hysteresis => Some(&mut self.hysteresis),
+            AlertField::Window => Some(&mut self.window),
+            AlertField::Period => Some(&mut self.period),
+            AlertField::Cooldown => Some(&mut self.cooldown),
+            AlertField::Sinks => Some(&mut self.sinks),
+            AlertField::Message => Some(&mut self.message),
+            AlertField::Symbol | AlertField::Condition | AlertField::Timeframe | AlertField::Mode => None,
+        }
+    }
+
+    /// Type into the active text field
+    pub fn input(&mut self, c: char) {
+        if let Some(text) = self.text_field() {
+            text.push(c);
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        if let Some(text) = self.text_field() {
+            text.pop();
+        }
+    }
+
+    /// The typed fields a condition is built from
+    fn condition_fields(&self) -> ConditionFields<'_> {
+        ConditionFields {
+            threshold: &self.threshold,
+            hysteresis: &self.hysteresis,
+            window: &self.window,
+            period: &self.period,
+            timeframe: self.timeframe,
+            rule: &self.rule,
+        }
+    }
+
+    /// Sink names typed into the notify field
+    fn sink_names(&self) -> Vec<String> {
+        self.sinks
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Minutes typed into the cooldown field
+    fn cooldown_minutes(&self) -> Result<u32, String> {
+        self.cooldown
+            .trim()
+            .parse()
+            .map_err(|_| format!("Invalid cooldown: '{}'. Use whole minutes", self.cooldown.trim()))
+    }
+
+    pub fn selected_symbol(&self) -> Option<&str> {
+        self.symbols.get(self.symbol_index).map(String::as_str)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -173,6 +892,8 @@ pub struct DataStatus {
     pub last_successful_sync: Option<DateTime<Utc>>,
     pub offline_mode: bool,
     pub consecutive_failures: u32,
+    pub streaming: bool,                 // Live ticker stream connected
+    pub throttled_until: Option<DateTime<Utc>>, // Requests held back by exchange rate limits until then
 }
 
 pub struct App {
@@ -186,6 +907,7 @@ pub struct App {
     pub config: Config,
     pub selected_candles: Vec<Candle>,
     pub selected_symbol_candles: String, // Track which symbol's candles we have
+    pub chart_loading: Option<String>,   // Symbol whose chart candles are loading in the background
     pub data_status: DataStatus,         // Track data freshness and offline status
     pub show_help: bool,                 // Show help overlay
     pub search_mode: bool,               // Interactive search mode
@@ -193,11 +915,23 @@ pub struct App {
     pub show_alert_management: bool,     // Show alert management screen
     pub errors: Vec<AppError>,           // Active application errors
     pub alerts: Vec<PriceAlert>,         // Price alerts
+    pub imported_rules: HashSet<String>, // Config file alert rules already turned into alerts
     pub recent_alerts: Vec<(String, DateTime<Utc>)>, // Recently triggered alerts (message, timestamp)
+    pub alert_triggers: Vec<AlertTrigger>, // Triggers not yet written to alert history
+    pub selected_alert: usize,           // Highlighted row of the alert manager
+    pub alert_editor: Option<AlertEditor>, // Open create/edit form in the alert manager
+    pub price_history: HashMap<String, Vec<PricePoint>>, // Recent prices per symbol, oldest first, for windowed alerts
+    pub indicator_candles: HashMap<(String, TimeFrame), Vec<Candle>>, // Latest candles per symbol and timeframe for indicator alerts
+    pub indicator_loaded: HashMap<(String, TimeFrame), (DateTime<Utc>, usize)>, // When each indicator series was last loaded, and how many candles
+    pub indicator_loading: HashSet<(String, TimeFrame)>, // Indicator series being loaded in the background
+    pub symbol_registry: SymbolRegistry, // Exchange metadata (assets, tick sizes), when loaded
+    pub chart_source: ChartSource,       // Where the detail chart's candles come from
+    pub status_message: Option<(String, DateTime<Utc>)>, // Outcome of the last user action, e.g. an export
 }
 
 impl App {
     pub fn new(config: Config) -> Self {
+        let chart_source = config.chart.source;
         Self {
             all_price_infos: Vec::new(),
             price_infos: Vec::new(),
@@ -209,11 +943,14 @@ impl App {
             config,
             selected_candles: Vec::new(),
             selected_symbol_candles: String::new(),
+            chart_loading: None,
             data_status: DataStatus {
                 last_price_update: None,
                 last_successful_sync: None,
                 offline_mode: false,
                 consecutive_failures: 0,
+                streaming: false,
+                throttled_until: None,
             },
             show_help: false,
             search_mode: false,
@@ -221,7 +958,18 @@ impl App {
             show_alert_management: false,
             errors: Vec::new(),
             alerts: Vec::new(),
+            imported_rules: HashSet::new(),
             recent_alerts: Vec::new(),
+            alert_triggers: Vec::new(),
+            selected_alert: 0,
+            alert_editor: None,
+            price_history: HashMap::new(),
+            indicator_candles: HashMap::new(),
+            indicator_loaded: HashMap::new(),
+            indicator_loading: HashSet::new(),
+            symbol_registry: SymbolRegistry::default(),
+            chart_source,
+            status_message: None,
         }
     }
 
@@ -229,8 +977,10 @@ impl App {
         // Store all price data
         self.all_price_infos = price_infos;
 
-        // Check alerts against new price data
+        // Check alerts against new price data, then remember the prices
+        // for windowed alerts
         self.check_alerts();
+        self.record_price_history(Utc::now());
 
         // Apply filters and sorting
         self.apply_filters_and_sorting();
@@ -241,6 +991,61 @@ impl App {
         }
     }
 
+    /// Fetch fresh prices for the configured symbols from a market data source.
+    ///
+    /// Fetched prices are merged in and returned so the caller can persist
+    /// them. Symbols that failed keep their last known price and are reported
+    /// in the error list; if the request fails as a whole the sync failure is
+    /// recorded and the error returned. Being throttled by rate limits is
+    /// reported through `DataStatus` rather than counted as a failure, since
+    /// the exchange itself is reachable.
+    pub async fn refresh_prices<S: MarketDataSource>(&mut self, source: &S) -> Result<Vec<PriceInfo>, CoinPeekError> {
+        let symbols: Vec<&str> = self.config.symbols.iter().map(|s| s.as_str()).collect();
+
+        let result = source.fetch_price_infos(&symbols).await;
+        self.set_throttled_until(source.throttled_until());
+
+        match result {
+            Ok(results) => {
+                let mut price_infos = Vec::with_capacity(results.len());
+                for SymbolResult { symbol, result } in results {
+                    match result {
+                        Ok(price_info) => price_infos.push(price_info),
+                        Err(e) => self.report_error(format!("Failed to fetch {}", symbol), &e),
+                    }
+                }
+
+                if price_infos.is_empty() {
+                    self.record_sync_failure();
+                } else {
+                    self.record_successful_sync();
+                    self.apply_price_updates(price_infos.clone());
+                }
+                Ok(price_infos)
+            }
+            Err(e) => {
+                if !self.is_throttled() {
+                    self.record_sync_failure();
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Merge partial price updates (e.g. from a ticker stream) into the current data
+    pub fn apply_price_updates(&mut self, updates: Vec<PriceInfo>) {
+        let mut merged = self.all_price_infos.clone();
+
+        for update in updates {
+            match merged.iter_mut().find(|p| p.symbol == update.symbol) {
+                Some(existing) => *existing = update,
+                None => merged.push(update),
+            }
+        }
+
+        self.update_prices(merged);
+    }
+
     pub fn apply_filters_and_sorting(&mut self) {
         let mut filtered = self.all_price_infos.clone();
 
@@ -330,10 +1135,10 @@ impl App {
                 price_infos.sort_by(|a, b| b.symbol.cmp(&a.symbol));
             }
             (SortMode::Price, SortDirection::Ascending) => {
-                price_infos.sort_by(|a, b| a.price.partial_cmp(&b.price).unwrap());
+                price_infos.sort_by_key(|p| p.price);
             }
             (SortMode::Price, SortDirection::Descending) => {
-                price_infos.sort_by(|a, b| b.price.partial_cmp(&a.price).unwrap());
+                price_infos.sort_by_key(|p| std::cmp::Reverse(p.price));
             }
             (SortMode::ChangePercent, SortDirection::Ascending) => {
                 price_infos.sort_by(|a, b| a.price_change_percent.partial_cmp(&b.price_change_percent).unwrap());
@@ -390,6 +1195,27 @@ impl App {
         self.paused = !self.paused;
     }
 
+    /// Switch the detail chart to the next candle source and reload it
+    pub fn next_chart_source(&mut self) {
+        self.chart_source = self.chart_source.next();
+        self.selected_candles.clear();
+        self.selected_symbol_candles.clear();
+        self.chart_loading = None;
+    }
+
+    /// Show a short confirmation in the footer
+    pub fn set_status_message(&mut self, message: String) {
+        self.status_message = Some((message, Utc::now()));
+    }
+
+    /// The footer message, while it is recent enough to show
+    pub fn current_status_message(&self) -> Option<&str> {
+        self.status_message
+            .as_ref()
+            .filter(|(_, at)| Utc::now() - *at < chrono::Duration::seconds(STATUS_MESSAGE_SECONDS))
+            .map(|(message, _)| message.as_str())
+    }
+
     pub fn toggle_help(&mut self) {
         self.show_help = !self.show_help;
     }
@@ -448,13 +1274,25 @@ impl App {
         }
     }
 
+    /// The selected symbol if its chart hasn't been loaded and isn't loading.
+    /// A load that found no candles isn't repeated here; the main loop
+    /// retries empty charts at the refresh interval.
     pub fn should_fetch_candles(&self) -> Option<String> {
-        if let Some(selected) = self.get_selected_symbol() {
-            if self.selected_symbol_candles != selected.symbol || self.selected_candles.is_empty() {
-                return Some(selected.symbol.clone());
-            }
+        let selected = self.get_selected_symbol()?;
+        let loading = self.chart_loading.as_deref() == Some(selected.symbol.as_str());
+        (self.selected_symbol_candles != selected.symbol && !loading).then(|| selected.symbol.clone())
+    }
+
+    /// Show the candles a background chart load for `symbol` finished with,
+    /// unless the chart has moved on to another symbol or source since
+    pub fn finish_chart_load(&mut self, symbol: &str, candles: Vec<Candle>) {
+        if self.chart_loading.as_deref() != Some(symbol) {
+            return;
+        }
+        self.chart_loading = None;
+        if self.get_selected_symbol().is_some_and(|selected| selected.symbol == symbol) {
+            self.update_candles_for_selected(candles);
         }
-        None
     }
 
     // Filter and preset management methods
@@ -535,6 +1373,16 @@ impl App {
         }
     }
 
+    /// Format a price for display using the symbol's tick size when known
+    pub fn format_price(&self, symbol: &str, price: Decimal) -> String {
+        crate::utils::format_price(price, self.symbol_registry.tick_size(symbol))
+    }
+
+    /// Use freshly loaded exchange metadata for formatting and the details panel
+    pub fn set_symbol_registry(&mut self, registry: SymbolRegistry) {
+        self.symbol_registry = registry;
+    }
+
     pub fn get_visible_count(&self) -> (usize, usize) {
         (self.price_infos.len(), self.all_price_infos.len())
     }
@@ -554,6 +1402,25 @@ impl App {
         }
     }
 
+    pub fn set_stream_connected(&mut self, connected: bool) {
+        self.data_status.streaming = connected;
+    }
+
+    /// Update the throttled state, raising an API warning when throttling starts
+    pub fn set_throttled_until(&mut self, until: Option<DateTime<Utc>>) {
+        if let (None, Some(retry_at)) = (self.data_status.throttled_until, until) {
+            self.add_api_error(
+                "Requests throttled by exchange rate limits".to_string(),
+                Some(format!("Resuming after {} UTC", retry_at.format("%H:%M:%S"))),
+            );
+        }
+        self.data_status.throttled_until = until;
+    }
+
+    pub fn is_throttled(&self) -> bool {
+        self.data_status.throttled_until.is_some_and(|until| until > Utc::now())
+    }
+
     pub fn toggle_offline_mode(&mut self) {
         self.data_status.offline_mode = !self.data_status.offline_mode;
         if !self.data_status.offline_mode {
@@ -584,8 +1451,13 @@ impl App {
     pub fn get_offline_indicator(&self) -> String {
         if self.data_status.offline_mode {
             "🔴 OFFLINE".to_string()
+        } else if let Some(until) = self.data_status.throttled_until.filter(|_| self.is_throttled()) {
+            let seconds = until.signed_duration_since(Utc::now()).num_seconds().max(1);
+            format!("🟡 throttled {}s", seconds)
         } else if self.data_status.consecutive_failures > 0 {
             format!("🟡 {} failures", self.data_status.consecutive_failures)
+        } else if self.data_status.streaming {
+            format!("🟢 live {}", self.get_data_age_string())
         } else {
             format!("🟢 synced {}", self.get_data_age_string())
         }
@@ -667,6 +1539,26 @@ impl App {
         // Note: Actual retry logic would be implemented in the main loop
     }
 
+    /// Record a data-layer error under the category and severity it maps to.
+    /// While the same problem is still unresolved it bumps the retry count
+    /// instead of adding a duplicate entry on every refresh.
+    pub fn report_error(&mut self, message: String, error: &CoinPeekError) {
+        if let Some(existing) = self.errors.iter_mut().find(|e| !e.resolved && e.message == message) {
+            existing.retry_count += 1;
+            existing.details = Some(error.to_string());
+            existing.timestamp = Utc::now();
+            return;
+        }
+
+        self.add_error(
+            error_type(error),
+            error_severity(error),
+            message,
+            Some(error.to_string()),
+            Some(error.recovery_suggestion()),
+        );
+    }
+
     // Convenience methods for common error types
     pub fn add_network_error(&mut self, message: String, details: Option<String>) {
         self.add_error(
@@ -709,8 +1601,131 @@ impl App {
     }
 
     // Alert management methods
+
+    /// Replace the alerts with ones saved earlier
+    pub fn load_alerts(&mut self, alerts: Vec<PriceAlert>) {
+        self.alerts = alerts;
+    }
+
+    /// Config file rules that earlier sessions already turned into alerts
+    pub fn load_imported_rules(&mut self, rules: Vec<String>) {
+        self.imported_rules = rules.into_iter().collect();
+    }
+
+    /// Create alerts for the config file rules not imported before, returning
+    /// each newly imported rule with the id of the alert it created so both
+    /// can be saved. Rules are only imported once: deleting or editing the
+    /// alert afterwards sticks. A rule matching an existing alert (saved
+    /// before imports were recorded) is marked imported without a new alert.
+    pub fn add_configured_alerts(&mut self) -> Vec<(String, Option<u32>)> {
+        let mut imported = Vec::new();
+        for configured in self.config.alerts.clone() {
+            let key = configured.rule.trim().to_string();
+            if self.imported_rules.contains(&key) {
+                continue;
+            }
+            let Ok(rule) = AlertRule::parse(&configured.rule) else {
+                continue;
+            };
+            let condition = AlertCondition::Rule(rule.clone());
+            self.imported_rules.insert(key.clone());
+            if self.alerts.iter().any(|alert| alert.condition == condition) {
+                imported.push((key, None));
+                continue;
+            }
+
+            let id = self.create_alert(rule.symbols()[0].to_string(), condition, configured.message);
+            let mode = if configured.once { AlertMode::Once } else { AlertMode::Recurring };
+            self.set_alert_schedule(id, configured.cooldown_minutes, mode);
+            self.set_alert_sinks(id, configured.sinks);
+            imported.push((key, Some(id)));
+        }
+        imported
+    }
+
+    /// Seed a symbol's recent prices (oldest first), e.g. from the database at
+    /// startup, so windowed alerts don't have to wait for their window to fill
+    pub fn load_price_history(&mut self, symbol: &str, points: Vec<PricePoint>) {
+        self.price_history.insert(symbol.to_string(), points);
+    }
+
+    /// Candles the enabled indicator alerts need, as `(symbol, timeframe,
+    /// count)` with one entry per symbol and timeframe
+    pub fn indicator_series(&self) -> Vec<(String, TimeFrame, usize)> {
+        let mut series: Vec<(String, TimeFrame, usize)> = Vec::new();
+        for alert in self.alerts.iter().filter(|a| a.enabled) {
+            let Some(timeframe) = alert.condition.indicator_timeframe() else {
+                continue;
+            };
+            let count = alert.condition.candles_needed();
+            match series.iter_mut().find(|(s, t, _)| *s == alert.symbol && *t == timeframe) {
+                Some(entry) => entry.2 = entry.2.max(count),
+                None => series.push((alert.symbol.clone(), timeframe, count)),
+            }
+        }
+        series
+    }
+
+    /// The indicator series due a reload, which are marked as loading from
+    /// `now` until `finish_indicator_load`: new series, ones that need more
+    /// candles than were loaded, and the rest once their bar has moved on or
+    /// a minute has passed, so the exchange isn't asked again on every price
+    /// refresh. Series still loading are never due.
+    pub fn take_due_indicator_series(&mut self, now: DateTime<Utc>) -> Vec<(String, TimeFrame, usize)> {
+        let now_ms = now.timestamp_millis().max(0) as u64;
+        let due: Vec<(String, TimeFrame, usize)> = self
+            .indicator_series()
+            .into_iter()
+            .filter(|(symbol, timeframe, _)| !self.indicator_loading.contains(&(symbol.clone(), *timeframe)))
+            .filter(|(symbol, timeframe, count)| match self.indicator_loaded.get(&(symbol.clone(), *timeframe)) {
+                None => true,
+                Some(&(loaded_at, loaded_count)) => {
+                    let loaded_ms = loaded_at.timestamp_millis().max(0) as u64;
+                    *count > loaded_count
+                        || timeframe.bar_start(now_ms) != timeframe.bar_start(loaded_ms)
+                        || now - loaded_at >= chrono::Duration::seconds(INDICATOR_REFRESH_SECONDS)
+                }
+            })
+            .collect();
+        for (symbol, timeframe, count) in &due {
+            self.indicator_loaded.insert((symbol.clone(), *timeframe), (now, *count));
+            self.indicator_loading.insert((symbol.clone(), *timeframe));
+        }
+        due
+    }
+
+    /// A background load of an indicator series is over, with the candles
+    /// it found if the database could be read
+    pub fn finish_indicator_load(&mut self, symbol: &str, timeframe: TimeFrame, candles: Option<Vec<Candle>>) {
+        self.indicator_loading.remove(&(symbol.to_string(), timeframe));
+        if let Some(candles) = candles {
+            self.update_indicator_candles(symbol, timeframe, candles);
+        }
+    }
+
+    /// Replace the candles (oldest first) indicator alerts use for a symbol and timeframe
+    pub fn update_indicator_candles(&mut self, symbol: &str, timeframe: TimeFrame, candles: Vec<Candle>) {
+        self.indicator_candles.insert((symbol.to_string(), timeframe), candles);
+    }
+
+    /// Remember the current prices for windowed alerts. A price is only added
+    /// when it changed, and prices older than the longest window are dropped
+    /// except the one still in effect at its start.
+    pub fn record_price_history(&mut self, now: DateTime<Utc>) {
+        let cutoff = now - chrono::Duration::minutes(MAX_ALERT_WINDOW_MINUTES as i64);
+        for price_info in &self.all_price_infos {
+            let points = self.price_history.entry(price_info.symbol.clone()).or_default();
+            if points.last().is_none_or(|p| p.price != price_info.price) {
+                points.push(PricePoint { timestamp: now, price: price_info.price });
+            }
+            let in_effect = points.iter().rposition(|p| p.timestamp <= cutoff).unwrap_or(0);
+            points.drain(..in_effect);
+        }
+    }
+
     pub fn create_alert(&mut self, symbol: String, condition: AlertCondition, message: Option<String>) -> u32 {
-        let id = self.alerts.len() as u32 + 1;
+        // Ids stay unique after deletes, since saved alerts are keyed by them
+        let id = self.alerts.iter().map(|a| a.id).max().unwrap_or(0) + 1;
         let alert = PriceAlert {
             id,
             symbol,
@@ -720,6 +1735,10 @@ impl App {
             last_triggered: None,
             trigger_count: 0,
             message,
+            cooldown_minutes: DEFAULT_ALERT_COOLDOWN_MINUTES,
+            mode: AlertMode::Recurring,
+            armed: None,
+            sinks: Vec::new(),
         };
         self.alerts.push(alert);
         id
@@ -728,83 +1747,379 @@ impl App {
     pub fn delete_alert(&mut self, id: u32) -> bool {
         let initial_len = self.alerts.len();
         self.alerts.retain(|alert| alert.id != id);
+        self.selected_alert = self.selected_alert.min(self.alerts.len().saturating_sub(1));
         self.alerts.len() < initial_len
     }
 
+    /// Change an alert's symbol, condition and message, keeping its history
+    pub fn update_alert(&mut self, id: u32, symbol: String, condition: AlertCondition, message: Option<String>) -> bool {
+        if let Some(alert) = self.alerts.iter_mut().find(|a| a.id == id) {
+            alert.symbol = symbol;
+            alert.condition = condition;
+            alert.message = message;
+            alert.armed = None;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Set how often an alert may fire and whether it switches off afterwards
+    pub fn set_alert_schedule(&mut self, id: u32, cooldown_minutes: u32, mode: AlertMode) -> bool {
+        if let Some(alert) = self.alerts.iter_mut().find(|a| a.id == id) {
+            alert.cooldown_minutes = cooldown_minutes;
+            alert.mode = mode;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Choose the notification sinks an alert is sent to; empty for the defaults
+    pub fn set_alert_sinks(&mut self, id: u32, sinks: Vec<String>) -> bool {
+        if let Some(alert) = self.alerts.iter_mut().find(|a| a.id == id) {
+            alert.sinks = sinks;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn select_next_alert(&mut self) {
+        if !self.alerts.is_empty() {
+            self.selected_alert = (self.selected_alert + 1) % self.alerts.len();
+        }
+    }
+
+    pub fn select_previous_alert(&mut self) {
+        if !self.alerts.is_empty() {
+            self.selected_alert = (self.selected_alert + self.alerts.len() - 1) % self.alerts.len();
+        }
+    }
+
+    /// The alert highlighted in the alert manager
+    pub fn get_selected_alert(&self) -> Option<&PriceAlert> {
+        self.alerts.get(self.selected_alert)
+    }
+
+    /// Open the editor for a new alert on the selected dashboard symbol
+    pub fn open_new_alert_editor(&mut self) {
+        let symbols = self.config.symbols.clone();
+        let selected = self.get_selected_symbol().map(|p| p.symbol.clone());
+        self.alert_editor = Some(AlertEditor {
+            editing: None,
+            symbol_index: selected.and_then(|s| symbols.iter().position(|c| *c == s)).unwrap_or(0),
+            symbols,
+            condition_kind: ConditionKind::PriceAbove,
+            rule: String::new(),
+            threshold: String::new(),
+            hysteresis: String::new(),
+            window: DEFAULT_ALERT_WINDOW_MINUTES.to_string(),
+            period: String::new(),
+            timeframe: DEFAULT_INDICATOR_TIMEFRAME,
+            cooldown: DEFAULT_ALERT_COOLDOWN_MINUTES.to_string(),
+            mode: AlertMode::Recurring,
+            sinks: String::new(),
+            message: String::new(),
+            field: AlertField::Symbol,
+            error: None,
+        });
+    }
+
+    /// Open the editor on the highlighted alert
+    pub fn open_alert_editor(&mut self) {
+        let Some(alert) = self.get_selected_alert().cloned() else {
+            return;
+        };
+        let mut symbols = self.config.symbols.clone();
+        if !symbols.contains(&alert.symbol) {
+            symbols.push(alert.symbol.clone());
+        }
+        let mut editor = AlertEditor {
+            editing: Some(alert.id),
+            symbol_index: symbols.iter().position(|s| *s == alert.symbol).unwrap_or(0),
+            symbols,
+            condition_kind: alert.condition.kind(),
+            rule: alert.condition.rule_text(),
+            threshold: alert.condition.threshold_text(),
+            hysteresis: alert.condition.hysteresis_text(),
+            window: alert.condition.window_minutes().unwrap_or(DEFAULT_ALERT_WINDOW_MINUTES).to_string(),
+            period: alert.condition.period_text(),
+            timeframe: alert.condition.indicator_timeframe().unwrap_or(DEFAULT_INDICATOR_TIMEFRAME),
+            cooldown: alert.cooldown_minutes.to_string(),
+            mode: alert.mode,
+            sinks: alert.sinks.join(", "),
+            message: alert.message.unwrap_or_default(),
+            field: AlertField::Threshold,
+            error: None,
+        };
+        // Start on the first setting the condition uses
+        editor.field = [AlertField::Rule, AlertField::Threshold, AlertField::Window, AlertField::Period]
+            .into_iter()
+            .find(|&field| editor.field_applies(field))
+            .unwrap_or(AlertField::Threshold);
+        self.alert_editor = Some(editor);
+    }
+
+    pub fn close_alert_editor(&mut self) {
+        self.alert_editor = None;
+    }
+
+    /// Create or update the alert described by the editor and close it,
+    /// returning the alert's id. An invalid form stays open with its error.
+    pub fn submit_alert_editor(&mut self) -> Option<u32> {
+        let editor = self.alert_editor.as_mut()?;
+        let result = AlertCondition::from_kind(editor.condition_kind, &editor.condition_fields()).and_then(|condition| {
+            // A rule alert is listed under the first symbol its rule reads
+            let symbol = match &condition {
+                AlertCondition::Rule(rule) => {
+                    rule.check_symbols(&self.config.symbols)?;
+                    rule.symbols()[0].to_string()
+                }
+                _ => editor.selected_symbol().ok_or("No symbols configured")?.to_string(),
+            };
+            let sinks = editor.sink_names();
+            self.config.notifications.check_sinks(&sinks)?;
+            Ok((symbol, condition, editor.cooldown_minutes()?, sinks))
+        });
+        let (symbol, condition, cooldown_minutes, sinks) = match result {
+            Ok(fields) => fields,
+            Err(e) => {
+                editor.error = Some(e);
+                return None;
+            }
+        };
+
+        let editor = self.alert_editor.take()?;
+        let message = Some(editor.message.trim().to_string()).filter(|m| !m.is_empty());
+        let id = match editor.editing {
+            Some(id) => self.update_alert(id, symbol, condition, message).then_some(id)?,
+            None => {
+                let id = self.create_alert(symbol, condition, message);
+                self.selected_alert = self.alerts.len() - 1;
+                id
+            }
+        };
+        self.set_alert_schedule(id, cooldown_minutes, editor.mode);
+        self.set_alert_sinks(id, sinks);
+        Some(id)
+    }
+
     pub fn toggle_alert(&mut self, id: u32) -> bool {
         if let Some(alert) = self.alerts.iter_mut().find(|a| a.id == id) {
             alert.enabled = !alert.enabled;
+            // Where the price stood while it was off doesn't count
+            alert.armed = None;
             true
         } else {
             false
         }
     }
 
+    /// Evaluate the enabled alerts against the latest prices. Level
+    /// conditions fire while they hold, crossings once per crossing; either
+    /// way an alert stays quiet for its cooldown after firing, and a
+    /// one-shot alert switches itself off.
     pub fn check_alerts(&mut self) {
+        let now = Utc::now();
+        let now_ms = now.timestamp_millis().max(0) as u64;
         for alert in &mut self.alerts {
             if !alert.enabled {
                 continue;
             }
 
             // Find the price info for this symbol
-            if let Some(price_info) = self.all_price_infos.iter().find(|p| p.symbol == alert.symbol) {
-                let should_trigger = match &alert.condition {
-                    AlertCondition::PriceAbove(threshold) => price_info.price > *threshold,
-                    AlertCondition::PriceBelow(threshold) => price_info.price < *threshold,
-                    AlertCondition::PercentChangeAbove(threshold) => price_info.price_change_percent > *threshold,
-                    AlertCondition::PercentChangeBelow(threshold) => price_info.price_change_percent < *threshold,
-                    AlertCondition::VolumeSpike(threshold) => price_info.volume > *threshold,
-                };
-
-                if should_trigger {
-                    // Check if we've already triggered this alert recently (avoid spam)
-                    let should_notify = match alert.last_triggered {
-                        Some(last_trigger) => {
-                            let now = Utc::now();
-                            let duration = now.signed_duration_since(last_trigger);
-                            // Only trigger once per hour for the same alert
-                            duration.num_hours() >= 1
-                        }
-                        None => true, // Never triggered before
-                    };
-
-                    if should_notify {
-                        alert.last_triggered = Some(Utc::now());
-                        alert.trigger_count += 1;
-
-                        // Create notification message
-                        let message = alert.message.clone().unwrap_or_else(|| {
-                            match &alert.condition {
-                                AlertCondition::PriceAbove(threshold) => {
-                                    format!("{} price above ${:.2} (currently ${:.2})", alert.symbol, threshold, price_info.price)
-                                }
-                                AlertCondition::PriceBelow(threshold) => {
-                                    format!("{} price below ${:.2} (currently ${:.2})", alert.symbol, threshold, price_info.price)
-                                }
-                                AlertCondition::PercentChangeAbove(threshold) => {
-                                    format!("{} up {:.1}% (currently {:.2}%)", alert.symbol, threshold, price_info.price_change_percent)
-                                }
-                                AlertCondition::PercentChangeBelow(threshold) => {
-                                    format!("{} down {:.1}% (currently {:.2}%)", alert.symbol, threshold, price_info.price_change_percent)
-                                }
-                                AlertCondition::VolumeSpike(threshold) => {
-                                    format!("{} volume spike: {:.0} (threshold: {:.0})", alert.symbol, price_info.volume, threshold)
-                                }
-                            }
-                        });
-
-                        // Terminal bell notification
-                        print!("\x07"); // ASCII bell character
-
-                        // Add to recent alerts for notification
-                        self.recent_alerts.push((format!("🔔 {}", message), Utc::now()));
-
-                        // Keep only the last 10 recent alerts
-                        if self.recent_alerts.len() > 10 {
-                            self.recent_alerts.remove(0);
+            let Some(price_info) = self.all_price_infos.iter().find(|p| p.symbol == alert.symbol) else {
+                continue;
+            };
+            let price = price_info.price;
+            // Low and high of the prices before this one over a windowed
+            // condition's window. Breakouts and new extremes need the whole
+            // window on record, or the first few prices would count as one.
+            let window_range = alert.condition.window_minutes().and_then(|minutes| {
+                let points = self.price_history.get(&alert.symbol).map(Vec::as_slice).unwrap_or_default();
+                let start = now - chrono::Duration::minutes(minutes as i64);
+                let needs_full_window = matches!(
+                    alert.condition,
+                    AlertCondition::RangeBreakout { .. } | AlertCondition::NewHigh { .. } | AlertCondition::NewLow { .. }
+                );
+                if needs_full_window && points.first().is_none_or(|p| p.timestamp > start) {
+                    return None;
+                }
+                history::window_range(points, start)
+            });
+            // Latest candles of an indicator condition's timeframe
+            let candles = alert
+                .condition
+                .indicator_timeframe()
+                .and_then(|timeframe| self.indicator_candles.get(&(alert.symbol.clone(), timeframe)))
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            // A bar still forming has only part of its volume, so volume
+            // alerts compare finished bars only
+            let closed_candles = match (alert.condition.indicator_timeframe(), candles.split_last()) {
+                (Some(timeframe), Some((last, earlier))) if last.timestamp + timeframe.duration_ms() > now_ms => earlier,
+                _ => candles,
+            };
+            let band = |level: Decimal, percent: f64| level * Decimal::from_f64(percent).unwrap_or_default() / Decimal::ONE_HUNDRED;
+
+            let should_trigger = match alert.condition.clone() {
+                AlertCondition::PriceAbove(threshold) => price > threshold,
+                AlertCondition::PriceBelow(threshold) => price < threshold,
+                AlertCondition::PercentChangeAbove(threshold) => price_info.price_change_percent > threshold,
+                AlertCondition::PercentChangeBelow(threshold) => price_info.price_change_percent < threshold,
+                AlertCondition::VolumeSpike(threshold) => price_info.volume > threshold,
+                AlertCondition::CrossesAbove { level, hysteresis_percent } => {
+                    let rearm = price < level && price <= level - band(level, hysteresis_percent);
+                    alert.cross(price >= level, rearm)
+                }
+                AlertCondition::CrossesBelow { level, hysteresis_percent } => {
+                    let rearm = price > level && price >= level + band(level, hysteresis_percent);
+                    alert.cross(price <= level, rearm)
+                }
+                AlertCondition::PercentMove { percent, .. } => window_range.is_some_and(|(low, high)| {
+                    let change = Decimal::from_f64(percent).unwrap_or_default() / Decimal::ONE_HUNDRED;
+                    if percent > 0.0 { price >= low + low * change } else { price <= high + high * change }
+                }),
+                AlertCondition::PriceMove { amount, .. } => window_range.is_some_and(|(low, high)| {
+                    if amount.is_sign_positive() { price - low >= amount } else { price - high <= amount }
+                }),
+                AlertCondition::RangeBreakout { .. } => window_range.is_some_and(|(low, high)| price < low || price > high),
+                AlertCondition::NewHigh { .. } => window_range.is_some_and(|(_, high)| price > high),
+                AlertCondition::NewLow { .. } => window_range.is_some_and(|(low, _)| price < low),
+                AlertCondition::RsiAbove { level, period, .. } => {
+                    indicators::rsi(candles, period as usize).is_some_and(|rsi| rsi > level)
+                }
+                AlertCondition::RsiBelow { level, period, .. } => {
+                    indicators::rsi(candles, period as usize).is_some_and(|rsi| rsi < level)
+                }
+                AlertCondition::GoldenCross { fast, slow, .. } | AlertCondition::DeathCross { fast, slow, .. } => {
+                    match (indicators::sma(candles, fast as usize), indicators::sma(candles, slow as usize)) {
+                        (Some(fast), Some(slow)) if matches!(alert.condition, AlertCondition::GoldenCross { .. }) => {
+                            alert.cross(fast > slow, fast < slow)
                         }
+                        (Some(fast), Some(slow)) => alert.cross(fast < slow, fast > slow),
+                        _ => false,
+                    }
+                }
+                AlertCondition::AboveUpperBand { period, std_devs, .. } => {
+                    match indicators::bollinger(candles, period as usize, std_devs) {
+                        Some(bands) => alert.cross(price > bands.upper, price <= bands.upper),
+                        None => false,
+                    }
+                }
+                AlertCondition::BelowLowerBand { period, std_devs, .. } => {
+                    match indicators::bollinger(candles, period as usize, std_devs) {
+                        Some(bands) => alert.cross(price < bands.lower, price >= bands.lower),
+                        None => false,
+                    }
+                }
+                AlertCondition::VolumeAboveAverage { multiple, period, .. } => {
+                    indicators::volume_ratio(closed_candles, period as usize).is_some_and(|ratio| ratio >= multiple)
+                }
+                AlertCondition::Rule(rule) => rule.holds(&self.all_price_infos),
+            };
+
+            // A crossing during the cooldown is dropped rather than reported
+            // late, when the price may have long moved on
+            let cooled_down = alert.last_triggered.is_none_or(|last| {
+                now.signed_duration_since(last) >= chrono::Duration::minutes(alert.cooldown_minutes as i64)
+            });
+            if !should_trigger || !cooled_down {
+                continue;
+            }
+
+            alert.last_triggered = Some(now);
+            alert.trigger_count += 1;
+            if alert.mode == AlertMode::Once {
+                alert.enabled = false;
+            }
+
+            // Create notification message
+            let message = alert.message.clone().unwrap_or_else(|| {
+                match &alert.condition {
+                    AlertCondition::PriceAbove(threshold) => {
+                        format!("{} price above ${} (currently ${})", alert.symbol, threshold, price)
+                    }
+                    AlertCondition::PriceBelow(threshold) => {
+                        format!("{} price below ${} (currently ${})", alert.symbol, threshold, price)
+                    }
+                    AlertCondition::CrossesAbove { level, .. } => {
+                        format!("{} crossed above ${} (currently ${})", alert.symbol, level, price)
+                    }
+                    AlertCondition::CrossesBelow { level, .. } => {
+                        format!("{} crossed below ${} (currently ${})", alert.symbol, level, price)
+                    }
+                    AlertCondition::PercentChangeAbove(threshold) => {
+                        format!("{} up {:.1}% (currently {:.2}%)", alert.symbol, threshold, price_info.price_change_percent)
+                    }
+                    AlertCondition::PercentChangeBelow(threshold) => {
+                        format!("{} down {:.1}% (currently {:.2}%)", alert.symbol, threshold, price_info.price_change_percent)
+                    }
+                    AlertCondition::VolumeSpike(threshold) => {
+                        format!("{} volume spike: {:.0} (threshold: {:.0})", alert.symbol, price_info.volume, threshold)
+                    }
+                    AlertCondition::PercentMove { percent, window_minutes } => {
+                        let direction = if *percent > 0.0 { "up" } else { "down" };
+                        format!("{} {} {:.1}% in {}m (currently ${})", alert.symbol, direction, percent.abs(), window_minutes, price)
+                    }
+                    AlertCondition::PriceMove { amount, window_minutes } => {
+                        let direction = if amount.is_sign_positive() { "up" } else { "down" };
+                        format!("{} {} ${} in {}m (currently ${})", alert.symbol, direction, amount.abs(), window_minutes, price)
+                    }
+                    AlertCondition::RangeBreakout { window_minutes } => {
+                        let (low, high) = window_range.unwrap_or_default();
+                        format!("{} broke out of its {}m range ${}-${} (currently ${})", alert.symbol, window_minutes, low, high, price)
                     }
+                    AlertCondition::NewHigh { window_minutes } => {
+                        format!("{} at a new {}m high (currently ${})", alert.symbol, window_minutes, price)
+                    }
+                    AlertCondition::NewLow { window_minutes } => {
+                        format!("{} at a new {}m low (currently ${})", alert.symbol, window_minutes, price)
+                    }
+                    AlertCondition::RsiAbove { level, period, timeframe }
+                    | AlertCondition::RsiBelow { level, period, timeframe } => {
+                        let direction = if matches!(alert.condition, AlertCondition::RsiAbove { .. }) { "above" } else { "below" };
+                        let rsi = indicators::rsi(candles, *period as usize).unwrap_or_default();
+                        format!("{} {} RSI({}) {} {} (currently {:.1})", alert.symbol, timeframe.as_str(), period, direction, level, rsi)
+                    }
+                    AlertCondition::GoldenCross { fast, slow, timeframe } => {
+                        format!("{} {} golden cross: SMA {} crossed above SMA {} (currently ${})", alert.symbol, timeframe.as_str(), fast, slow, price)
+                    }
+                    AlertCondition::DeathCross { fast, slow, timeframe } => {
+                        format!("{} {} death cross: SMA {} crossed below SMA {} (currently ${})", alert.symbol, timeframe.as_str(), fast, slow, price)
+                    }
+                    AlertCondition::AboveUpperBand { period, std_devs, timeframe } => {
+                        let bands = indicators::bollinger(candles, *period as usize, *std_devs);
+                        let upper = bands.map(|b| b.upper.round_dp(8).normalize()).unwrap_or_default();
+                        format!("{} above its {} upper Bollinger band ${} (currently ${})", alert.symbol, timeframe.as_str(), upper, price)
+                    }
+                    AlertCondition::BelowLowerBand { period, std_devs, timeframe } => {
+                        let bands = indicators::bollinger(candles, *period as usize, *std_devs);
+                        let lower = bands.map(|b| b.lower.round_dp(8).normalize()).unwrap_or_default();
+                        format!("{} below its {} lower Bollinger band ${} (currently ${})", alert.symbol, timeframe.as_str(), lower, price)
+                    }
+                    AlertCondition::VolumeAboveAverage { period, timeframe, .. } => {
+                        let ratio = indicators::volume_ratio(closed_candles, *period as usize).unwrap_or_default();
+                        format!("{} {} volume at {:.1}x its {}-candle average", alert.symbol, timeframe.as_str(), ratio, period)
+                    }
+                    AlertCondition::Rule(rule) => format!("Rule met: {}", rule),
                 }
+            });
+
+            // Shown in the app; notification sinks are sent the queued trigger
+            self.recent_alerts.push((format!("🔔 {}", message), now));
+            self.alert_triggers.push(AlertTrigger {
+                alert_id: alert.id,
+                symbol: alert.symbol.clone(),
+                price,
+                message,
+                triggered_at: now,
+            });
+
+            // Keep only the last 10 recent alerts
+            if self.recent_alerts.len() > 10 {
+                self.recent_alerts.remove(0);
             }
         }
     }
@@ -820,6 +2135,12 @@ impl App {
     pub fn clear_recent_alerts(&mut self) {
         self.recent_alerts.clear();
     }
+
+    /// Triggers since the last call, oldest first, for saving to alert
+    /// history and sending to notification sinks
+    pub fn take_alert_triggers(&mut self) -> Vec<AlertTrigger> {
+        std::mem::take(&mut self.alert_triggers)
+    }
 }
 
 // Helper function to check if two filters are of the same type
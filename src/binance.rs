@@ -1,6 +1,10 @@
+use rust_decimal::Decimal;
 use serde::Deserialize;
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
+use crate::error::{CoinPeekError, SymbolResult};
+use crate::exchange::{MarketDataSource, StreamEvent, TickerStream};
+use crate::symbols::SymbolInfo;
 
 #[derive(Debug, Deserialize)]
 pub struct PriceResponse {
@@ -33,6 +37,40 @@ pub struct Ticker24hrResponse {
     pub count: u64,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ExchangeInfoResponse {
+    pub symbols: Vec<ExchangeSymbol>,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(non_snake_case)]
+pub struct ExchangeSymbol {
+    pub symbol: String,
+    pub status: String,
+    pub baseAsset: String,
+    pub quoteAsset: String,
+    pub filters: Vec<SymbolFilter>,
+}
+
+/// The trading rule filters we use; Binance sends several more
+#[derive(Debug, Deserialize)]
+#[serde(tag = "filterType")]
+#[allow(non_snake_case)]
+pub enum SymbolFilter {
+    #[serde(rename = "PRICE_FILTER")]
+    Price { tickSize: String },
+    #[serde(rename = "LOT_SIZE")]
+    LotSize { stepSize: String, minQty: String },
+    #[serde(other)]
+    Other,
+}
+
+/// Error body Binance sends with 4xx responses, e.g. `{"code":-1121,"msg":"Invalid symbol."}`
+#[derive(Debug, Deserialize)]
+struct ApiErrorResponse {
+    msg: String,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct WebSocketPriceUpdate {
     pub stream: String,
@@ -70,23 +108,33 @@ pub struct PriceData {
     pub n: u64,    // Total number of trades
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+/// Prices are exact decimals parsed from Binance's strings, so low-priced
+/// coins keep every digit; volumes and percentages stay `f64`.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct PriceInfo {
     pub symbol: String,
-    pub price: f64,
+    pub price: Decimal,
     pub price_change_percent: f64,
     pub volume: f64,
-    pub high_24h: f64,
-    pub low_24h: f64,
-    pub prev_close_price: f64,
+    pub high_24h: Decimal,
+    pub low_24h: Decimal,
+    pub prev_close_price: Decimal,
+    // Missing from data saved before these were tracked
+    #[serde(default)]
+    pub bid_price: Decimal,
+    #[serde(default)]
+    pub ask_price: Decimal,
+    /// 24h volume in the quote asset (e.g. USDT)
+    #[serde(default)]
+    pub quote_volume: f64,
 }
 
 #[derive(Debug, Clone)]
 pub struct Candle {
-    pub open: f64,
-    pub high: f64,
-    pub low: f64,
-    pub close: f64,
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
     pub volume: f64,
     pub timestamp: u64,
 }
@@ -95,238 +143,564 @@ pub struct Candle {
 #[cfg(target_arch = "wasm32")]
 use gloo::net::http::Request;
 #[cfg(not(target_arch = "wasm32"))]
-use reqwest::Error as ReqwestError;
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use futures::{SinkExt, StreamExt};
+#[cfg(not(target_arch = "wasm32"))]
+use tokio_tungstenite::tungstenite::Message;
 
-#[cfg(target_arch = "wasm32")]
-type Error = gloo::net::Error;
+use crate::config::ApiConfig;
+use crate::rate_limit::RateLimiter;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Response header carrying the request weight used in the current minute
+const USED_WEIGHT_HEADER: &str = "x-mbx-used-weight-1m";
+
+/// Request weight of single-symbol ticker and kline endpoints
+const SINGLE_SYMBOL_WEIGHT: u32 = 2;
+
+/// Request weight of `/api/v3/exchangeInfo` for all symbols
+const EXCHANGE_INFO_WEIGHT: u32 = 20;
+
+/// Largest `limit` the klines endpoint accepts
+const MAX_KLINES_PER_REQUEST: u16 = 1000;
+
+/// Binance rejects `symbols=` lists longer than this
+const MAX_SYMBOLS_PER_TICKER_REQUEST: usize = 100;
+
+/// Reconnect delays for the ticker stream double up to this cap
 #[cfg(not(target_arch = "wasm32"))]
-type Error = ReqwestError;
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
 
-/// Validate that a symbol is safe for API calls
-pub fn validate_symbol_for_api(symbol: &str) -> Result<(), String> {
-    // Only allow uppercase letters and specific lengths
-    if symbol.len() < 6 || symbol.len() > 14 {
-        return Err("Symbol length must be 6-14 characters".to_string());
+/// Treat the stream as dead if nothing (not even a ping) arrives for this long
+#[cfg(not(target_arch = "wasm32"))]
+const STREAM_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Binance spot market data source.
+///
+/// Holds the REST base URL and, on native targets, a shared HTTP client so
+/// connections are pooled across requests. Point `ApiConfig::base_url` at a
+/// local mock server, a proxy or `https://api.binance.us` as needed.
+/// Clones share one rate limiter, so every copy sees the same request weight.
+#[derive(Debug, Clone)]
+pub struct BinanceClient {
+    base_url: String,
+    stream_url: String,
+    limiter: Arc<RateLimiter>,
+    #[cfg(not(target_arch = "wasm32"))]
+    http: reqwest::Client,
+}
+
+impl BinanceClient {
+    /// Build a client from the API section of the configuration
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn new(config: &ApiConfig) -> Result<Self, CoinPeekError> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_seconds))
+            .connect_timeout(Duration::from_secs(config.connect_timeout_seconds))
+            .user_agent(config.user_agent.clone())
+            .gzip(true)
+            .build()?;
+
+        Ok(Self {
+            base_url: config.base_url.trim_end_matches('/').to_string(),
+            stream_url: config.stream_url.trim_end_matches('/').to_string(),
+            limiter: Arc::new(RateLimiter::new(config.weight_limit_per_minute)),
+            http,
+        })
     }
 
-    if !symbol.chars().all(|c| c.is_ascii_uppercase()) {
-        return Err("Symbol must contain only uppercase ASCII letters".to_string());
+    /// Build a client from the API section of the configuration
+    #[cfg(target_arch = "wasm32")]
+    pub fn new(config: &ApiConfig) -> Result<Self, CoinPeekError> {
+        // Timeouts and the user agent are controlled by the browser
+        Ok(Self {
+            base_url: config.base_url.trim_end_matches('/').to_string(),
+            stream_url: config.stream_url.trim_end_matches('/').to_string(),
+            limiter: Arc::new(RateLimiter::new(config.weight_limit_per_minute)),
+        })
     }
 
-    // Prevent obvious injection attempts
-    if symbol.contains("HTTP") || symbol.contains("://") || symbol.contains("<") || symbol.contains(">") {
-        return Err("Symbol contains invalid characters".to_string());
+    pub fn base_url(&self) -> &str {
+        &self.base_url
     }
 
-    Ok(())
-}
+    pub fn stream_url(&self) -> &str {
+        &self.stream_url
+    }
 
-/// Fetches the price of a single crypto symbol from Binance API
-#[cfg(not(target_arch = "wasm32"))]
-pub async fn fetch_price(symbol: &str) -> Result<f64, Box<dyn std::error::Error>> {
-    // Validate symbol before making API call
-    validate_symbol_for_api(symbol)?;
-
-    let url = format!(
-        "https://api.binance.com/api/v3/ticker/price?symbol={}",
-        symbol
-    );
-    let resp = reqwest::get(&url).await?.json::<PriceResponse>().await?;
-    let price = resp.price.parse::<f64>().unwrap_or(0.0);
-    Ok(price)
-}
+    pub fn rate_limiter(&self) -> &RateLimiter {
+        &self.limiter
+    }
 
-#[cfg(target_arch = "wasm32")]
-pub async fn fetch_price(symbol: &str) -> Result<f64, Error> {
-    let url = format!(
-        "https://api.binance.com/api/v3/ticker/price?symbol={}",
-        symbol
-    );
-    let resp = Request::get(&url).send().await?.json::<PriceResponse>().await?;
-    let price = resp.price.parse::<f64>().unwrap_or(0.0);
-    Ok(price)
-}
+    /// Fetch one batch of 24hr tickers. Binance rejects the whole batch with
+    /// HTTP 400 if any symbol isn't listed, so in that case the symbols are
+    /// retried one by one to find out which ones are bad.
+    async fn fetch_ticker_chunk<'a>(&self, chunk: &[&'a str]) -> Result<Vec<(&'a str, Result<PriceInfo, CoinPeekError>)>, CoinPeekError> {
+        let path = format!("/api/v3/ticker/24hr?symbols={}", symbols_query_param(chunk));
+
+        match self.get_json::<Vec<Ticker24hrResponse>>(&path, ticker_24hr_weight(chunk.len())).await {
+            Ok(tickers) => {
+                let mut by_symbol: HashMap<String, Ticker24hrResponse> = tickers
+                    .into_iter()
+                    .map(|ticker| (ticker.symbol.clone(), ticker))
+                    .collect();
+
+                Ok(chunk
+                    .iter()
+                    .map(|&symbol| {
+                        let result = match by_symbol.remove(symbol) {
+                            Some(ticker) => ticker_to_price_info(&ticker),
+                            None => Err(not_listed(symbol)),
+                        };
+                        (symbol, result)
+                    })
+                    .collect())
+            }
+            Err(CoinPeekError::HttpStatus { status: 400, .. }) => {
+                let singles = chunk.iter().map(|&symbol| async move {
+                    let result = self.fetch_24hr_stats(symbol).await.map_err(|e| match e {
+                        CoinPeekError::HttpStatus { status: 400, message } => CoinPeekError::InvalidSymbol {
+                            symbol: symbol.to_string(),
+                            reason: message,
+                        },
+                        other => other,
+                    });
+                    (symbol, result)
+                });
+                Ok(futures::future::join_all(singles).await)
+            }
+            Err(e) => Err(e),
+        }
+    }
 
+    /// GET an API path (including query string) and decode the JSON body.
+    /// `weight` is the endpoint's request weight as documented by Binance.
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn get_json<T: serde::de::DeserializeOwned>(&self, path: &str, weight: u32) -> Result<T, CoinPeekError> {
+        self.limiter.acquire(weight, Utc::now())?;
 
-/// Fetches 24hr ticker statistics for a single symbol
-#[cfg(not(target_arch = "wasm32"))]
-pub async fn fetch_24hr_stats(symbol: &str) -> Result<Ticker24hrResponse, Error> {
-    let url = format!(
-        "https://api.binance.com/api/v3/ticker/24hr?symbol={}",
-        symbol
-    );
-    let resp = reqwest::get(&url).await?.json::<Ticker24hrResponse>().await?;
-    Ok(resp)
-}
+        let url = format!("{}{}", self.base_url, path);
+        let resp = match self.http.get(&url).send().await {
+            Ok(resp) => resp,
+            Err(e) => {
+                self.limiter.record_failure(Utc::now());
+                return Err(e.into());
+            }
+        };
 
-#[cfg(target_arch = "wasm32")]
-pub async fn fetch_24hr_stats(symbol: &str) -> Result<Ticker24hrResponse, Error> {
-    let url = format!(
-        "https://api.binance.com/api/v3/ticker/24hr?symbol={}",
-        symbol
-    );
-    let resp = Request::get(&url).send().await?.json::<Ticker24hrResponse>().await?;
-    Ok(resp)
-}
+        let header = |name: &str| resp.headers().get(name).and_then(|v| v.to_str().ok()).and_then(|v| v.parse::<u64>().ok());
+        if let Some(used) = header(USED_WEIGHT_HEADER) {
+            self.limiter.record_used_weight(used as u32, Utc::now());
+        }
 
-/// Fetches prices for multiple symbols concurrently
-#[cfg(not(target_arch = "wasm32"))]
-pub async fn fetch_prices(symbols: &[&str]) -> Result<Vec<(String, f64)>, Box<dyn std::error::Error>> {
-    let fetches = symbols.iter().cloned().map(|symbol| async move {
-        match fetch_price(&symbol).await {
-            Ok(price) => (symbol.to_string(), price),
-            Err(e) => {
-                eprintln!("Failed to fetch {}: {}", symbol, e);
-                (symbol.to_string(), 0.0)
+        let status = resp.status().as_u16();
+        if status == 429 || status == 418 {
+            let retry_after = header("retry-after");
+            return Err(self.limiter.record_rate_limited(status, retry_after, Utc::now()));
+        }
+        if !resp.status().is_success() {
+            if resp.status().is_server_error() {
+                self.limiter.record_failure(Utc::now());
             }
+            let body = resp.text().await.unwrap_or_default();
+            return Err(http_status_error(status, &body));
         }
-    });
 
-    Ok(futures::future::join_all(fetches).await)
-}
+        let data = resp.json::<T>().await?;
+        self.limiter.record_success();
+        Ok(data)
+    }
 
-#[cfg(target_arch = "wasm32")]
-pub async fn fetch_prices(symbols: &[&str]) -> Result<Vec<(String, f64)>, Box<dyn std::error::Error>> {
-    let fetches = symbols.iter().cloned().map(|symbol| async move {
-        match fetch_price(&symbol).await {
-            Ok(price) => (symbol.to_string(), price),
+    /// GET an API path (including query string) and decode the JSON body.
+    /// `weight` is the endpoint's request weight as documented by Binance.
+    #[cfg(target_arch = "wasm32")]
+    async fn get_json<T: serde::de::DeserializeOwned>(&self, path: &str, weight: u32) -> Result<T, CoinPeekError> {
+        self.limiter.acquire(weight, Utc::now())?;
+
+        let url = format!("{}{}", self.base_url, path);
+        let resp = match Request::get(&url).send().await {
+            Ok(resp) => resp,
             Err(e) => {
-                web_sys::console::log_1(&format!("Failed to fetch {}: {:?}", symbol, e).into());
-                (symbol.to_string(), 0.0)
+                self.limiter.record_failure(Utc::now());
+                return Err(e.into());
+            }
+        };
+
+        // Only visible when the API exposes these headers to the browser
+        let header = |name: &str| resp.headers().get(name).and_then(|v| v.parse::<u64>().ok());
+        if let Some(used) = header(USED_WEIGHT_HEADER) {
+            self.limiter.record_used_weight(used as u32, Utc::now());
+        }
+
+        let status = resp.status();
+        if status == 429 || status == 418 {
+            let retry_after = header("retry-after");
+            return Err(self.limiter.record_rate_limited(status, retry_after, Utc::now()));
+        }
+        if !resp.ok() {
+            if status >= 500 {
+                self.limiter.record_failure(Utc::now());
             }
+            let body = resp.text().await.unwrap_or_default();
+            return Err(http_status_error(status, &body));
         }
-    });
 
-    Ok(futures::future::join_all(fetches).await)
+        let data = resp.json::<T>().await?;
+        self.limiter.record_success();
+        Ok(data)
+    }
 }
 
-/// Fetches comprehensive price information including 24hr stats for multiple symbols
-#[cfg(not(target_arch = "wasm32"))]
-pub async fn fetch_price_infos(symbols: &[&str]) -> Result<Vec<PriceInfo>, Box<dyn std::error::Error>> {
-    let fetches = symbols.iter().cloned().map(|symbol| async move {
-        let (price_result, stats_result) = tokio::join!(
-            fetch_price(&symbol),
-            fetch_24hr_stats(&symbol)
-        );
-
-        match (price_result, stats_result) {
-            (Ok(price), Ok(stats)) => PriceInfo {
-                symbol: symbol.to_string(),
-                price,
-                price_change_percent: stats.priceChangePercent.parse().unwrap_or(0.0),
-                volume: stats.volume.parse().unwrap_or(0.0),
-                high_24h: stats.highPrice.parse().unwrap_or(0.0),
-                low_24h: stats.lowPrice.parse().unwrap_or(0.0),
-                prev_close_price: stats.prevClosePrice.parse().unwrap_or(0.0),
-            },
-            (Ok(price), Err(_)) => {
-                eprintln!("Failed to fetch 24hr stats for {}, using basic price", symbol);
-                PriceInfo {
-                    symbol: symbol.to_string(),
-                    price,
-                    price_change_percent: 0.0,
-                    volume: 0.0,
-                    high_24h: 0.0,
-                    low_24h: 0.0,
-                    prev_close_price: 0.0,
-                }
-            },
-            (Err(e), _) => {
-                eprintln!("Failed to fetch {}: {}", symbol, e);
-                PriceInfo {
-                    symbol: symbol.to_string(),
-                    price: 0.0,
-                    price_change_percent: 0.0,
-                    volume: 0.0,
-                    high_24h: 0.0,
-                    low_24h: 0.0,
-                    prev_close_price: 0.0,
+impl MarketDataSource for BinanceClient {
+    fn name(&self) -> &'static str {
+        "binance"
+    }
+
+    async fn fetch_price(&self, symbol: &str) -> Result<Decimal, CoinPeekError> {
+        // Validate symbol before making API call
+        validate_symbol_for_api(symbol)?;
+
+        let resp: PriceResponse = self.get_json(&format!("/api/v3/ticker/price?symbol={}", symbol), SINGLE_SYMBOL_WEIGHT).await?;
+        parse_price("price", &resp.price)
+    }
+
+    async fn fetch_24hr_stats(&self, symbol: &str) -> Result<PriceInfo, CoinPeekError> {
+        validate_symbol_for_api(symbol)?;
+
+        let stats: Ticker24hrResponse = self.get_json(&format!("/api/v3/ticker/24hr?symbol={}", symbol), SINGLE_SYMBOL_WEIGHT).await?;
+        ticker_to_price_info(&stats)
+    }
+
+    /// Uses the multi-symbol 24hr ticker endpoint, so N symbols cost one
+    /// request per 100 instead of two per symbol. Symbols that are malformed
+    /// or that the exchange doesn't return get their own error entry.
+    async fn fetch_price_infos(&self, symbols: &[&str]) -> Result<Vec<SymbolResult<PriceInfo>>, CoinPeekError> {
+        let mut outcomes: HashMap<&str, Result<PriceInfo, CoinPeekError>> = HashMap::new();
+        let mut valid = Vec::with_capacity(symbols.len());
+        for &symbol in symbols {
+            match validate_symbol_for_api(symbol) {
+                Ok(()) => valid.push(symbol),
+                Err(e) => {
+                    outcomes.insert(symbol, Err(e));
                 }
             }
         }
-    });
 
-    Ok(futures::future::join_all(fetches).await)
-}
+        let chunks = valid.chunks(MAX_SYMBOLS_PER_TICKER_REQUEST).map(|chunk| self.fetch_ticker_chunk(chunk));
+        for chunk_outcomes in futures::future::try_join_all(chunks).await? {
+            outcomes.extend(chunk_outcomes);
+        }
 
-#[cfg(target_arch = "wasm32")]
-pub async fn fetch_price_infos(symbols: &[&str]) -> Result<Vec<PriceInfo>, Box<dyn std::error::Error>> {
-    let fetches = symbols.iter().cloned().map(|symbol| async move {
-        let (price_result, stats_result) = futures::join!(
-            fetch_price(&symbol),
-            fetch_24hr_stats(&symbol)
-        );
-
-        match (price_result, stats_result) {
-            (Ok(price), Ok(stats)) => PriceInfo {
+        // Answer in the order the caller asked for
+        Ok(symbols
+            .iter()
+            .map(|&symbol| SymbolResult {
                 symbol: symbol.to_string(),
-                price,
-                price_change_percent: stats.priceChangePercent.parse().unwrap_or(0.0),
-                volume: stats.volume.parse().unwrap_or(0.0),
-                high_24h: stats.highPrice.parse().unwrap_or(0.0),
-                low_24h: stats.lowPrice.parse().unwrap_or(0.0),
-                prev_close_price: stats.prevClosePrice.parse().unwrap_or(0.0),
-            },
-            (Ok(price), Err(_)) => {
-                web_sys::console::log_1(&format!("Failed to fetch 24hr stats for {}, using basic price", symbol).into());
-                PriceInfo {
-                    symbol: symbol.to_string(),
-                    price,
-                    price_change_percent: 0.0,
-                    volume: 0.0,
-                    high_24h: 0.0,
-                    low_24h: 0.0,
-                    prev_close_price: 0.0,
+                result: outcomes.remove(symbol).unwrap_or_else(|| Err(not_listed(symbol))),
+            })
+            .collect())
+    }
+
+    async fn fetch_candles(&self, symbol: &str, interval: &str, limit: u8) -> Result<Vec<Candle>, CoinPeekError> {
+        validate_symbol_for_api(symbol)?;
+
+        let raw_data: Vec<Vec<serde_json::Value>> = self.get_json(&format!(
+            "/api/v3/klines?symbol={}&interval={}&limit={}",
+            symbol, interval, limit
+        ), SINGLE_SYMBOL_WEIGHT).await?;
+
+        Ok(parse_klines(raw_data))
+    }
+
+    async fn fetch_candle_page(
+        &self,
+        symbol: &str,
+        interval: &str,
+        start_ms: u64,
+        end_ms: u64,
+        limit: u16,
+    ) -> Result<Vec<Candle>, CoinPeekError> {
+        validate_symbol_for_api(symbol)?;
+
+        let raw_data: Vec<Vec<serde_json::Value>> = self.get_json(&format!(
+            "/api/v3/klines?symbol={}&interval={}&startTime={}&endTime={}&limit={}",
+            symbol, interval, start_ms, end_ms, limit.clamp(1, MAX_KLINES_PER_REQUEST)
+        ), SINGLE_SYMBOL_WEIGHT).await?;
+
+        Ok(parse_klines(raw_data))
+    }
+
+    /// Trading rules for every symbol on the exchange, including ones that
+    /// are halted or delisted, so callers can explain why a symbol is unusable
+    async fn fetch_symbols(&self) -> Result<Vec<SymbolInfo>, CoinPeekError> {
+        let info: ExchangeInfoResponse = self.get_json("/api/v3/exchangeInfo", EXCHANGE_INFO_WEIGHT).await?;
+        info.symbols.iter().map(exchange_symbol_to_info).collect()
+    }
+
+    fn throttled_until(&self) -> Option<DateTime<Utc>> {
+        self.limiter.throttled_until(Utc::now())
+    }
+
+    /// Spawns a background task on the current tokio runtime that keeps the
+    /// combined stream connected, reconnecting with backoff until the returned
+    /// receiver is dropped.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn subscribe_tickers(&self, symbols: &[String]) -> Result<TickerStream, CoinPeekError> {
+        for symbol in symbols {
+            validate_symbol_for_api(symbol)?;
+        }
+
+        let url = combined_stream_url(&self.stream_url, symbols);
+        let (tx, rx) = futures::channel::mpsc::unbounded();
+        tokio::spawn(run_ticker_stream(url, tx));
+        Ok(rx)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn subscribe_tickers(&self, symbols: &[String]) -> Result<TickerStream, CoinPeekError> {
+        let (tx, rx) = futures::channel::mpsc::unbounded();
+        create_price_websocket(&self.stream_url, symbols.to_vec(), move |update| {
+            match websocket_data_to_price_info(&update) {
+                Ok(price_info) => {
+                    let _ = tx.unbounded_send(StreamEvent::Ticker(price_info));
                 }
-            },
-            (Err(e), _) => {
-                web_sys::console::log_1(&format!("Failed to fetch {}: {:?}", symbol, e).into());
-                PriceInfo {
-                    symbol: symbol.to_string(),
-                    price: 0.0,
-                    price_change_percent: 0.0,
-                    volume: 0.0,
-                    high_24h: 0.0,
-                    low_24h: 0.0,
-                    prev_close_price: 0.0,
+                Err(e) => {
+                    let _ = tx.unbounded_send(StreamEvent::Skipped(e));
                 }
             }
+        })?;
+        Ok(rx)
+    }
+}
+
+/// Convert a 24hr ticker response to PriceInfo
+pub fn ticker_to_price_info(stats: &Ticker24hrResponse) -> Result<PriceInfo, CoinPeekError> {
+    Ok(PriceInfo {
+        symbol: stats.symbol.clone(),
+        price: parse_price("lastPrice", &stats.lastPrice)?,
+        price_change_percent: parse_number("priceChangePercent", &stats.priceChangePercent)?,
+        volume: parse_number("volume", &stats.volume)?,
+        high_24h: parse_price("highPrice", &stats.highPrice)?,
+        low_24h: parse_price("lowPrice", &stats.lowPrice)?,
+        prev_close_price: parse_price("prevClosePrice", &stats.prevClosePrice)?,
+        bid_price: parse_price("bidPrice", &stats.bidPrice)?,
+        ask_price: parse_price("askPrice", &stats.askPrice)?,
+        quote_volume: parse_number("quoteVolume", &stats.quoteVolume)?,
+    })
+}
+
+/// Convert an exchangeInfo entry to SymbolInfo. Symbols without a price or
+/// lot size filter get a zero tick/step size, meaning "unknown".
+pub fn exchange_symbol_to_info(symbol: &ExchangeSymbol) -> Result<SymbolInfo, CoinPeekError> {
+    let mut info = SymbolInfo {
+        symbol: symbol.symbol.clone(),
+        base_asset: symbol.baseAsset.clone(),
+        quote_asset: symbol.quoteAsset.clone(),
+        status: symbol.status.clone(),
+        tick_size: Decimal::ZERO,
+        step_size: Decimal::ZERO,
+        min_qty: Decimal::ZERO,
+    };
+
+    for filter in &symbol.filters {
+        match filter {
+            SymbolFilter::Price { tickSize } => info.tick_size = parse_price("tickSize", tickSize)?,
+            SymbolFilter::LotSize { stepSize, minQty } => {
+                info.step_size = parse_price("stepSize", stepSize)?;
+                info.min_qty = parse_price("minQty", minQty)?;
+            }
+            SymbolFilter::Other => {}
         }
-    });
+    }
+
+    Ok(info)
+}
+
+/// Parse one of Binance's string-encoded prices without going through `f64`.
+/// Trailing zeros are dropped ("0.02345000" becomes 0.02345).
+fn parse_price(field: &str, value: &str) -> Result<Decimal, CoinPeekError> {
+    value
+        .parse::<Decimal>()
+        .map(|price| price.normalize())
+        .map_err(|_| CoinPeekError::Decode(format!("invalid {} value {:?}", field, value)))
+}
 
-    Ok(futures::future::join_all(fetches).await)
+/// Parse one of Binance's string-encoded quantities or percentages
+fn parse_number(field: &str, value: &str) -> Result<f64, CoinPeekError> {
+    value
+        .parse()
+        .map_err(|_| CoinPeekError::Decode(format!("invalid {} value {:?}", field, value)))
 }
 
-/// Fetch candlestick (OHLC) data for a symbol over a given interval and number of points
+/// Error for a symbol the exchange silently left out of a batch response
+fn not_listed(symbol: &str) -> CoinPeekError {
+    CoinPeekError::InvalidSymbol {
+        symbol: symbol.to_string(),
+        reason: "not returned by the exchange".to_string(),
+    }
+}
+
+/// Build an HTTP status error, keeping Binance's explanation if the body has one
+fn http_status_error(status: u16, body: &str) -> CoinPeekError {
+    let message = serde_json::from_str::<ApiErrorResponse>(body)
+        .map(|e| e.msg)
+        .unwrap_or_default();
+    CoinPeekError::HttpStatus { status, message }
+}
+
+/// Request weight of `/api/v3/ticker/24hr?symbols=` for a number of symbols
+fn ticker_24hr_weight(symbol_count: usize) -> u32 {
+    match symbol_count {
+        0..=20 => 2,
+        21..=100 => 40,
+        _ => 80,
+    }
+}
+
+/// Encode symbols as the JSON array Binance expects, e.g. `["BTCUSDT","ETHUSDT"]`
+fn symbols_query_param(symbols: &[&str]) -> String {
+    let quoted: Vec<String> = symbols.iter().map(|symbol| format!("%22{}%22", symbol)).collect();
+    format!("%5B{}%5D", quoted.join(","))
+}
+
+/// Build the combined-stream URL for `<symbol>@ticker` streams
+pub fn combined_stream_url(stream_url: &str, symbols: &[String]) -> String {
+    let streams: Vec<String> = symbols.iter()
+        .map(|symbol| format!("{}@ticker", symbol.to_lowercase()))
+        .collect();
+
+    format!("{}/stream?streams={}", stream_url.trim_end_matches('/'), streams.join("/"))
+}
+
+/// Keep a combined ticker stream connected, forwarding events until the receiver goes away
 #[cfg(not(target_arch = "wasm32"))]
-pub async fn fetch_candles(symbol: &str, interval: &str, limit: u8) -> Result<Vec<Candle>, Box<dyn std::error::Error>> {
-    let url = format!(
-        "https://api.binance.com/api/v3/klines?symbol={}&interval={}&limit={}",
-        symbol, interval, limit
-    );
+async fn run_ticker_stream(url: String, tx: futures::channel::mpsc::UnboundedSender<StreamEvent>) {
+    let mut attempt: u32 = 0;
+
+    loop {
+        let reason = match tokio_tungstenite::connect_async(url.as_str()).await {
+            Ok((ws, _)) => {
+                attempt = 0;
+                if tx.unbounded_send(StreamEvent::Connected).is_err() {
+                    return;
+                }
+                pump_ticker_stream(ws, &tx).await
+            }
+            Err(e) => format!("Connection failed: {}", e),
+        };
 
-    let raw_data = reqwest::get(&url).await?.json::<Vec<Vec<serde_json::Value>>>().await?;
+        if tx.unbounded_send(StreamEvent::Disconnected(reason)).is_err() {
+            return; // Subscriber dropped the stream
+        }
 
-    let candles = raw_data
+        // Exponential backoff between reconnect attempts
+        let delay = Duration::from_secs(1u64 << attempt.min(5)).min(MAX_RECONNECT_DELAY);
+        attempt = attempt.saturating_add(1);
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Read messages from a connected stream until it fails, returning the reason
+#[cfg(not(target_arch = "wasm32"))]
+async fn pump_ticker_stream<S>(
+    mut ws: tokio_tungstenite::WebSocketStream<S>,
+    tx: &futures::channel::mpsc::UnboundedSender<StreamEvent>,
+) -> String
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+{
+    loop {
+        let message = match tokio::time::timeout(STREAM_IDLE_TIMEOUT, ws.next()).await {
+            Err(_) => return "No data received within idle timeout".to_string(),
+            Ok(None) => return "Connection closed".to_string(),
+            Ok(Some(Err(e))) => return format!("Stream error: {}", e),
+            Ok(Some(Ok(message))) => message,
+        };
+
+        match message {
+            Message::Text(text) => {
+                let parsed = serde_json::from_str::<WebSocketPriceUpdate>(&text)
+                    .map_err(CoinPeekError::from)
+                    .and_then(|update| websocket_data_to_price_info(&update.data));
+                match parsed {
+                    Ok(price_info) => {
+                        if tx.unbounded_send(StreamEvent::Ticker(price_info)).is_err() {
+                            let _ = ws.close(None).await;
+                            return "Subscriber dropped".to_string();
+                        }
+                    }
+                    Err(e) => {
+                        if tx.unbounded_send(StreamEvent::Skipped(e)).is_err() {
+                            let _ = ws.close(None).await;
+                            return "Subscriber dropped".to_string();
+                        }
+                    }
+                }
+            }
+            // Binance disconnects clients that don't answer pings promptly
+            Message::Ping(payload) => {
+                if let Err(e) = ws.send(Message::Pong(payload)).await {
+                    return format!("Failed to answer ping: {}", e);
+                }
+            }
+            Message::Close(frame) => {
+                return match frame {
+                    Some(frame) => format!("Closed by server: {}", frame.reason),
+                    None => "Closed by server".to_string(),
+                };
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Parse a raw klines response, skipping malformed entries
+pub fn parse_klines(raw_data: Vec<Vec<serde_json::Value>>) -> Vec<Candle> {
+    raw_data
         .into_iter()
         .filter_map(|entry| {
             Some(Candle {
-                open: entry.get(1)?.as_str()?.parse().ok()?,
-                high: entry.get(2)?.as_str()?.parse().ok()?,
-                low: entry.get(3)?.as_str()?.parse().ok()?,
-                close: entry.get(4)?.as_str()?.parse().ok()?,
+                open: parse_price("open", entry.get(1)?.as_str()?).ok()?,
+                high: parse_price("high", entry.get(2)?.as_str()?).ok()?,
+                low: parse_price("low", entry.get(3)?.as_str()?).ok()?,
+                close: parse_price("close", entry.get(4)?.as_str()?).ok()?,
                 volume: entry.get(5)?.as_str()?.parse().ok()?,
-                timestamp: entry.get(0)?.as_u64()?,
+                timestamp: entry.first()?.as_u64()?,
             })
         })
-        .collect();
+        .collect()
+}
+
+/// Validate that a symbol is safe for API calls
+pub fn validate_symbol_for_api(symbol: &str) -> Result<(), CoinPeekError> {
+    let invalid = |reason: &str| CoinPeekError::InvalidSymbol {
+        symbol: symbol.to_string(),
+        reason: reason.to_string(),
+    };
+
+    // Only allow uppercase letters, digits (e.g. 1INCHUSDT) and specific lengths
+    if symbol.len() < 5 || symbol.len() > 20 {
+        return Err(invalid("Symbol length must be 5-20 characters"));
+    }
+
+    if !symbol.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit()) {
+        return Err(invalid("Symbol must contain only uppercase ASCII letters and digits"));
+    }
+
+    // Prevent obvious injection attempts
+    if symbol.contains("HTTP") || symbol.contains("://") || symbol.contains("<") || symbol.contains(">") {
+        return Err(invalid("Symbol contains invalid characters"));
+    }
 
-    Ok(candles)
+    Ok(())
 }
 
 /// Create WebSocket connection for real-time price updates
 #[cfg(target_arch = "wasm32")]
-pub fn create_price_websocket<F>(symbols: Vec<String>, on_message: F) -> Result<(), Box<dyn std::error::Error>>
+pub fn create_price_websocket<F>(stream_url: &str, symbols: Vec<String>, on_message: F) -> Result<(), CoinPeekError>
 where
     F: Fn(IndividualTickerUpdate) + 'static,
 {
@@ -340,12 +714,12 @@ where
         .collect();
 
     let streams_param = streams.join("/");
-    let url = format!("wss://stream.binance.com:9443/ws/{}", streams_param);
+    let url = format!("{}/ws/{}", stream_url, streams_param);
 
     web_sys::console::log_1(&format!("Connecting to WebSocket: {}", url).into());
 
     // Create WebSocket connection
-    let ws = WebSocket::new(&url).map_err(|_| "Failed to create WebSocket")?;
+    let ws = WebSocket::new(&url).map_err(|_| CoinPeekError::Network("Failed to create WebSocket".to_string()))?;
 
     // Set binary type
     ws.set_binary_type(web_sys::BinaryType::Blob);
@@ -409,41 +783,17 @@ where
 }
 
 /// Convert WebSocket price data to PriceInfo
-#[cfg(target_arch = "wasm32")]
-pub fn websocket_data_to_price_info(data: &PriceData) -> PriceInfo {
-    PriceInfo {
+pub fn websocket_data_to_price_info(data: &PriceData) -> Result<PriceInfo, CoinPeekError> {
+    Ok(PriceInfo {
         symbol: data.s.clone(),
-        price: data.c.parse().unwrap_or(0.0),
-        price_change_percent: data.P.parse().unwrap_or(0.0),
-        volume: data.v.parse().unwrap_or(0.0),
-        high_24h: data.h.parse().unwrap_or(0.0),
-        low_24h: data.l.parse().unwrap_or(0.0),
-        prev_close_price: data.x.parse().unwrap_or(0.0),
-    }
-}
-
-#[cfg(target_arch = "wasm32")]
-pub async fn fetch_candles(symbol: &str, interval: &str, limit: u8) -> Result<Vec<Candle>, Box<dyn std::error::Error>> {
-    let url = format!(
-        "https://api.binance.com/api/v3/klines?symbol={}&interval={}&limit={}",
-        symbol, interval, limit
-    );
-
-    let raw_data = Request::get(&url).send().await?.json::<Vec<Vec<serde_json::Value>>>().await?;
-
-    let candles = raw_data
-        .into_iter()
-        .filter_map(|entry| {
-            Some(Candle {
-                open: entry.get(1)?.as_str()?.parse().ok()?,
-                high: entry.get(2)?.as_str()?.parse().ok()?,
-                low: entry.get(3)?.as_str()?.parse().ok()?,
-                close: entry.get(4)?.as_str()?.parse().ok()?,
-                volume: entry.get(5)?.as_str()?.parse().ok()?,
-                timestamp: entry.get(0)?.as_u64()?,
-            })
-        })
-        .collect();
-
-    Ok(candles)
+        price: parse_price("c", &data.c)?,
+        price_change_percent: parse_number("P", &data.P)?,
+        volume: parse_number("v", &data.v)?,
+        high_24h: parse_price("h", &data.h)?,
+        low_24h: parse_price("l", &data.l)?,
+        prev_close_price: parse_price("x", &data.x)?,
+        bid_price: parse_price("b", &data.b)?,
+        ask_price: parse_price("a", &data.a)?,
+        quote_volume: parse_number("q", &data.q)?,
+    })
 }
@@ -1,12 +1,323 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
-use regex::Regex;
+use crate::app::DEFAULT_ALERT_COOLDOWN_MINUTES;
+use crate::error::CoinPeekError;
+use crate::rules::AlertRule;
+use crate::symbols::SymbolRegistry;
+use crate::timeframe::TimeFrame;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub symbols: Vec<String>,
     pub refresh_interval_seconds: u64,
+    #[serde(default)]
+    pub api: ApiConfig,
+    #[serde(default)]
+    pub chart: ChartConfig,
+    #[serde(default)]
+    pub retention: RetentionConfig,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alerts: Vec<RuleAlertConfig>,
+    #[serde(default)]
+    pub notifications: NotificationConfig,
+}
+
+/// An alert rule set up in the config file; created in the alert manager on
+/// the first startup that sees it, and left alone after that
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleAlertConfig {
+    pub rule: String,              // e.g. "ETHUSDT < 2000 and ETHUSDT.volume > 500000"
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default = "default_alert_cooldown")]
+    pub cooldown_minutes: u32,
+    #[serde(default)]
+    pub once: bool,                // Switch off after firing once
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sinks: Vec<String>,        // Notification sinks; empty for the default ones
+}
+
+fn default_alert_cooldown() -> u32 {
+    DEFAULT_ALERT_COOLDOWN_MINUTES
+}
+
+/// Where alert notifications go. Each sink is named so alerts can pick the
+/// ones they're sent to; alerts that don't pick any use `default_sinks`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    pub sinks: BTreeMap<String, SinkConfig>,
+    pub default_sinks: Vec<String>,
+    pub retries: u32,          // Extra attempts after a failed delivery
+    pub retry_delay_ms: u64,   // Wait before the first retry; doubles on each one after
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            sinks: BTreeMap::from([("bell".to_string(), SinkConfig::Bell)]),
+            default_sinks: vec!["bell".to_string()],
+            retries: 2,
+            retry_delay_ms: 1000,
+        }
+    }
+}
+
+/// One way of delivering alert notifications
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum SinkConfig {
+    Bell,  // Terminal bell
+    Command {
+        command: String,  // Run by the shell with the alert in COINPEEK_* variables, e.g. notify-send
+    },
+    Webhook {
+        url: String,
+        #[serde(default)]
+        format: WebhookFormat,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        topic: Option<String>,  // ntfy topic to publish to
+    },
+    Log {
+        path: String,  // File each notification is appended to as a line
+    },
+}
+
+/// Shape of the JSON body a webhook is sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookFormat {
+    #[default]
+    Json,     // Every field of the alert
+    Slack,    // {"text": ...}
+    Discord,  // {"content": ...}
+    Ntfy,     // ntfy's JSON publishing, posted to the server root with the topic
+}
+
+impl NotificationConfig {
+    /// Validate the sinks and the routing to them
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, sink) in &self.sinks {
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+                return Err(format!("Invalid notification sink name: '{}'. Use letters, digits, '-' and '_'", name));
+            }
+
+            match sink {
+                SinkConfig::Bell => {}
+                SinkConfig::Command { command } if command.trim().is_empty() => {
+                    return Err(format!("Notification sink '{}' needs a command", name));
+                }
+                SinkConfig::Command { .. } => {}
+                SinkConfig::Webhook { url, format, topic } => {
+                    if !(url.starts_with("https://") || url.starts_with("http://")) {
+                        return Err(format!("Invalid webhook URL for '{}': {}. Must start with http:// or https://", name, url));
+                    }
+                    if *format == WebhookFormat::Ntfy && topic.as_deref().is_none_or(|t| t.trim().is_empty()) {
+                        return Err(format!("Notification sink '{}' needs an ntfy topic", name));
+                    }
+                }
+                SinkConfig::Log { path } if path.trim().is_empty() => {
+                    return Err(format!("Notification sink '{}' needs a log file path", name));
+                }
+                SinkConfig::Log { .. } => {}
+            }
+        }
+
+        self.check_sinks(&self.default_sinks)?;
+
+        if self.retries > 10 {
+            return Err(format!("Invalid notification retries: {}. Must be between 0-10", self.retries));
+        }
+
+        if self.retry_delay_ms > 60_000 {
+            return Err(format!("Invalid notification retry delay: {}. Must be at most 60000 ms", self.retry_delay_ms));
+        }
+
+        Ok(())
+    }
+
+    /// Check that every name in `sinks` is a configured sink
+    pub fn check_sinks(&self, sinks: &[String]) -> Result<(), String> {
+        match sinks.iter().find(|name| !self.sinks.contains_key(*name)) {
+            Some(name) => Err(format!("Unknown notification sink: {}", name)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Where the detail chart's candles come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChartSource {
+    #[default]
+    Exchange,  // Klines downloaded from the exchange and cached in the database
+    Ticks,     // Bars built from the prices recorded on every refresh
+}
+
+impl ChartSource {
+    pub fn next(&self) -> ChartSource {
+        match self {
+            ChartSource::Exchange => ChartSource::Ticks,
+            ChartSource::Ticks => ChartSource::Exchange,
+        }
+    }
+}
+
+/// Detail chart settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChartConfig {
+    pub source: ChartSource,
+    pub tick_bar_seconds: u64,  // Bar length when charting recorded ticks
+}
+
+impl Default for ChartConfig {
+    fn default() -> Self {
+        Self {
+            source: ChartSource::Exchange,
+            tick_bar_seconds: 10,
+        }
+    }
+}
+
+impl ChartConfig {
+    /// Validate the chart settings
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=86_400).contains(&self.tick_bar_seconds) {
+            return Err(format!("Invalid tick bar length: {}. Must be between 1-86400 seconds", self.tick_bar_seconds));
+        }
+
+        Ok(())
+    }
+}
+
+/// Connection settings for the exchange REST API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiConfig {
+    pub base_url: String,              // e.g. https://api.binance.us or a local mock server
+    pub timeout_seconds: u64,          // Whole-request timeout
+    pub connect_timeout_seconds: u64,  // TCP/TLS connect timeout
+    pub user_agent: String,
+    pub stream_url: String,            // WebSocket endpoint for live ticker streams
+    pub enable_streaming: bool,        // Fall back to REST polling only when false or the stream is down
+    pub weight_limit_per_minute: u32,  // Request weight budget; Binance spot allows 6000/min per IP
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            base_url: "https://api.binance.com".to_string(),
+            timeout_seconds: 10,
+            connect_timeout_seconds: 5,
+            user_agent: format!("coinpeek/{}", env!("CARGO_PKG_VERSION")),
+            stream_url: "wss://stream.binance.com:9443".to_string(),
+            enable_streaming: true,
+            weight_limit_per_minute: 6000,
+        }
+    }
+}
+
+impl ApiConfig {
+    /// Validate the API connection settings
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.base_url.starts_with("https://") || self.base_url.starts_with("http://")) {
+            return Err(format!("Invalid API base URL: {}. Must start with http:// or https://", self.base_url));
+        }
+
+        if !(self.stream_url.starts_with("wss://") || self.stream_url.starts_with("ws://")) {
+            return Err(format!("Invalid stream URL: {}. Must start with ws:// or wss://", self.stream_url));
+        }
+
+        if !(1..=120).contains(&self.timeout_seconds) || !(1..=120).contains(&self.connect_timeout_seconds) {
+            return Err("API timeouts must be between 1-120 seconds".to_string());
+        }
+
+        if self.weight_limit_per_minute == 0 {
+            return Err("API weight limit per minute must be greater than 0".to_string());
+        }
+
+        if self.user_agent.trim().is_empty() || !self.user_agent.chars().all(|c| c.is_ascii_graphic() || c == ' ') {
+            return Err("API user agent must be non-empty printable ASCII".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+/// How long stored data is kept before the background cleanup prunes it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionConfig {
+    pub price_days: u32,                    // Raw price refreshes; older ones are downsampled into candles
+    pub downsample_interval: String,        // Candle interval old price refreshes are rolled into, e.g. "1m"
+    pub candle_days: BTreeMap<String, u32>, // Per candle interval, e.g. {"1m": 30}; 0 keeps them forever
+    pub default_candle_days: u32,           // Candle intervals not listed in candle_days; 0 keeps them forever
+    pub cleanup_interval_minutes: u64,      // How often the cleanup runs in the background
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            price_days: 30,
+            downsample_interval: "1m".to_string(),
+            candle_days: BTreeMap::from([
+                ("1m".to_string(), 90),
+                ("1d".to_string(), 0),
+                ("1w".to_string(), 0),
+            ]),
+            default_candle_days: 90,
+            cleanup_interval_minutes: 60,
+        }
+    }
+}
+
+impl RetentionConfig {
+    /// Interval old price refreshes are downsampled into
+    pub fn downsample_timeframe(&self) -> TimeFrame {
+        TimeFrame::parse(&self.downsample_interval).unwrap_or(TimeFrame::M1)
+    }
+
+    /// Days candles of `timeframe` are kept; 0 means forever
+    pub fn candle_days_for(&self, timeframe: TimeFrame) -> u32 {
+        self.candle_days.get(timeframe.as_str()).copied().unwrap_or(self.default_candle_days)
+    }
+
+    /// Validate the retention settings
+    pub fn validate(&self) -> Result<(), String> {
+        if self.price_days == 0 {
+            return Err("Price retention must be at least 1 day".to_string());
+        }
+
+        match TimeFrame::parse(&self.downsample_interval) {
+            Some(TimeFrame::W1) | None => {
+                return Err(format!("Invalid downsample interval: {}. Must be one of 1m-1d", self.downsample_interval));
+            }
+            Some(_) => {}
+        }
+
+        if let Some(interval) = self.candle_days.keys().find(|interval| TimeFrame::parse(interval).is_none()) {
+            return Err(format!("Invalid candle retention interval: {}", interval));
+        }
+
+        // Downsampled candles start out `price_days` old, so a shorter
+        // retention would prune them in the same cleanup that creates them
+        let downsampled_days = self.candle_days_for(self.downsample_timeframe());
+        if downsampled_days != 0 && downsampled_days <= self.price_days {
+            return Err(format!(
+                "{} candles are kept for {} days but price refreshes are downsampled into them after {}. Keep them longer or forever (0)",
+                self.downsample_interval, downsampled_days, self.price_days
+            ));
+        }
+
+        if !(1..=10_080).contains(&self.cleanup_interval_minutes) {
+            return Err("Cleanup interval must be between 1-10080 minutes".to_string());
+        }
+
+        Ok(())
+    }
 }
 
 impl Default for Config {
@@ -24,23 +335,54 @@ impl Default for Config {
                 "LTCUSDT".to_string(),
                 "LINKUSDT".to_string(),
                 "XRPUSDT".to_string(),
-                "MATICUSDT".to_string(),
+                "POLUSDT".to_string(),
                 "UNIUSDT".to_string(),
                 "ALGOUSDT".to_string(),
                 "VETUSDT".to_string(),
             ],
             refresh_interval_seconds: 3,
+            api: ApiConfig::default(),
+            chart: ChartConfig::default(),
+            retention: RetentionConfig::default(),
+            alerts: Vec::new(),
+            notifications: NotificationConfig::default(),
         }
     }
 }
 
 impl Config {
-    /// Validate that a symbol follows proper cryptocurrency trading pair format
+    /// Cheap shape check for a symbol before exchange metadata is available.
+    /// Whether it is actually listed is decided by `validate_symbols`.
     pub fn is_valid_symbol(symbol: &str) -> bool {
-        // Binance symbols are typically 3-10 uppercase letters, followed by 3-4 uppercase letters
-        // Examples: BTCUSDT, ETHBTC, ADAUSDT, etc.
-        let symbol_regex = Regex::new(r"^[A-Z]{3,10}[A-Z]{3,4}$").unwrap();
-        symbol_regex.is_match(symbol)
+        // Digits are allowed: Binance lists pairs like 1INCHUSDT
+        (5..=20).contains(&symbol.len())
+            && symbol.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+    }
+
+    /// Check the configured symbols against the exchange's symbol list and
+    /// drop unknown, delisted and halted pairs, returning why each was
+    /// dropped. A pair that is only paused for a while shouldn't keep the
+    /// rest from being watched, so this fails only when no symbol is left.
+    /// Does nothing when the registry hasn't been loaded yet, e.g. on a
+    /// first run while offline.
+    pub fn retain_usable_symbols(&mut self, registry: &SymbolRegistry) -> Result<Vec<CoinPeekError>, String> {
+        if registry.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let dropped: Vec<CoinPeekError> = self.symbols.iter().filter_map(|symbol| registry.validate(symbol).err()).collect();
+        if dropped.len() == self.symbols.len() {
+            let problems: Vec<String> = dropped
+                .iter()
+                .map(|e| match e {
+                    CoinPeekError::InvalidSymbol { symbol, reason } => format!("{} is {}", symbol, reason),
+                    other => other.to_string(),
+                })
+                .collect();
+            return Err(format!("No usable symbols in configuration: {}", problems.join("; ")));
+        }
+        self.symbols.retain(|symbol| registry.validate(symbol).is_ok());
+        Ok(dropped)
     }
 
     /// Validate refresh interval is reasonable (not too fast to avoid rate limits)
@@ -68,7 +410,7 @@ impl Config {
             }
 
             if !Self::is_valid_symbol(symbol) {
-                return Err(format!("Invalid symbol format: {}. Must be uppercase letters only, like 'BTCUSDT'", symbol));
+                return Err(format!("Invalid symbol format: {}. Must be uppercase letters and digits, like 'BTCUSDT'", symbol));
             }
         }
 
@@ -77,6 +419,20 @@ impl Config {
             return Err(format!("Invalid refresh interval: {}. Must be between 1-300 seconds", self.refresh_interval_seconds));
         }
 
+        self.api.validate()?;
+        self.chart.validate()?;
+        self.retention.validate()?;
+        self.notifications.validate()?;
+
+        for alert in &self.alerts {
+            AlertRule::parse(&alert.rule)
+                .and_then(|rule| rule.check_symbols(&self.symbols))
+                .map_err(|e| format!("Invalid alert rule '{}': {}", alert.rule, e))?;
+            self.notifications
+                .check_sinks(&alert.sinks)
+                .map_err(|e| format!("Invalid alert rule '{}': {}", alert.rule, e))?;
+        }
+
         Ok(())
     }
 
@@ -1,24 +1,46 @@
-use rusqlite::{Connection, Result as SqlResult, params, OptionalExtension};
+use rusqlite::{Result as SqlResult, Row, params, OptionalExtension};
+use rusqlite::types::ValueRef;
+use rust_decimal::Decimal;
 use tokio_rusqlite::Connection as AsyncConnection;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
 use std::path::Path;
 use crate::binance::{PriceInfo, Candle};
+use crate::aggregate::{self, CandleMismatch};
+use crate::app::{AlertMode, AlertTrigger, PriceAlert};
+use crate::config::RetentionConfig;
+use crate::error::CoinPeekError;
+use crate::history::{self, AlignedSeries, PricePoint, PriceStats, SymbolSeries};
+use crate::migrations;
+use crate::symbols::{SymbolInfo, SymbolRegistry};
+use crate::timeframe::TimeFrame;
+
+/// sync_metadata key holding the report of the last retention cleanup
+const LAST_CLEANUP_KEY: &str = "last_cleanup";
+
+/// `PRAGMA auto_vacuum` value for incremental mode
+const INCREMENTAL_AUTO_VACUUM: i64 = 2;
+
+/// Span of price refreshes the cleanup downsamples in one go, per symbol
+const DOWNSAMPLE_WINDOW_MS: u64 = 6 * 60 * 60 * 1000;
 
 /// Database connection manager
+#[derive(Clone)]
 pub struct Database {
     conn: AsyncConnection,
 }
 
 impl Database {
     /// Create a new database connection and initialize schema
-    pub async fn new(db_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    pub async fn new(db_path: &str) -> Result<Self, CoinPeekError> {
         let conn = AsyncConnection::open(db_path).await?;
 
         // Enable WAL mode for better concurrency
         conn.call(|conn| {
             // Execute PRAGMA statements that don't return results
             conn.execute_batch(
-                "PRAGMA journal_mode = WAL;
+                "PRAGMA auto_vacuum = INCREMENTAL;
+                 PRAGMA journal_mode = WAL;
                  PRAGMA synchronous = NORMAL;
                  PRAGMA cache_size = 1000000;
                  PRAGMA temp_store = MEMORY;"
@@ -29,85 +51,27 @@ impl Database {
         // Initialize schema
         Self::init_schema(&conn).await?;
 
-        Ok(Database { conn })
-    }
-
-    /// Initialize database schema
-    async fn init_schema(conn: &AsyncConnection) -> Result<(), Box<dyn std::error::Error>> {
+        // auto_vacuum only applies to new files; older databases need one
+        // full VACUUM to switch to incremental mode
         conn.call(|conn| {
-            // Prices table for current price data
-            conn.execute(
-                "CREATE TABLE IF NOT EXISTS prices (
-                    id INTEGER PRIMARY KEY,
-                    symbol TEXT NOT NULL,
-                    price REAL NOT NULL,
-                    price_change_percent REAL,
-                    volume REAL,
-                    high_24h REAL,
-                    low_24h REAL,
-                    prev_close_price REAL,
-                    timestamp INTEGER NOT NULL,
-                    exchange TEXT DEFAULT 'binance',
-                    created_at INTEGER DEFAULT (strftime('%s', 'now'))
-                )",
-                [],
-            )?;
-
-            // Candles table for historical OHLC data
-            conn.execute(
-                "CREATE TABLE IF NOT EXISTS candles (
-                    id INTEGER PRIMARY KEY,
-                    symbol TEXT NOT NULL,
-                    timeframe TEXT NOT NULL,
-                    open REAL NOT NULL,
-                    high REAL NOT NULL,
-                    low REAL NOT NULL,
-                    close REAL NOT NULL,
-                    volume REAL,
-                    timestamp INTEGER NOT NULL,
-                    exchange TEXT DEFAULT 'binance',
-                    created_at INTEGER DEFAULT (strftime('%s', 'now'))
-                )",
-                [],
-            )?;
-
-            // Sync metadata for tracking last sync times
-            conn.execute(
-                "CREATE TABLE IF NOT EXISTS sync_metadata (
-                    key TEXT PRIMARY KEY,
-                    value TEXT,
-                    updated_at INTEGER DEFAULT (strftime('%s', 'now'))
-                )",
-                [],
-            )?;
-
-            // Indexes for performance
-            conn.execute(
-                "CREATE INDEX IF NOT EXISTS idx_prices_symbol_timestamp
-                ON prices(symbol, timestamp)",
-                [],
-            )?;
-
-            conn.execute(
-                "CREATE INDEX IF NOT EXISTS idx_candles_symbol_timeframe_timestamp
-                ON candles(symbol, timeframe, timestamp)",
-                [],
-            )?;
-
-            conn.execute(
-                "CREATE INDEX IF NOT EXISTS idx_prices_timestamp
-                ON prices(timestamp)",
-                [],
-            )?;
-
+            let auto_vacuum: i64 = conn.query_row("PRAGMA auto_vacuum", [], |row| row.get(0))?;
+            if auto_vacuum != INCREMENTAL_AUTO_VACUUM {
+                conn.execute_batch("PRAGMA auto_vacuum = INCREMENTAL; VACUUM;")?;
+            }
             Ok(())
         }).await?;
 
+        Ok(Database { conn })
+    }
+
+    /// Create the schema or upgrade it to the version this build expects
+    async fn init_schema(conn: &AsyncConnection) -> Result<(), CoinPeekError> {
+        conn.call(|conn| Ok(migrations::migrate(conn))).await??;
         Ok(())
     }
 
     /// Store price information
-    pub async fn store_price_info(&self, price_info: &PriceInfo) -> Result<(), Box<dyn std::error::Error>> {
+    pub async fn store_price_info(&self, price_info: &PriceInfo) -> Result<(), CoinPeekError> {
         let symbol = price_info.symbol.clone();
         let price = price_info.price;
         let price_change_percent = price_info.price_change_percent;
@@ -115,22 +79,29 @@ impl Database {
         let high_24h = price_info.high_24h;
         let low_24h = price_info.low_24h;
         let prev_close_price = price_info.prev_close_price;
+        let bid_price = price_info.bid_price;
+        let ask_price = price_info.ask_price;
+        let quote_volume = price_info.quote_volume;
         let timestamp = Utc::now().timestamp();
 
         self.conn.call(move |conn| {
             conn.execute(
                 "INSERT INTO prices (
                     symbol, price, price_change_percent, volume,
-                    high_24h, low_24h, prev_close_price, timestamp
-                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                    high_24h, low_24h, prev_close_price,
+                    bid_price, ask_price, quote_volume, timestamp
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
                 params![
                     symbol,
-                    price,
+                    price.to_string(),
                     price_change_percent,
                     volume,
-                    high_24h,
-                    low_24h,
-                    prev_close_price,
+                    high_24h.to_string(),
+                    low_24h.to_string(),
+                    prev_close_price.to_string(),
+                    bid_price.to_string(),
+                    ask_price.to_string(),
+                    quote_volume,
                     timestamp
                 ],
             )?;
@@ -141,7 +112,7 @@ impl Database {
     }
 
     /// Store multiple price infos efficiently
-    pub async fn store_price_infos(&self, price_infos: &[PriceInfo]) -> Result<(), Box<dyn std::error::Error>> {
+    pub async fn store_price_infos(&self, price_infos: &[PriceInfo]) -> Result<(), CoinPeekError> {
         if price_infos.is_empty() {
             return Ok(());
         }
@@ -156,16 +127,20 @@ impl Database {
                 tx.execute(
                     "INSERT INTO prices (
                         symbol, price, price_change_percent, volume,
-                        high_24h, low_24h, prev_close_price, timestamp
-                    ) VALUES (?, ?, ?, ?, ?, ?, ?, strftime('%s', 'now'))",
+                        high_24h, low_24h, prev_close_price,
+                        bid_price, ask_price, quote_volume, timestamp
+                    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, strftime('%s', 'now'))",
                     params![
                         price_info.symbol,
-                        price_info.price,
+                        price_info.price.to_string(),
                         price_info.price_change_percent,
                         price_info.volume,
-                        price_info.high_24h,
-                        price_info.low_24h,
-                        price_info.prev_close_price
+                        price_info.high_24h.to_string(),
+                        price_info.low_24h.to_string(),
+                        price_info.prev_close_price.to_string(),
+                        price_info.bid_price.to_string(),
+                        price_info.ask_price.to_string(),
+                        price_info.quote_volume
                     ],
                 )?;
             }
@@ -178,7 +153,7 @@ impl Database {
     }
 
     /// Store candle data
-    pub async fn store_candles(&self, symbol: &str, timeframe: &str, candles: &[Candle]) -> Result<(), Box<dyn std::error::Error>> {
+    pub async fn store_candles(&self, symbol: &str, timeframe: &str, candles: &[Candle]) -> Result<(), CoinPeekError> {
         if candles.is_empty() {
             return Ok(());
         }
@@ -192,17 +167,24 @@ impl Database {
             let tx = conn.transaction()?;
 
             for candle in &cloned_candles {
+                // The newest bar may still be open, so a stored bar is updated in place
                 tx.execute(
                     "INSERT INTO candles (
                         symbol, timeframe, open, high, low, close, volume, timestamp
-                    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                    ON CONFLICT(exchange, symbol, timeframe, timestamp) DO UPDATE SET
+                        open = excluded.open,
+                        high = excluded.high,
+                        low = excluded.low,
+                        close = excluded.close,
+                        volume = excluded.volume",
                     params![
                         &symbol,
                         &timeframe,
-                        candle.open,
-                        candle.high,
-                        candle.low,
-                        candle.close,
+                        candle.open.to_string(),
+                        candle.high.to_string(),
+                        candle.low.to_string(),
+                        candle.close.to_string(),
                         candle.volume,
                         candle.timestamp
                     ],
@@ -217,13 +199,14 @@ impl Database {
     }
 
     /// Get latest price for a symbol
-    pub async fn get_latest_price(&self, symbol: &str) -> Result<Option<PriceInfo>, Box<dyn std::error::Error>> {
+    pub async fn get_latest_price(&self, symbol: &str) -> Result<Option<PriceInfo>, CoinPeekError> {
         let symbol = symbol.to_string();
 
         let result = self.conn.call(move |conn| {
             let mut stmt = conn.prepare(
                 "SELECT symbol, price, price_change_percent, volume,
-                        high_24h, low_24h, prev_close_price
+                        high_24h, low_24h, prev_close_price,
+                        bid_price, ask_price, quote_volume
                  FROM prices
                  WHERE symbol = ?
                  ORDER BY timestamp DESC
@@ -233,12 +216,16 @@ impl Database {
             let price_info = stmt.query_row(params![symbol], |row| {
                 Ok(PriceInfo {
                     symbol: row.get(0)?,
-                    price: row.get(1)?,
+                    price: decimal_column(row, 1)?,
                     price_change_percent: row.get(2)?,
                     volume: row.get(3)?,
-                    high_24h: row.get(4)?,
-                    low_24h: row.get(5)?,
-                    prev_close_price: row.get(6)?,
+                    high_24h: decimal_column(row, 4)?,
+                    low_24h: decimal_column(row, 5)?,
+                    prev_close_price: decimal_column(row, 6)?,
+                    // NULL in rows written before these columns existed
+                    bid_price: decimal_column(row, 7)?,
+                    ask_price: decimal_column(row, 8)?,
+                    quote_volume: row.get::<_, Option<f64>>(9)?.unwrap_or(0.0),
                 })
             }).optional()?;
 
@@ -248,13 +235,120 @@ impl Database {
         Ok(result)
     }
 
+    /// Recorded prices of a symbol between `start` and `end` inclusive, oldest first
+    pub async fn get_price_history(
+        &self,
+        symbol: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>
+    ) -> Result<Vec<PricePoint>, CoinPeekError> {
+        let symbol = symbol.to_string();
+        let (start, end) = (start.timestamp(), end.timestamp());
+
+        let result = self.conn.call(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT timestamp, price FROM prices
+                 WHERE symbol = ? AND timestamp BETWEEN ? AND ?
+                 ORDER BY timestamp, id"
+            )?;
+            let points = stmt
+                .query_map(params![symbol, start, end], price_point)?
+                .collect::<SqlResult<Vec<_>>>()?;
+            Ok(points)
+        }).await?;
+
+        Ok(result)
+    }
+
+    /// The last known price of a symbol at `at`, e.g. "BTC at 09:00
+    /// yesterday". Recorded refreshes are used where they exist; once those
+    /// have been pruned the stored candles answer instead: the open of the
+    /// candle containing `at`, or the close of the last one before it.
+    /// The returned timestamp says when that price was observed.
+    pub async fn get_price_at(&self, symbol: &str, at: DateTime<Utc>) -> Result<Option<PricePoint>, CoinPeekError> {
+        let symbol = symbol.to_string();
+
+        let result = self.conn.call(move |conn| {
+            let mut best: Option<PricePoint> = conn.query_row(
+                "SELECT timestamp, price FROM prices
+                 WHERE symbol = ? AND timestamp <= ?
+                 ORDER BY timestamp DESC, id DESC
+                 LIMIT 1",
+                params![symbol, at.timestamp()],
+                price_point,
+            ).optional()?;
+
+            let at_ms = at.timestamp_millis().max(0) as u64;
+            let mut stmt = conn.prepare(
+                "SELECT timestamp, open, close FROM candles
+                 WHERE symbol = ? AND timeframe = ? AND timestamp <= ?
+                 ORDER BY timestamp DESC
+                 LIMIT 1"
+            )?;
+            for timeframe in TimeFrame::ALL {
+                let candle = stmt.query_row(params![symbol, timeframe.as_str(), at_ms], |row| {
+                    Ok((row.get::<_, u64>(0)?, decimal_column(row, 1)?, decimal_column(row, 2)?))
+                }).optional()?;
+                let Some((open_ms, open, close)) = candle else { continue };
+
+                let close_ms = open_ms + timeframe.duration_ms();
+                let (observed_ms, price) = if close_ms <= at_ms { (close_ms, close) } else { (open_ms, open) };
+                let observed = DateTime::from_timestamp_millis(observed_ms as i64).unwrap_or_default();
+                if best.is_none_or(|b| observed > b.timestamp) {
+                    best = Some(PricePoint { timestamp: observed, price });
+                }
+            }
+
+            Ok(best)
+        }).await?;
+
+        Ok(result)
+    }
+
+    /// Min, max, average and return of a symbol's recorded prices between
+    /// `start` and `end`; `None` if nothing was recorded then
+    pub async fn get_price_stats(
+        &self,
+        symbol: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>
+    ) -> Result<Option<PriceStats>, CoinPeekError> {
+        let points = self.get_price_history(symbol, start, end).await?;
+        Ok(history::price_stats(symbol, &points))
+    }
+
+    /// Prices of several symbols sampled every `step` from `start` to `end`,
+    /// each carrying its last known price forward so the series line up
+    pub async fn get_aligned_series(
+        &self,
+        symbols: &[&str],
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        step: Duration
+    ) -> Result<AlignedSeries, CoinPeekError> {
+        let timestamps = history::time_grid(start, end, step);
+
+        let mut series = Vec::with_capacity(symbols.len());
+        for &symbol in symbols {
+            // Seed with the price at the start so the first grid points aren't empty
+            let mut points: Vec<PricePoint> = self.get_price_at(symbol, start).await?.into_iter().collect();
+            points.extend(self.get_price_history(symbol, start, end).await?);
+            series.push(SymbolSeries {
+                symbol: symbol.to_string(),
+                prices: history::align_to_grid(&points, &timestamps),
+            });
+        }
+
+        Ok(AlignedSeries { timestamps, series })
+    }
+
     /// Get candles for a symbol and timeframe within date range
     pub async fn get_candles(
         &self,
         symbol: &str,
         timeframe: &str,
         limit: usize
-    ) -> Result<Vec<Candle>, Box<dyn std::error::Error>> {
+    ) -> Result<Vec<Candle>, CoinPeekError> {
         let symbol = symbol.to_string();
         let timeframe = timeframe.to_string();
 
@@ -270,10 +364,10 @@ impl Database {
             let mut candles = Vec::new();
             let mut rows = stmt.query_map(params![symbol, timeframe, limit as i64], |row| {
                 Ok(Candle {
-                    open: row.get(0)?,
-                    high: row.get(1)?,
-                    low: row.get(2)?,
-                    close: row.get(3)?,
+                    open: decimal_column(row, 0)?,
+                    high: decimal_column(row, 1)?,
+                    low: decimal_column(row, 2)?,
+                    close: decimal_column(row, 3)?,
                     volume: row.get(4)?,
                     timestamp: row.get(5)?,
                 })
@@ -292,8 +386,196 @@ impl Database {
         Ok(result)
     }
 
+    /// The most recent `limit` candles of `target` built from stored
+    /// lower-timeframe candles, oldest first. Uses whichever source timeframe
+    /// yields the most complete bars, so charts work without asking the
+    /// exchange. Empty if `target` can't be derived.
+    pub async fn get_rolled_up_candles(
+        &self,
+        symbol: &str,
+        target: TimeFrame,
+        limit: usize
+    ) -> Result<Vec<Candle>, CoinPeekError> {
+        let now_ms = Utc::now().timestamp_millis().max(0) as u64;
+
+        let mut best = Vec::new();
+        for &source in aggregate::rollup_sources(target) {
+            let per_bar = (target.duration_ms() / source.duration_ms()) as usize;
+            // One bar extra in case the newest stored candles are a partial bar
+            let candles = self.get_candles(symbol, source.as_str(), (limit + 1) * per_bar).await?;
+            let bars = aggregate::rollup(&candles, source, target, now_ms);
+            if bars.len() > best.len() {
+                best = bars;
+            }
+        }
+
+        let skip = best.len().saturating_sub(limit);
+        Ok(best.split_off(skip))
+    }
+
+    /// The most recent `limit` candles of `timeframe`, oldest first: those
+    /// stored from the exchange, with bars rolled up from lower timeframes
+    /// filling in where none were downloaded. A derived bar also replaces an
+    /// exchange bar that was stored while still open and has closed since
+    /// without being downloaded again.
+    pub async fn get_candles_with_rollup(
+        &self,
+        symbol: &str,
+        timeframe: TimeFrame,
+        limit: usize
+    ) -> Result<Vec<Candle>, CoinPeekError> {
+        let derived = self.get_rolled_up_candles(symbol, timeframe, limit).await?;
+        let covered = self.get_candle_coverage(symbol, timeframe.as_str()).await?;
+        let forming = timeframe.bar_start(Utc::now().timestamp_millis().max(0) as u64);
+        let is_final = |candle: &Candle| {
+            candle.timestamp >= forming || covered.iter().any(|&(start, end)| (start..=end).contains(&candle.timestamp))
+        };
+
+        let mut candles: Vec<Candle> = self
+            .get_candles(symbol, timeframe.as_str(), limit)
+            .await?
+            .into_iter()
+            .filter(|candle| is_final(candle) || !derived.iter().any(|bar| bar.timestamp == candle.timestamp))
+            .collect();
+        let stored: Vec<u64> = candles.iter().map(|candle| candle.timestamp).collect();
+        candles.extend(derived.into_iter().filter(|bar| !stored.contains(&bar.timestamp)));
+        candles.sort_by_key(|candle| candle.timestamp);
+
+        let skip = candles.len().saturating_sub(limit);
+        Ok(candles.split_off(skip))
+    }
+
+    /// The most recent `limit` bars of `bar_seconds` built from the prices
+    /// recorded on each refresh, oldest first. Useful for intervals the
+    /// exchange has no klines for, like 10 second bars.
+    pub async fn get_tick_candles(
+        &self,
+        symbol: &str,
+        bar_seconds: u64,
+        limit: usize
+    ) -> Result<Vec<Candle>, CoinPeekError> {
+        let symbol = symbol.to_string();
+        let bar_seconds = bar_seconds.max(1) as i64;
+        let lookback = limit.saturating_sub(1) as i64 * bar_seconds;
+
+        let ticks = self.conn.call(move |conn| {
+            // Start at the opening of the oldest bar that can still be shown
+            let mut stmt = conn.prepare(
+                "SELECT price, timestamp FROM prices
+                 WHERE symbol = ?1
+                   AND timestamp >= (SELECT MAX(timestamp) FROM prices WHERE symbol = ?1) / ?2 * ?2 - ?3
+                 ORDER BY timestamp, id"
+            )?;
+            let ticks = stmt
+                .query_map(params![symbol, bar_seconds, lookback], |row| {
+                    Ok((row.get::<_, u64>(1)? * 1000, decimal_column(row, 0)?))
+                })?
+                .collect::<SqlResult<Vec<_>>>()?;
+            Ok(ticks)
+        }).await?;
+
+        Ok(aggregate::ticks_to_candles(&ticks, bar_seconds as u64 * 1000))
+    }
+
+    /// Closed bars of the last `limit` where the candles derived from
+    /// lower timeframes disagree with the ones stored from the exchange
+    pub async fn check_rollup(
+        &self,
+        symbol: &str,
+        target: TimeFrame,
+        limit: usize
+    ) -> Result<Vec<CandleMismatch>, CoinPeekError> {
+        let forming = target.bar_start(Utc::now().timestamp_millis().max(0) as u64);
+        let derived: Vec<Candle> = self
+            .get_rolled_up_candles(symbol, target, limit)
+            .await?
+            .into_iter()
+            .filter(|bar| bar.timestamp != forming)
+            .collect();
+        let exchange = self.get_candles(symbol, target.as_str(), limit).await?;
+
+        Ok(aggregate::compare_with_exchange(&derived, &exchange))
+    }
+
+    /// Candles of a symbol and timeframe opening between `start` and `end`
+    /// inclusive, oldest first
+    pub async fn get_candle_range(
+        &self,
+        symbol: &str,
+        timeframe: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>
+    ) -> Result<Vec<Candle>, CoinPeekError> {
+        let symbol = symbol.to_string();
+        let timeframe = timeframe.to_string();
+        let (start_ms, end_ms) = (start.timestamp_millis(), end.timestamp_millis());
+
+        let result = self.conn.call(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT open, high, low, close, volume, timestamp
+                 FROM candles
+                 WHERE symbol = ? AND timeframe = ? AND timestamp BETWEEN ? AND ?
+                 ORDER BY timestamp"
+            )?;
+            let candles = stmt
+                .query_map(params![symbol, timeframe, start_ms, end_ms], |row| {
+                    Ok(Candle {
+                        open: decimal_column(row, 0)?,
+                        high: decimal_column(row, 1)?,
+                        low: decimal_column(row, 2)?,
+                        close: decimal_column(row, 3)?,
+                        volume: row.get(4)?,
+                        timestamp: row.get(5)?,
+                    })
+                })?
+                .collect::<SqlResult<Vec<_>>>()?;
+            Ok(candles)
+        }).await?;
+
+        Ok(result)
+    }
+
+    /// Candles the cleanup built from old price refreshes for `timeframe`,
+    /// with open times from `start` through `end`, oldest first. They carry
+    /// no volume.
+    pub async fn get_downsampled_candle_range(
+        &self,
+        symbol: &str,
+        timeframe: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>
+    ) -> Result<Vec<Candle>, CoinPeekError> {
+        let symbol = symbol.to_string();
+        let timeframe = timeframe.to_string();
+        let (start_ms, end_ms) = (start.timestamp_millis(), end.timestamp_millis());
+
+        let result = self.conn.call(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT open, high, low, close, timestamp
+                 FROM downsampled_candles
+                 WHERE symbol = ? AND timeframe = ? AND timestamp BETWEEN ? AND ?
+                 ORDER BY timestamp"
+            )?;
+            let candles = stmt
+                .query_map(params![symbol, timeframe, start_ms, end_ms], |row| {
+                    Ok(Candle {
+                        open: decimal_column(row, 0)?,
+                        high: decimal_column(row, 1)?,
+                        low: decimal_column(row, 2)?,
+                        close: decimal_column(row, 3)?,
+                        volume: 0.0,
+                        timestamp: row.get(4)?,
+                    })
+                })?
+                .collect::<SqlResult<Vec<_>>>()?;
+            Ok(candles)
+        }).await?;
+
+        Ok(result)
+    }
+
     /// Get all symbols that have recent price data
-    pub async fn get_active_symbols(&self) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    pub async fn get_active_symbols(&self) -> Result<Vec<String>, CoinPeekError> {
         let result = self.conn.call(|conn| {
             let mut stmt = conn.prepare(
                 "SELECT DISTINCT symbol FROM prices
@@ -317,8 +599,134 @@ impl Database {
         Ok(result)
     }
 
+    /// Replace the cached symbol metadata with a fresh copy from the exchange
+    pub async fn store_symbols(&self, symbols: &[SymbolInfo], updated_at: DateTime<Utc>) -> Result<(), CoinPeekError> {
+        let symbols = symbols.to_vec();
+        let updated_at = updated_at.timestamp();
+
+        self.conn.call(move |conn| {
+            let tx = conn.transaction()?;
+            tx.execute("DELETE FROM symbols", [])?;
+            {
+                let mut stmt = tx.prepare(
+                    "INSERT INTO symbols (symbol, base_asset, quote_asset, status, tick_size, step_size, min_qty, updated_at)
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+                )?;
+
+                for info in &symbols {
+                    stmt.execute(params![
+                        info.symbol,
+                        info.base_asset,
+                        info.quote_asset,
+                        info.status,
+                        info.tick_size.to_string(),
+                        info.step_size.to_string(),
+                        info.min_qty.to_string(),
+                        updated_at
+                    ])?;
+                }
+            }
+            tx.commit()?;
+            Ok(())
+        }).await?;
+
+        Ok(())
+    }
+
+    /// Load the cached symbol metadata; empty if it was never fetched
+    pub async fn load_symbol_registry(&self) -> Result<SymbolRegistry, CoinPeekError> {
+        let result = self.conn.call(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT symbol, base_asset, quote_asset, status, tick_size, step_size, min_qty, updated_at
+                 FROM symbols"
+            )?;
+
+            let mut symbols = Vec::new();
+            let mut oldest: Option<i64> = None;
+            let rows = stmt.query_map([], |row| {
+                let info = SymbolInfo {
+                    symbol: row.get(0)?,
+                    base_asset: row.get(1)?,
+                    quote_asset: row.get(2)?,
+                    status: row.get(3)?,
+                    tick_size: decimal_column(row, 4)?,
+                    step_size: decimal_column(row, 5)?,
+                    min_qty: decimal_column(row, 6)?,
+                };
+                Ok((info, row.get::<_, i64>(7)?))
+            })?;
+
+            for row in rows {
+                let (info, updated_at) = row?;
+                oldest = Some(oldest.map_or(updated_at, |t| t.min(updated_at)));
+                symbols.push(info);
+            }
+
+            Ok(match oldest.and_then(|t| DateTime::from_timestamp(t, 0)) {
+                Some(updated_at) => SymbolRegistry::new(symbols, updated_at),
+                None => SymbolRegistry::default(),
+            })
+        }).await?;
+
+        Ok(result)
+    }
+
+    /// Time ranges (inclusive Unix milliseconds, oldest first) whose candles
+    /// have been fetched from the exchange
+    pub async fn get_candle_coverage(&self, symbol: &str, timeframe: &str) -> Result<Vec<(u64, u64)>, CoinPeekError> {
+        let symbol = symbol.to_string();
+        let timeframe = timeframe.to_string();
+
+        let result = self.conn.call(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT start_ms, end_ms FROM candle_coverage
+                 WHERE symbol = ? AND timeframe = ?
+                 ORDER BY start_ms"
+            )?;
+            let ranges = stmt
+                .query_map(params![symbol, timeframe], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<SqlResult<Vec<(u64, u64)>>>()?;
+            Ok(ranges)
+        }).await?;
+
+        Ok(result)
+    }
+
+    /// Mark a time range as fetched, merging it with overlapping or adjacent ranges
+    pub async fn record_candle_coverage(&self, symbol: &str, timeframe: &str, start_ms: u64, end_ms: u64) -> Result<(), CoinPeekError> {
+        if end_ms < start_ms {
+            return Ok(());
+        }
+        let symbol = symbol.to_string();
+        let timeframe = timeframe.to_string();
+
+        self.conn.call(move |conn| {
+            let tx = conn.transaction()?;
+            let mut ranges = {
+                let mut stmt = tx.prepare(
+                    "SELECT start_ms, end_ms FROM candle_coverage WHERE symbol = ? AND timeframe = ?"
+                )?;
+                stmt.query_map(params![symbol, timeframe], |row| Ok((row.get(0)?, row.get(1)?)))?
+                    .collect::<SqlResult<Vec<(u64, u64)>>>()?
+            };
+            ranges.push((start_ms, end_ms));
+
+            tx.execute("DELETE FROM candle_coverage WHERE symbol = ? AND timeframe = ?", params![symbol, timeframe])?;
+            for (start, end) in merge_ranges(ranges) {
+                tx.execute(
+                    "INSERT INTO candle_coverage (symbol, timeframe, start_ms, end_ms) VALUES (?, ?, ?, ?)",
+                    params![symbol, timeframe, start, end],
+                )?;
+            }
+            tx.commit()?;
+            Ok(())
+        }).await?;
+
+        Ok(())
+    }
+
     /// Update sync metadata
-    pub async fn update_sync_metadata(&self, key: &str, value: &str) -> Result<(), Box<dyn std::error::Error>> {
+    pub async fn update_sync_metadata(&self, key: &str, value: &str) -> Result<(), CoinPeekError> {
         let key = key.to_string();
         let value = value.to_string();
 
@@ -335,7 +743,7 @@ impl Database {
     }
 
     /// Get sync metadata
-    pub async fn get_sync_metadata(&self, key: &str) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    pub async fn get_sync_metadata(&self, key: &str) -> Result<Option<String>, CoinPeekError> {
         let key = key.to_string();
 
         let result = self.conn.call(move |conn| {
@@ -354,32 +762,312 @@ impl Database {
         Ok(result)
     }
 
-    /// Clean old data (keep last 30 days for prices, last 90 days for candles)
-    pub async fn cleanup_old_data(&self) -> Result<(), Box<dyn std::error::Error>> {
-        self.conn.call(|conn| {
-            // Clean old price data (keep 30 days)
+    /// Prune data past its retention period. Price refreshes are rolled into
+    /// candles of the downsample interval before they're deleted, so old
+    /// history stays chartable at a coarser resolution. Those go to their
+    /// own table rather than among the exchange klines, and are built one
+    /// symbol and `DOWNSAMPLE_WINDOW_MS` at a time so a long backlog of
+    /// refreshes isn't read into memory at once. Freed pages are returned to
+    /// the filesystem with an incremental vacuum rather than rewriting the file.
+    pub async fn cleanup_old_data(&self, retention: &RetentionConfig, now: DateTime<Utc>) -> Result<CleanupReport, CoinPeekError> {
+        let downsample = retention.downsample_timeframe();
+        let now_ms = now.timestamp_millis().max(0) as u64;
+        // Whole bars only, so a downsampled candle never misses ticks that are kept
+        let price_cutoff_ms = downsample.bar_start(now_ms.saturating_sub(days_ms(retention.price_days)));
+        let candle_cutoffs: Vec<(TimeFrame, u64)> = TimeFrame::ALL
+            .into_iter()
+            .filter(|&timeframe| retention.candle_days_for(timeframe) > 0)
+            .map(|timeframe| (timeframe, now_ms.saturating_sub(days_ms(retention.candle_days_for(timeframe)))))
+            .collect();
+
+        let report = self.conn.call(move |conn| {
+            let price_cutoff = (price_cutoff_ms / 1000) as i64;
+            let tx = conn.transaction()?;
+
+            let symbols = {
+                let mut stmt = tx.prepare("SELECT DISTINCT symbol FROM prices WHERE timestamp < ?")?;
+                stmt.query_map(params![price_cutoff], |row| row.get::<_, String>(0))?
+                    .collect::<SqlResult<Vec<_>>>()?
+            };
+
+            // A few hours of one symbol's ticks at a time, in whole bars
+            let bar_ms = downsample.duration_ms();
+            let window_ms = DOWNSAMPLE_WINDOW_MS.div_ceil(bar_ms) * bar_ms;
+            let mut downsampled_candles = 0;
+            for symbol in &symbols {
+                let mut from_ms = 0;
+                loop {
+                    let first: Option<u64> = tx.query_row(
+                        "SELECT MIN(timestamp) FROM prices WHERE symbol = ? AND timestamp >= ? AND timestamp < ?",
+                        params![symbol, from_ms / 1000, price_cutoff],
+                        |row| row.get(0),
+                    )?;
+                    let Some(first) = first else {
+                        break;
+                    };
+                    let start_ms = downsample.bar_start(first * 1000);
+                    let end_ms = (start_ms + window_ms).min(price_cutoff_ms);
+
+                    let ticks = {
+                        let mut stmt = tx.prepare_cached(
+                            "SELECT timestamp, price FROM prices
+                             WHERE symbol = ? AND timestamp >= ? AND timestamp < ?
+                             ORDER BY timestamp, id"
+                        )?;
+                        stmt.query_map(params![symbol, start_ms / 1000, end_ms / 1000], |row| {
+                            Ok((row.get::<_, u64>(0)? * 1000, decimal_column(row, 1)?))
+                        })?
+                        .collect::<SqlResult<Vec<_>>>()?
+                    };
+                    for candle in aggregate::ticks_to_candles(&ticks, bar_ms) {
+                        downsampled_candles += tx.execute(
+                            "INSERT OR REPLACE INTO downsampled_candles (symbol, timeframe, open, high, low, close, timestamp)
+                             VALUES (?, ?, ?, ?, ?, ?, ?)",
+                            params![
+                                symbol,
+                                downsample.as_str(),
+                                candle.open.to_string(),
+                                candle.high.to_string(),
+                                candle.low.to_string(),
+                                candle.close.to_string(),
+                                candle.timestamp
+                            ],
+                        )?;
+                    }
+                    from_ms = end_ms;
+                }
+            }
+            let prices_pruned = tx.execute("DELETE FROM prices WHERE timestamp < ?", params![price_cutoff])?;
+
+            let mut candles_pruned = 0;
+            for (timeframe, cutoff_ms) in &candle_cutoffs {
+                candles_pruned += tx.execute(
+                    "DELETE FROM candles WHERE timeframe = ? AND timestamp < ?",
+                    params![timeframe.as_str(), cutoff_ms],
+                )?;
+                candles_pruned += tx.execute(
+                    "DELETE FROM downsampled_candles WHERE timeframe = ? AND timestamp < ?",
+                    params![timeframe.as_str(), cutoff_ms],
+                )?;
+                // Pruned candles no longer count as fetched
+                tx.execute(
+                    "DELETE FROM candle_coverage WHERE timeframe = ? AND end_ms < ?",
+                    params![timeframe.as_str(), cutoff_ms],
+                )?;
+                tx.execute(
+                    "UPDATE candle_coverage SET start_ms = ?2 WHERE timeframe = ?1 AND start_ms < ?2",
+                    params![timeframe.as_str(), cutoff_ms],
+                )?;
+            }
+            tx.commit()?;
+
+            let free_pages = |conn: &rusqlite::Connection| -> SqlResult<i64> {
+                conn.query_row("PRAGMA freelist_count", [], |row| row.get(0))
+            };
+            let free_before = free_pages(conn)?;
+            conn.execute_batch("PRAGMA incremental_vacuum;")?;
+            let page_size: i64 = conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+
+            Ok(CleanupReport {
+                ran_at: now,
+                prices_pruned,
+                downsampled_candles,
+                candles_pruned,
+                bytes_reclaimed: (free_before - free_pages(conn)?) * page_size,
+            })
+        }).await?;
+
+        let json = serde_json::to_string(&report).map_err(|e| CoinPeekError::Database(e.to_string()))?;
+        self.update_sync_metadata(LAST_CLEANUP_KEY, &json).await?;
+
+        Ok(report)
+    }
+
+    /// Saved alerts, oldest first
+    pub async fn get_alerts(&self) -> Result<Vec<PriceAlert>, CoinPeekError> {
+        let result = self.conn.call(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, symbol, condition, enabled, message, created_at, last_triggered, trigger_count,
+                        cooldown_minutes, mode, sinks
+                 FROM alerts
+                 ORDER BY id"
+            )?;
+
+            let alerts = stmt.query_map([], |row| {
+                let condition: String = row.get(2)?;
+                Ok(PriceAlert {
+                    id: row.get(0)?,
+                    symbol: row.get(1)?,
+                    condition: serde_json::from_str(&condition).map_err(|e| {
+                        rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(e))
+                    })?,
+                    enabled: row.get(3)?,
+                    message: row.get(4)?,
+                    created_at: DateTime::from_timestamp(row.get(5)?, 0).unwrap_or_default(),
+                    last_triggered: row.get::<_, Option<i64>>(6)?.and_then(|t| DateTime::from_timestamp(t, 0)),
+                    trigger_count: row.get(7)?,
+                    cooldown_minutes: row.get(8)?,
+                    mode: AlertMode::parse(&row.get::<_, String>(9)?).unwrap_or_default(),
+                    armed: None,
+                    sinks: row
+                        .get::<_, String>(10)?
+                        .split(',')
+                        .filter(|name| !name.is_empty())
+                        .map(str::to_string)
+                        .collect(),
+                })
+            })?
+            .collect::<SqlResult<Vec<_>>>()?;
+
+            Ok(alerts)
+        }).await?;
+
+        Ok(result)
+    }
+
+    /// Insert an alert, or overwrite the saved alert with the same id
+    pub async fn save_alert(&self, alert: &PriceAlert) -> Result<(), CoinPeekError> {
+        let alert = alert.clone();
+        let condition = serde_json::to_string(&alert.condition).map_err(|e| CoinPeekError::Database(e.to_string()))?;
+
+        self.conn.call(move |conn| {
             conn.execute(
-                "DELETE FROM prices WHERE timestamp < strftime('%s', 'now', '-30 days')",
-                [],
+                "INSERT OR REPLACE INTO alerts
+                 (id, symbol, condition, enabled, message, created_at, last_triggered, trigger_count, cooldown_minutes, mode, sinks)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                params![
+                    alert.id,
+                    alert.symbol,
+                    condition,
+                    alert.enabled,
+                    alert.message,
+                    alert.created_at.timestamp(),
+                    alert.last_triggered.map(|t| t.timestamp()),
+                    alert.trigger_count,
+                    alert.cooldown_minutes,
+                    alert.mode.as_str(),
+                    alert.sinks.join(",")
+                ],
             )?;
+            Ok(())
+        }).await?;
+
+        Ok(())
+    }
 
-            // Clean old candle data (keep 90 days)
+    /// Enable or disable a saved alert
+    pub async fn set_alert_enabled(&self, id: u32, enabled: bool) -> Result<(), CoinPeekError> {
+        self.conn.call(move |conn| {
+            conn.execute("UPDATE alerts SET enabled = ? WHERE id = ?", params![enabled, id])?;
+            Ok(())
+        }).await?;
+
+        Ok(())
+    }
+
+    /// Delete a saved alert along with its history, so a later alert given
+    /// the same id doesn't inherit it
+    pub async fn delete_alert(&self, id: u32) -> Result<(), CoinPeekError> {
+        self.conn.call(move |conn| {
+            let tx = conn.transaction()?;
+            tx.execute("DELETE FROM alert_history WHERE alert_id = ?", params![id])?;
+            tx.execute("DELETE FROM alerts WHERE id = ?", params![id])?;
+            tx.commit()?;
+            Ok(())
+        }).await?;
+
+        Ok(())
+    }
+
+    /// Config file rules that have already been turned into alerts
+    pub async fn get_imported_alert_rules(&self) -> Result<Vec<String>, CoinPeekError> {
+        let result = self.conn.call(|conn| {
+            let mut stmt = conn.prepare("SELECT rule FROM alert_rule_imports ORDER BY imported_at, rule")?;
+            let rules = stmt.query_map([], |row| row.get(0))?.collect::<SqlResult<Vec<String>>>()?;
+            Ok(rules)
+        }).await?;
+
+        Ok(result)
+    }
+
+    /// Remember that a config file rule was turned into an alert, so it
+    /// isn't created again after the alert is deleted or edited
+    pub async fn record_alert_rule_import(&self, rule: &str) -> Result<(), CoinPeekError> {
+        let rule = rule.to_string();
+        let now = Utc::now().timestamp();
+
+        self.conn.call(move |conn| {
             conn.execute(
-                "DELETE FROM candles WHERE timestamp < strftime('%s', 'now', '-90 days')",
-                [],
+                "INSERT OR IGNORE INTO alert_rule_imports (rule, imported_at) VALUES (?, ?)",
+                params![rule, now],
             )?;
+            Ok(())
+        }).await?;
 
-            // Optimize database
-            conn.execute("VACUUM", [])?;
+        Ok(())
+    }
+
+    /// Add a trigger to the alert history and update the alert's last
+    /// trigger time and count, switching off alerts that only fire once
+    pub async fn record_alert_trigger(&self, trigger: &AlertTrigger) -> Result<(), CoinPeekError> {
+        let trigger = trigger.clone();
 
+        self.conn.call(move |conn| {
+            let tx = conn.transaction()?;
+            tx.execute(
+                "INSERT INTO alert_history (alert_id, symbol, price, message, triggered_at) VALUES (?, ?, ?, ?, ?)",
+                params![
+                    trigger.alert_id,
+                    trigger.symbol,
+                    trigger.price.to_string(),
+                    trigger.message,
+                    trigger.triggered_at.timestamp()
+                ],
+            )?;
+            tx.execute(
+                "UPDATE alerts
+                 SET last_triggered = ?1, trigger_count = trigger_count + 1,
+                     enabled = CASE WHEN mode = ?2 THEN 0 ELSE enabled END
+                 WHERE id = ?3",
+                params![trigger.triggered_at.timestamp(), AlertMode::Once.as_str(), trigger.alert_id],
+            )?;
+            tx.commit()?;
             Ok(())
         }).await?;
 
         Ok(())
     }
 
+    /// The `limit` most recent triggers of an alert, newest first
+    pub async fn get_alert_history(&self, alert_id: u32, limit: usize) -> Result<Vec<AlertTrigger>, CoinPeekError> {
+        let result = self.conn.call(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT alert_id, symbol, price, message, triggered_at
+                 FROM alert_history
+                 WHERE alert_id = ?
+                 ORDER BY triggered_at DESC, id DESC
+                 LIMIT ?"
+            )?;
+
+            let triggers = stmt.query_map(params![alert_id, limit], |row| {
+                Ok(AlertTrigger {
+                    alert_id: row.get(0)?,
+                    symbol: row.get(1)?,
+                    price: decimal_column(row, 2)?,
+                    message: row.get(3)?,
+                    triggered_at: DateTime::from_timestamp(row.get(4)?, 0).unwrap_or_default(),
+                })
+            })?
+            .collect::<SqlResult<Vec<_>>>()?;
+
+            Ok(triggers)
+        }).await?;
+
+        Ok(result)
+    }
+
     /// Get database statistics
-    pub async fn get_stats(&self) -> Result<DatabaseStats, Box<dyn std::error::Error>> {
+    pub async fn get_stats(&self) -> Result<DatabaseStats, CoinPeekError> {
         let result = self.conn.call(|conn| {
             let price_count: i64 = conn.query_row(
                 "SELECT COUNT(*) FROM prices",
@@ -399,10 +1087,17 @@ impl Database {
                 |row| row.get(0),
             )?;
 
+            let last_cleanup: Option<String> = conn.query_row(
+                "SELECT value FROM sync_metadata WHERE key = ?",
+                params![LAST_CLEANUP_KEY],
+                |row| row.get(0),
+            ).optional()?;
+
             Ok(DatabaseStats {
                 price_records: price_count,
                 candle_records: candle_count,
                 database_size_bytes: db_size,
+                last_cleanup: last_cleanup.and_then(|json| serde_json::from_str(&json).ok()),
             })
         }).await?;
 
@@ -410,12 +1105,62 @@ impl Database {
     }
 }
 
+/// Read a `(timestamp, price)` row of the prices table
+fn price_point(row: &Row) -> SqlResult<PricePoint> {
+    Ok(PricePoint {
+        timestamp: DateTime::from_timestamp(row.get(0)?, 0).unwrap_or_default(),
+        price: decimal_column(row, 1)?,
+    })
+}
+
+fn days_ms(days: u32) -> u64 {
+    days as u64 * 24 * 60 * 60 * 1000
+}
+
+/// Sort inclusive ranges and join the ones that overlap or touch
+fn merge_ranges(mut ranges: Vec<(u64, u64)>) -> Vec<(u64, u64)> {
+    ranges.sort_unstable();
+    let mut merged: Vec<(u64, u64)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1.saturating_add(1) => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// Read a price column, accepting the REAL values older databases hold and
+/// treating NULL as zero
+fn decimal_column(row: &Row, idx: usize) -> SqlResult<Decimal> {
+    let value = row.get_ref(idx)?;
+    let parsed = match value {
+        ValueRef::Null => Some(Decimal::ZERO),
+        ValueRef::Integer(i) => Some(Decimal::from(i)),
+        ValueRef::Real(f) => Decimal::from_f64_retain(f).map(|d| d.round_dp(12).normalize()),
+        ValueRef::Text(text) => std::str::from_utf8(text).ok().and_then(|t| t.parse().ok()),
+        ValueRef::Blob(_) => None,
+    };
+    parsed.ok_or_else(|| rusqlite::Error::InvalidColumnType(idx, "decimal".to_string(), value.data_type()))
+}
+
 /// Database statistics
 #[derive(Debug, Clone)]
 pub struct DatabaseStats {
     pub price_records: i64,
     pub candle_records: i64,
     pub database_size_bytes: i64,
+    pub last_cleanup: Option<CleanupReport>,  // What the most recent retention cleanup pruned
+}
+
+/// What one retention cleanup removed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CleanupReport {
+    pub ran_at: DateTime<Utc>,
+    pub prices_pruned: usize,        // Price refreshes past their retention
+    pub downsampled_candles: usize,  // Candles written from those refreshes
+    pub candles_pruned: usize,       // Candles past the retention of their interval, downsampled ones included
+    pub bytes_reclaimed: i64,        // Space returned to the filesystem by the incremental vacuum
 }
 
 impl DatabaseStats {
@@ -0,0 +1,130 @@
+use chrono::{DateTime, Utc};
+
+/// Errors produced by the data layer (exchange client and database)
+#[derive(Debug, Clone)]
+pub enum CoinPeekError {
+    /// Connection, DNS, TLS or timeout failure before a response arrived
+    Network(String),
+    /// The API answered with a non-success status; `message` is the
+    /// exchange's own explanation when it sent one
+    HttpStatus { status: u16, message: String },
+    /// A response or stored value could not be decoded
+    Decode(String),
+    /// A symbol was rejected locally or by the exchange
+    InvalidSymbol { symbol: String, reason: String },
+    /// Requests are being held back until `retry_at`
+    RateLimited { reason: String, retry_at: DateTime<Utc> },
+    /// SQLite failure
+    Database(String),
+    /// Reading or writing a local file (exports, imports) failed
+    Io(String),
+    /// The data source doesn't offer what was asked of it
+    Unsupported(String),
+}
+
+pub type Result<T> = std::result::Result<T, CoinPeekError>;
+
+/// Outcome for one symbol of a multi-symbol request, so one bad symbol
+/// doesn't hide the data fetched for the rest
+#[derive(Debug, Clone)]
+pub struct SymbolResult<T> {
+    pub symbol: String,
+    pub result: Result<T>,
+}
+
+impl CoinPeekError {
+    pub fn recovery_suggestion(&self) -> String {
+        match self {
+            CoinPeekError::Network(_) => "Check your internet connection".to_string(),
+            CoinPeekError::HttpStatus { status, .. } if *status >= 500 => {
+                "Exchange may be temporarily unavailable - data will refresh when available".to_string()
+            }
+            CoinPeekError::HttpStatus { .. } => "Check the API settings in coinpeek.json".to_string(),
+            CoinPeekError::Decode(_) => "The exchange sent unexpected data - try again later".to_string(),
+            CoinPeekError::InvalidSymbol { .. } => "Remove or correct the symbol in coinpeek.json".to_string(),
+            CoinPeekError::RateLimited { retry_at, .. } => {
+                format!("Requests resume automatically after {} UTC", retry_at.format("%H:%M:%S"))
+            }
+            CoinPeekError::Database(_) => "Application may run in limited mode - restart may help".to_string(),
+            CoinPeekError::Io(_) => "Check the file path and that there is space to write".to_string(),
+            CoinPeekError::Unsupported(_) => "Switch to an exchange that offers this".to_string(),
+        }
+    }
+
+    /// When a rate-limited request may be retried
+    pub fn retry_at(&self) -> Option<DateTime<Utc>> {
+        match self {
+            CoinPeekError::RateLimited { retry_at, .. } => Some(*retry_at),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for CoinPeekError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CoinPeekError::Network(msg) => write!(f, "Network error: {}", msg),
+            CoinPeekError::HttpStatus { status, message } if message.is_empty() => write!(f, "HTTP {}", status),
+            CoinPeekError::HttpStatus { status, message } => write!(f, "HTTP {}: {}", status, message),
+            CoinPeekError::Decode(msg) => write!(f, "Unexpected data: {}", msg),
+            CoinPeekError::InvalidSymbol { symbol, reason } => write!(f, "Invalid symbol {}: {}", symbol, reason),
+            CoinPeekError::RateLimited { reason, retry_at } => {
+                write!(f, "{}; retrying after {} UTC", reason, retry_at.format("%H:%M:%S"))
+            }
+            CoinPeekError::Database(msg) => write!(f, "Database error: {}", msg),
+            CoinPeekError::Io(msg) => write!(f, "File error: {}", msg),
+            CoinPeekError::Unsupported(msg) => write!(f, "Not supported: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for CoinPeekError {}
+
+impl From<std::io::Error> for CoinPeekError {
+    fn from(e: std::io::Error) -> Self {
+        CoinPeekError::Io(e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for CoinPeekError {
+    fn from(e: serde_json::Error) -> Self {
+        CoinPeekError::Decode(e.to_string())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl From<reqwest::Error> for CoinPeekError {
+    fn from(e: reqwest::Error) -> Self {
+        if let Some(status) = e.status() {
+            CoinPeekError::HttpStatus { status: status.as_u16(), message: String::new() }
+        } else if e.is_decode() {
+            CoinPeekError::Decode(e.to_string())
+        } else {
+            CoinPeekError::Network(e.to_string())
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl From<gloo::net::Error> for CoinPeekError {
+    fn from(e: gloo::net::Error) -> Self {
+        match e {
+            gloo::net::Error::SerdeError(e) => CoinPeekError::Decode(e.to_string()),
+            other => CoinPeekError::Network(other.to_string()),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl From<rusqlite::Error> for CoinPeekError {
+    fn from(e: rusqlite::Error) -> Self {
+        CoinPeekError::Database(e.to_string())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl From<tokio_rusqlite::Error> for CoinPeekError {
+    fn from(e: tokio_rusqlite::Error) -> Self {
+        CoinPeekError::Database(e.to_string())
+    }
+}
@@ -0,0 +1,114 @@
+use crate::binance::{Candle, PriceInfo};
+use crate::error::{CoinPeekError, SymbolResult};
+use crate::symbols::SymbolInfo;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use futures::channel::mpsc::UnboundedReceiver;
+use futures::Stream;
+
+/// Most candles requested per page by `fetch_candle_range`
+pub const MAX_CANDLES_PER_PAGE: u16 = 1000;
+
+/// Events delivered by a live ticker stream
+#[derive(Debug, Clone)]
+pub enum StreamEvent {
+    Connected,
+    Ticker(PriceInfo),
+    Skipped(CoinPeekError),  // A message that couldn't be read; the stream carries on
+    Disconnected(String),
+}
+
+/// Receiving half of a live ticker subscription
+pub type TickerStream = UnboundedReceiver<StreamEvent>;
+
+/// A venue that can provide market data to the TUI and web front ends.
+///
+/// Binance is the only implementation today, but everything that consumes
+/// prices or candles goes through this trait so other exchanges (or fakes in
+/// tests) can be swapped in without touching the UI code.
+// The futures are awaited on the thread that created them (TUI loop, yew
+// component), so we deliberately don't require them to be `Send`.
+#[allow(async_fn_in_trait)]
+pub trait MarketDataSource {
+    /// Short identifier stored alongside persisted data, e.g. "binance"
+    fn name(&self) -> &'static str;
+
+    /// Latest traded price for a single symbol
+    async fn fetch_price(&self, symbol: &str) -> Result<Decimal, CoinPeekError>;
+
+    /// Rolling 24h statistics for a single symbol
+    async fn fetch_24hr_stats(&self, symbol: &str) -> Result<PriceInfo, CoinPeekError>;
+
+    /// Price and 24h statistics for every requested symbol, in request order.
+    /// The outer error means nothing could be fetched; per-symbol failures
+    /// are reported in the individual results.
+    async fn fetch_price_infos(&self, symbols: &[&str]) -> Result<Vec<SymbolResult<PriceInfo>>, CoinPeekError>;
+
+    /// Candlestick (OHLC) data for a symbol over a given interval and number of points
+    async fn fetch_candles(&self, symbol: &str, interval: &str, limit: u8) -> Result<Vec<Candle>, CoinPeekError>;
+
+    /// One page of at most `limit` candles opening between `start_ms` and
+    /// `end_ms` inclusive (Unix milliseconds), oldest first
+    async fn fetch_candle_page(
+        &self,
+        _symbol: &str,
+        _interval: &str,
+        _start_ms: u64,
+        _end_ms: u64,
+        _limit: u16,
+    ) -> Result<Vec<Candle>, CoinPeekError> {
+        Err(CoinPeekError::Unsupported(format!("{} does not support candle history ranges", self.name())))
+    }
+
+    /// Every candle opening between `start` and `end`, yielded a page at a
+    /// time so months of history never have to sit in memory at once. An
+    /// error is yielded and ends the stream; with `wait_on_rate_limit`,
+    /// long-running jobs wait rate limits out instead.
+    fn fetch_candle_range(
+        &self,
+        symbol: &str,
+        interval: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        wait_on_rate_limit: bool,
+    ) -> impl Stream<Item = Result<Vec<Candle>, CoinPeekError>> {
+        let end_ms = end.timestamp_millis().max(0) as u64;
+        let first_page = Some(start.timestamp_millis().max(0) as u64);
+
+        futures::stream::unfold(first_page, move |cursor| async move {
+            let start_ms = cursor.filter(|&start_ms| start_ms <= end_ms)?;
+            loop {
+                match self.fetch_candle_page(symbol, interval, start_ms, end_ms, MAX_CANDLES_PER_PAGE).await {
+                    Ok(page) if page.is_empty() => return None,
+                    Ok(page) => {
+                        // A short page means the range is exhausted
+                        let next = (page.len() >= MAX_CANDLES_PER_PAGE as usize)
+                            .then(|| page.last().map(|candle| candle.timestamp + 1))
+                            .flatten();
+                        return Some((Ok(page), next));
+                    }
+                    Err(CoinPeekError::RateLimited { retry_at, .. }) if wait_on_rate_limit => {
+                        crate::rate_limit::sleep_until(retry_at).await
+                    }
+                    Err(e) => return Some((Err(e), None)),
+                }
+            }
+        })
+    }
+
+    /// Trading rules and asset names for the symbols the venue lists
+    async fn fetch_symbols(&self) -> Result<Vec<SymbolInfo>, CoinPeekError> {
+        Err(CoinPeekError::Unsupported(format!("{} does not provide symbol metadata", self.name())))
+    }
+
+    /// When requests may resume if the source is holding them back because of
+    /// exchange rate limits or backoff; `None` while requests flow normally
+    fn throttled_until(&self) -> Option<DateTime<Utc>> {
+        None
+    }
+
+    /// Subscribe to live ticker updates for the given symbols
+    fn subscribe_tickers(&self, _symbols: &[String]) -> Result<TickerStream, CoinPeekError> {
+        Err(CoinPeekError::Unsupported(format!("{} does not support live ticker streams", self.name())))
+    }
+}
@@ -0,0 +1,249 @@
+use std::io::Write;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+
+use crate::database::Database;
+use crate::error::CoinPeekError;
+use crate::timeframe::TimeFrame;
+
+/// Output layouts for exported data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,        // Header row, then one line per record
+    JsonLines,  // One JSON object per line
+    Columnar,   // One JSON object holding an array per column, ready for a dataframe or Parquet writer
+}
+
+impl ExportFormat {
+    pub fn parse(name: &str) -> Option<ExportFormat> {
+        match name {
+            "csv" => Some(ExportFormat::Csv),
+            "jsonl" => Some(ExportFormat::JsonLines),
+            "columnar" => Some(ExportFormat::Columnar),
+            _ => None,
+        }
+    }
+
+    /// File extension for exports in this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::JsonLines => "jsonl",
+            ExportFormat::Columnar => "json",
+        }
+    }
+}
+
+/// Which table to export
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExportData {
+    Prices,
+    Candles(Vec<TimeFrame>),
+    Downsampled(Vec<TimeFrame>),  // Candles the cleanup built from old price refreshes
+}
+
+/// What to export: the data, symbols and time window, and the layout
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportRequest {
+    pub data: ExportData,
+    pub symbols: Vec<String>,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub format: ExportFormat,
+}
+
+/// Usage line for the `export` subcommand
+pub const EXPORT_USAGE: &str = "Usage: coinpeek export <prices|candles|downsampled> <SYMBOL,...> [--timeframes 1m,5m] \
+    [--from YYYY-MM-DD] [--to YYYY-MM-DD] [--format csv|jsonl|columnar] [--output FILE]";
+
+impl ExportRequest {
+    /// Parse the arguments of `coinpeek export`, returning the request and
+    /// the output file (stdout when absent). Dates are UTC and `--to` is
+    /// inclusive; the window defaults to everything up to `now`.
+    pub fn from_args(args: &[String], now: DateTime<Utc>) -> Result<(ExportRequest, Option<String>), String> {
+        let [data, symbols, options @ ..] = args else {
+            return Err(EXPORT_USAGE.to_string());
+        };
+
+        let mut timeframes = vec![TimeFrame::M5];
+        let mut start = DateTime::<Utc>::UNIX_EPOCH;
+        let mut end = now;
+        let mut format = ExportFormat::Csv;
+        let mut output = None;
+
+        let mut options = options.iter();
+        while let Some(flag) = options.next() {
+            let value = options.next().ok_or_else(|| format!("Missing value for {}", flag))?;
+            match flag.as_str() {
+                "--timeframes" => {
+                    timeframes = value
+                        .split(',')
+                        .map(|t| TimeFrame::parse(t).ok_or_else(|| format!("Invalid timeframe: {}", t)))
+                        .collect::<Result<_, _>>()?;
+                }
+                "--from" => start = parse_date(value)?.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc(),
+                "--to" => end = parse_date(value)?.and_hms_milli_opt(23, 59, 59, 999).unwrap_or_default().and_utc(),
+                "--format" => format = ExportFormat::parse(value).ok_or_else(|| format!("Invalid format: {}", value))?,
+                "--output" => output = Some(value.clone()),
+                _ => return Err(format!("Unknown option {}\n{}", flag, EXPORT_USAGE)),
+            }
+        }
+
+        let data = match data.as_str() {
+            "prices" => ExportData::Prices,
+            "candles" => ExportData::Candles(timeframes),
+            "downsampled" => ExportData::Downsampled(timeframes),
+            other => return Err(format!("Unknown data to export: {}\n{}", other, EXPORT_USAGE)),
+        };
+
+        let request = ExportRequest {
+            data,
+            symbols: symbols.split(',').map(|s| s.trim().to_uppercase()).filter(|s| !s.is_empty()).collect(),
+            start,
+            end,
+            format,
+        };
+        Ok((request, output))
+    }
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| format!("Invalid date: {}. Use YYYY-MM-DD", value))
+}
+
+/// Write the requested records to `out`, reading one symbol (and
+/// timeframe) at a time. Timestamps are Unix milliseconds and prices keep
+/// their exact decimal digits. Returns the number of records written.
+pub async fn export<W: Write>(db: &Database, request: &ExportRequest, out: W) -> Result<usize, CoinPeekError> {
+    let columns: &[&str] = match request.data {
+        ExportData::Prices => &["symbol", "timestamp", "price"],
+        ExportData::Candles(_) | ExportData::Downsampled(_) => &["symbol", "timeframe", "timestamp", "open", "high", "low", "close", "volume"],
+    };
+    let mut writer = RecordWriter::new(request.format, columns, out)?;
+
+    for symbol in &request.symbols {
+        match &request.data {
+            ExportData::Prices => {
+                for point in db.get_price_history(symbol, request.start, request.end).await? {
+                    writer.write(vec![
+                        Cell::Text(symbol.clone()),
+                        Cell::Integer(point.timestamp.timestamp_millis()),
+                        Cell::Decimal(point.price),
+                    ])?;
+                }
+            }
+            ExportData::Candles(timeframes) | ExportData::Downsampled(timeframes) => {
+                for timeframe in timeframes {
+                    let candles = match request.data {
+                        ExportData::Downsampled(_) => {
+                            db.get_downsampled_candle_range(symbol, timeframe.as_str(), request.start, request.end).await?
+                        }
+                        _ => db.get_candle_range(symbol, timeframe.as_str(), request.start, request.end).await?,
+                    };
+                    for candle in candles {
+                        writer.write(vec![
+                            Cell::Text(symbol.clone()),
+                            Cell::Text(timeframe.as_str().to_string()),
+                            Cell::Integer(candle.timestamp as i64),
+                            Cell::Decimal(candle.open),
+                            Cell::Decimal(candle.high),
+                            Cell::Decimal(candle.low),
+                            Cell::Decimal(candle.close),
+                            Cell::Float(candle.volume),
+                        ])?;
+                    }
+                }
+            }
+        }
+    }
+
+    writer.finish()
+}
+
+/// One exported value
+enum Cell {
+    Text(String),
+    Integer(i64),
+    Decimal(Decimal),
+    Float(f64),
+}
+
+impl Cell {
+    fn csv(&self) -> String {
+        match self {
+            Cell::Text(text) if text.contains([',', '"', '\n']) => format!("\"{}\"", text.replace('"', "\"\"")),
+            Cell::Text(text) => text.clone(),
+            Cell::Integer(value) => value.to_string(),
+            Cell::Decimal(value) => value.normalize().to_string(),
+            Cell::Float(value) => value.to_string(),
+        }
+    }
+
+    /// Numbers are written as JSON numbers, decimals with all their digits
+    fn json(&self) -> String {
+        match self {
+            Cell::Text(text) => serde_json::Value::from(text.as_str()).to_string(),
+            Cell::Integer(value) => value.to_string(),
+            Cell::Decimal(value) => value.normalize().to_string(),
+            Cell::Float(value) if value.is_finite() => value.to_string(),
+            Cell::Float(_) => "null".to_string(),
+        }
+    }
+}
+
+/// Writes records in the chosen format. Columnar output can only be written
+/// once every record is known, so those are collected until `finish`.
+struct RecordWriter<'a, W: Write> {
+    format: ExportFormat,
+    columns: &'a [&'a str],
+    out: W,
+    column_values: Vec<Vec<String>>,
+    records: usize,
+}
+
+impl<'a, W: Write> RecordWriter<'a, W> {
+    fn new(format: ExportFormat, columns: &'a [&'a str], mut out: W) -> Result<Self, CoinPeekError> {
+        if format == ExportFormat::Csv {
+            writeln!(out, "{}", columns.join(","))?;
+        }
+        Ok(Self { format, columns, out, column_values: vec![Vec::new(); columns.len()], records: 0 })
+    }
+
+    fn write(&mut self, cells: Vec<Cell>) -> Result<(), CoinPeekError> {
+        match self.format {
+            ExportFormat::Csv => {
+                let line: Vec<String> = cells.iter().map(Cell::csv).collect();
+                writeln!(self.out, "{}", line.join(","))?;
+            }
+            ExportFormat::JsonLines => {
+                let fields: Vec<String> = self.columns
+                    .iter()
+                    .zip(&cells)
+                    .map(|(column, cell)| format!("\"{}\":{}", column, cell.json()))
+                    .collect();
+                writeln!(self.out, "{{{}}}", fields.join(","))?;
+            }
+            ExportFormat::Columnar => {
+                for (values, cell) in self.column_values.iter_mut().zip(&cells) {
+                    values.push(cell.json());
+                }
+            }
+        }
+        self.records += 1;
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<usize, CoinPeekError> {
+        if self.format == ExportFormat::Columnar {
+            let fields: Vec<String> = self.columns
+                .iter()
+                .zip(&self.column_values)
+                .map(|(column, values)| format!("\"{}\":[{}]", column, values.join(",")))
+                .collect();
+            writeln!(self.out, "{{{}}}", fields.join(","))?;
+        }
+        self.out.flush()?;
+        Ok(self.records)
+    }
+}
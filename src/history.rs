@@ -0,0 +1,99 @@
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+
+/// A stored price observation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PricePoint {
+    pub timestamp: DateTime<Utc>,
+    pub price: Decimal,
+}
+
+/// Summary of a symbol's recorded prices over a time window
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceStats {
+    pub symbol: String,
+    pub samples: usize,
+    pub first: PricePoint,
+    pub last: PricePoint,
+    pub min: Decimal,
+    pub max: Decimal,
+    pub average: Decimal,
+    pub return_percent: f64,  // Change from the first to the last price
+}
+
+/// Prices of several symbols sampled on a shared time grid, e.g. for
+/// comparing them in one chart
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlignedSeries {
+    pub timestamps: Vec<DateTime<Utc>>,
+    pub series: Vec<SymbolSeries>,
+}
+
+/// One symbol's prices on an `AlignedSeries` grid. `None` where nothing had
+/// been recorded yet at that time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymbolSeries {
+    pub symbol: String,
+    pub prices: Vec<Option<Decimal>>,
+}
+
+/// Statistics over `points` (oldest first); `None` if there are none
+pub fn price_stats(symbol: &str, points: &[PricePoint]) -> Option<PriceStats> {
+    let first = *points.first()?;
+    let last = *points.last()?;
+    let min = points.iter().map(|p| p.price).min()?;
+    let max = points.iter().map(|p| p.price).max()?;
+    let total: Decimal = points.iter().map(|p| p.price).sum();
+    let return_percent = if first.price.is_zero() {
+        0.0
+    } else {
+        ((last.price - first.price) / first.price * Decimal::from(100)).to_f64().unwrap_or(0.0)
+    };
+
+    Some(PriceStats {
+        symbol: symbol.to_string(),
+        samples: points.len(),
+        first,
+        last,
+        min,
+        max,
+        average: total / Decimal::from(points.len()),
+        return_percent,
+    })
+}
+
+/// Times from `start` to `end` inclusive, `step` apart
+pub fn time_grid(start: DateTime<Utc>, end: DateTime<Utc>, step: Duration) -> Vec<DateTime<Utc>> {
+    if step <= Duration::zero() {
+        return Vec::new();
+    }
+    std::iter::successors(Some(start), |&t| Some(t + step))
+        .take_while(|&t| t <= end)
+        .collect()
+}
+
+/// The last known price at each grid time, carrying prices forward over
+/// gaps. `points` must be oldest first and may start before the grid.
+pub fn align_to_grid(points: &[PricePoint], grid: &[DateTime<Utc>]) -> Vec<Option<Decimal>> {
+    let mut next = 0;
+    let mut current = None;
+    grid.iter()
+        .map(|&t| {
+            while next < points.len() && points[next].timestamp <= t {
+                current = Some(points[next].price);
+                next += 1;
+            }
+            current
+        })
+        .collect()
+}
+
+/// Lowest and highest price from `start` on, counting the price already in
+/// effect at `start`. `points` must be oldest first. `None` if no price is
+/// known by the end of `points`.
+pub fn window_range(points: &[PricePoint], start: DateTime<Utc>) -> Option<(Decimal, Decimal)> {
+    let first = points.iter().rposition(|p| p.timestamp <= start).unwrap_or(0);
+    let prices = points.get(first..)?.iter().map(|p| p.price);
+    Some((prices.clone().min()?, prices.max()?))
+}
@@ -0,0 +1,317 @@
+use std::io::BufRead;
+use std::path::Path;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use rust_decimal::Decimal;
+
+use crate::binance::Candle;
+use crate::database::Database;
+use crate::error::CoinPeekError;
+use crate::timeframe::TimeFrame;
+
+/// Candles handed to `Database::store_candles` per batch
+const IMPORT_BATCH_SIZE: usize = 5_000;
+
+/// Row errors kept in an `ImportReport`; the rest are only counted
+const MAX_REPORTED_ROW_ERRORS: usize = 20;
+
+/// How the columns of an import file are laid out
+#[derive(Debug, Clone, PartialEq)]
+pub enum CsvLayout {
+    /// Kline files from data.binance.vision: open time, open, high, low,
+    /// close, volume, then columns we don't store. Newer files have a
+    /// header row and microsecond timestamps; both are handled.
+    BinanceKlines,
+    /// Any CSV with a header row, columns picked by name
+    Mapped(ColumnMapping),
+}
+
+/// Header names of the columns holding each candle field
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnMapping {
+    pub timestamp: String,
+    pub open: String,
+    pub high: String,
+    pub low: String,
+    pub close: String,
+    pub volume: Option<String>,  // Volume is stored as 0 without one
+}
+
+impl Default for ColumnMapping {
+    fn default() -> Self {
+        Self {
+            timestamp: "timestamp".to_string(),
+            open: "open".to_string(),
+            high: "high".to_string(),
+            low: "low".to_string(),
+            close: "close".to_string(),
+            volume: Some("volume".to_string()),
+        }
+    }
+}
+
+impl ColumnMapping {
+    /// Parse overrides like `timestamp=Date,close=Adj Close,volume=`, where
+    /// an empty volume means the file has none. Fields not mentioned keep
+    /// their default header name.
+    pub fn parse(spec: &str) -> Result<ColumnMapping, String> {
+        let mut mapping = ColumnMapping::default();
+        for pair in spec.split(',').filter(|pair| !pair.trim().is_empty()) {
+            let (field, column) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid column mapping: {}. Use field=Header", pair))?;
+            let column = column.trim().to_string();
+            match field.trim() {
+                "timestamp" => mapping.timestamp = column,
+                "open" => mapping.open = column,
+                "high" => mapping.high = column,
+                "low" => mapping.low = column,
+                "close" => mapping.close = column,
+                "volume" => mapping.volume = (!column.is_empty()).then_some(column),
+                other => return Err(format!("Unknown candle field in column mapping: {}", other)),
+            }
+        }
+        Ok(mapping)
+    }
+}
+
+/// A row that was skipped, with its 1-based line number
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowError {
+    pub line: usize,
+    pub reason: String,
+}
+
+/// Candles read from a file, oldest first with duplicates removed, and the
+/// rows that had to be skipped
+#[derive(Debug, Clone, Default)]
+pub struct ParsedCandles {
+    pub candles: Vec<Candle>,
+    pub errors: Vec<RowError>,
+}
+
+/// Read and validate candles from CSV. Rows that don't parse, aren't
+/// aligned to `timeframe`, or have impossible prices (high below low, a
+/// close outside the range, negative volume) are reported and skipped.
+/// A row repeating an earlier timestamp replaces it.
+pub fn parse_candles<R: BufRead>(reader: R, layout: &CsvLayout, timeframe: TimeFrame) -> Result<ParsedCandles, CoinPeekError> {
+    let mut parsed = ParsedCandles::default();
+    let mut columns: Option<[Option<usize>; 6]> = match layout {
+        CsvLayout::BinanceKlines => Some([Some(0), Some(1), Some(2), Some(3), Some(4), Some(5)]),
+        CsvLayout::Mapped(_) => None,
+    };
+
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line_number = index + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields = split_csv_line(&line);
+
+        let Some(positions) = columns else {
+            // Mapped files start with their header row
+            if let CsvLayout::Mapped(mapping) = layout {
+                columns = Some(header_positions(&fields, mapping)?);
+            }
+            continue;
+        };
+        if index == 0 && layout == &CsvLayout::BinanceKlines && fields[0].trim().parse::<u64>().is_err() {
+            continue; // Newer Binance dumps have a header row
+        }
+
+        match parse_row(&fields, &positions, timeframe) {
+            Ok(candle) => parsed.candles.push(candle),
+            Err(reason) => parsed.errors.push(RowError { line: line_number, reason }),
+        }
+    }
+
+    // Oldest first, keeping the last row written for each timestamp
+    parsed.candles.reverse();
+    parsed.candles.sort_by_key(|candle| candle.timestamp);
+    parsed.candles.dedup_by_key(|candle| candle.timestamp);
+    Ok(parsed)
+}
+
+/// Column index of each candle field, in the order timestamp, open, high,
+/// low, close, volume
+fn header_positions(header: &[String], mapping: &ColumnMapping) -> Result<[Option<usize>; 6], CoinPeekError> {
+    let find = |name: &str| {
+        header.iter().position(|column| column.trim().eq_ignore_ascii_case(name.trim())).ok_or_else(|| {
+            CoinPeekError::Decode(format!("column '{}' not found in header: {}", name, header.join(",")))
+        })
+    };
+
+    Ok([
+        Some(find(&mapping.timestamp)?),
+        Some(find(&mapping.open)?),
+        Some(find(&mapping.high)?),
+        Some(find(&mapping.low)?),
+        Some(find(&mapping.close)?),
+        mapping.volume.as_deref().map(find).transpose()?,
+    ])
+}
+
+fn parse_row(fields: &[String], positions: &[Option<usize>; 6], timeframe: TimeFrame) -> Result<Candle, String> {
+    let field = |position: Option<usize>, name: &str| -> Result<&str, String> {
+        position
+            .and_then(|i| fields.get(i))
+            .map(|value| value.trim())
+            .ok_or_else(|| format!("missing {}", name))
+    };
+    let price = |position: Option<usize>, name: &str| -> Result<Decimal, String> {
+        let value = field(position, name)?;
+        value.parse::<Decimal>()
+            .or_else(|_| Decimal::from_scientific(value))
+            .map_err(|_| format!("invalid {} '{}'", name, value))
+    };
+
+    let timestamp = parse_timestamp(field(positions[0], "timestamp")?)?;
+    let open = price(positions[1], "open")?;
+    let high = price(positions[2], "high")?;
+    let low = price(positions[3], "low")?;
+    let close = price(positions[4], "close")?;
+    let volume = match positions[5] {
+        Some(_) => {
+            let value = field(positions[5], "volume")?;
+            value.parse::<f64>().map_err(|_| format!("invalid volume '{}'", value))?
+        }
+        None => 0.0,
+    };
+
+    if timeframe.bar_start(timestamp) != timestamp {
+        return Err(format!("timestamp {} is not on a {} boundary", timestamp, timeframe.as_str()));
+    }
+    if low <= Decimal::ZERO {
+        return Err(format!("low {} is not positive", low));
+    }
+    if high < low || open < low || open > high || close < low || close > high {
+        return Err(format!("prices outside the high/low range (o {} h {} l {} c {})", open, high, low, close));
+    }
+    if !volume.is_finite() || volume < 0.0 {
+        return Err(format!("invalid volume {}", volume));
+    }
+
+    Ok(Candle { open, high, low, close, volume, timestamp })
+}
+
+/// Unix time in seconds, milliseconds or microseconds (told apart by size),
+/// or an ISO 8601 date or date-time in UTC. Returns milliseconds.
+fn parse_timestamp(value: &str) -> Result<u64, String> {
+    if let Ok(number) = value.parse::<u64>() {
+        return Ok(match number {
+            n if n < 100_000_000_000 => n * 1000,     // seconds
+            n if n < 100_000_000_000_000 => n,        // milliseconds
+            n => n / 1000,                            // microseconds
+        });
+    }
+
+    let parsed = DateTime::parse_from_rfc3339(value)
+        .map(|t| t.with_timezone(&Utc))
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").map(|t| t.and_utc()))
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S").map(|t| t.and_utc()))
+        .or_else(|_| NaiveDate::parse_from_str(value, "%Y-%m-%d").map(|d| d.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc()))
+        .map_err(|_| format!("invalid timestamp '{}'", value))?;
+    u64::try_from(parsed.timestamp_millis()).map_err(|_| format!("timestamp '{}' is before 1970", value))
+}
+
+/// Split one CSV line, honouring double-quoted fields
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// Symbol and interval from a data.binance.vision file name such as
+/// `BTCUSDT-1m-2024-01.csv`
+pub fn binance_file_info(path: &Path) -> Option<(String, TimeFrame)> {
+    let name = path.file_stem()?.to_str()?;
+    let mut parts = name.split('-');
+    let symbol = parts.next()?.to_uppercase();
+    let timeframe = TimeFrame::parse(parts.next()?)?;
+    Some((symbol, timeframe))
+}
+
+/// Progress of a running import, reported after every stored batch
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportProgress {
+    pub stored: usize,
+    pub total: usize,
+}
+
+/// Outcome of importing one file
+#[derive(Debug, Clone, Default)]
+pub struct ImportReport {
+    pub stored: usize,              // Candles written, new or replacing stored ones
+    pub skipped: usize,             // Rows that failed validation
+    pub errors: Vec<RowError>,      // The first few skipped rows and why
+    pub first: Option<u64>,         // Open time of the oldest imported candle
+    pub last: Option<u64>,          // Open time of the newest imported candle
+}
+
+/// Validate `path` and load its candles through `Database::store_candles`,
+/// which replaces candles already stored for the same bars. Exchange dumps
+/// are complete, so for `CsvLayout::BinanceKlines` the imported range is also
+/// recorded as covered and won't be downloaded again. When rows had to be
+/// skipped only the unbroken runs of bars around them count as covered, so
+/// the bars that were dropped can still be downloaded.
+pub async fn import_file(
+    db: &Database,
+    path: &Path,
+    symbol: &str,
+    timeframe: TimeFrame,
+    layout: &CsvLayout,
+    progress: &mut dyn FnMut(&ImportProgress),
+) -> Result<ImportReport, CoinPeekError> {
+    let file = std::fs::File::open(path).map_err(|e| CoinPeekError::Io(format!("{}: {}", path.display(), e)))?;
+    let parsed = parse_candles(std::io::BufReader::new(file), layout, timeframe)?;
+
+    let mut report = ImportReport {
+        skipped: parsed.errors.len(),
+        errors: parsed.errors.into_iter().take(MAX_REPORTED_ROW_ERRORS).collect(),
+        first: parsed.candles.first().map(|c| c.timestamp),
+        last: parsed.candles.last().map(|c| c.timestamp),
+        ..ImportReport::default()
+    };
+
+    for batch in parsed.candles.chunks(IMPORT_BATCH_SIZE) {
+        db.store_candles(symbol, timeframe.as_str(), batch).await?;
+        report.stored += batch.len();
+        progress(&ImportProgress { stored: report.stored, total: parsed.candles.len() });
+    }
+
+    if layout == &CsvLayout::BinanceKlines {
+        // A dump of the current day or month may end with a bar still open
+        let closed_end = timeframe.bar_start(Utc::now().timestamp_millis().max(0) as u64).saturating_sub(1);
+        let runs: Vec<&[Candle]> = if report.skipped == 0 {
+            vec![parsed.candles.as_slice()]
+        } else {
+            parsed.candles.chunk_by(|a, b| b.timestamp == a.timestamp + timeframe.duration_ms()).collect()
+        };
+        for run in runs {
+            let (Some(first), Some(last)) = (run.first(), run.last()) else {
+                continue;
+            };
+            let end = (last.timestamp + timeframe.duration_ms() - 1).min(closed_end);
+            if end >= first.timestamp {
+                db.record_candle_coverage(symbol, timeframe.as_str(), first.timestamp, end).await?;
+            }
+        }
+    }
+
+    Ok(report)
+}
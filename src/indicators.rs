@@ -0,0 +1,79 @@
+use crate::binance::Candle;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+
+/// Bollinger bands at the last candle
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bands {
+    pub lower: Decimal,
+    pub middle: Decimal,  // Moving average of the closes
+    pub upper: Decimal,
+}
+
+/// Simple moving average of the last `period` closes. `candles` must be
+/// oldest first; `None` if there are fewer than `period`.
+pub fn sma(candles: &[Candle], period: usize) -> Option<Decimal> {
+    let recent = last(candles, period)?;
+    let total: Decimal = recent.iter().map(|c| c.close).sum();
+    Some(total / Decimal::from(period))
+}
+
+/// Relative strength index of the closes, from 0 to 100, using Wilder's
+/// smoothing. It needs `period + 1` closes and settles as more are given.
+pub fn rsi(candles: &[Candle], period: usize) -> Option<f64> {
+    if period == 0 || candles.len() <= period {
+        return None;
+    }
+    let changes: Vec<f64> = candles
+        .windows(2)
+        .map(|pair| (pair[1].close - pair[0].close).to_f64().unwrap_or(0.0))
+        .collect();
+
+    let (first, rest) = changes.split_at(period);
+    let mut gain = first.iter().map(|c| c.max(0.0)).sum::<f64>() / period as f64;
+    let mut loss = first.iter().map(|c| (-c).max(0.0)).sum::<f64>() / period as f64;
+    for change in rest {
+        gain = (gain * (period - 1) as f64 + change.max(0.0)) / period as f64;
+        loss = (loss * (period - 1) as f64 + (-change).max(0.0)) / period as f64;
+    }
+
+    Some(match (gain, loss) {
+        (0.0, 0.0) => 50.0,
+        (_, 0.0) => 100.0,
+        _ => 100.0 - 100.0 / (1.0 + gain / loss),
+    })
+}
+
+/// Bands `std_devs` standard deviations either side of the `period` close
+/// moving average; `None` with fewer than `period` candles
+pub fn bollinger(candles: &[Candle], period: usize, std_devs: f64) -> Option<Bands> {
+    let recent = last(candles, period)?;
+    let middle = sma(candles, period)?;
+    let mean = middle.to_f64()?;
+    let variance = recent
+        .iter()
+        .map(|c| (c.close.to_f64().unwrap_or(mean) - mean).powi(2))
+        .sum::<f64>()
+        / period as f64;
+    let offset = Decimal::from_f64(variance.sqrt() * std_devs)?;
+    Some(Bands { lower: middle - offset, middle, upper: middle + offset })
+}
+
+/// Volume of the last candle as a multiple of the average volume of the
+/// `period` candles before it. `None` without enough candles or if they
+/// saw no volume. A bar still forming reads low against full ones, so pass
+/// closed candles only.
+pub fn volume_ratio(candles: &[Candle], period: usize) -> Option<f64> {
+    let (latest, earlier) = candles.split_last()?;
+    let earlier = last(earlier, period)?;
+    let average = earlier.iter().map(|c| c.volume).sum::<f64>() / period as f64;
+    (average > 0.0).then(|| latest.volume / average)
+}
+
+/// The last `period` candles, if there are that many
+fn last(candles: &[Candle], period: usize) -> Option<&[Candle]> {
+    if period == 0 || candles.len() < period {
+        return None;
+    }
+    Some(&candles[candles.len() - period..])
+}
@@ -1,12 +1,31 @@
+pub mod aggregate;
 pub mod app;
 pub mod binance;
 pub mod config;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod database;
+pub mod error;
+pub mod exchange;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod export;
+pub mod history;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod import;
+pub mod indicators;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod input;
 #[cfg(not(target_arch = "wasm32"))]
+pub mod migrations;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod notify;
+pub mod rate_limit;
+pub mod rules;
+pub mod symbols;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod sync;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod theme;
+pub mod timeframe;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod ui;
 pub mod utils;
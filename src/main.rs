@@ -1,9 +1,23 @@
+mod aggregate;
 mod app;
 mod binance;
 mod config;
 mod database;
+mod error;
+mod exchange;
+mod export;
+mod history;
+mod import;
+mod indicators;
 mod input;
+mod migrations;
+mod notify;
+mod rate_limit;
+mod rules;
+mod symbols;
+mod sync;
 mod theme;
+mod timeframe;
 mod ui;
 mod utils;
 
@@ -18,8 +32,33 @@ use crossterm::{
 };
 use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+use exchange::MarketDataSource;
+use config::ChartSource;
+use timeframe::TimeFrame;
+
+/// Interval and number of candles drawn in the detail chart
+const CHART_TIMEFRAME: TimeFrame = TimeFrame::M5;
+const CHART_CANDLES: usize = 50;
+
+/// Wait before the first retention cleanup after launch
+const CLEANUP_STARTUP_DELAY: Duration = Duration::from_secs(60);
+
+/// Where background tasks send failures for the error panel, as the message
+/// to show and the error behind it
+type BackgroundErrors = tokio::sync::mpsc::UnboundedSender<(String, error::CoinPeekError)>;
+
+/// Candles a background load finished with
+enum CandleLoad {
+    Chart { symbol: String, candles: Vec<binance::Candle> },
+    Indicator { symbol: String, timeframe: TimeFrame, candles: Option<Vec<binance::Candle>> },
+}
+
+/// Where background candle loads send what they found
+type CandleLoads = tokio::sync::mpsc::UnboundedSender<CandleLoad>;
+
 /// Handle mouse click events for cryptocurrency selection
 fn handle_mouse_click(app: &mut app::App, mouse_event: MouseEvent) {
     // Only handle left mouse button down events
@@ -79,8 +118,24 @@ async fn main() -> Result<(), Box<dyn Error>> {
     // Load configuration
     let config = config::Config::load()?;
 
+    let source = binance::BinanceClient::new(&config.api)?;
+
+    // `coinpeek backfill <SYMBOL> <INTERVAL> <DAYS>` downloads history without starting the UI
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("backfill") {
+        return run_backfill(&source, &args[1..]).await;
+    }
+    // `coinpeek export ...` writes stored data for notebooks and spreadsheets
+    if args.first().map(String::as_str) == Some("export") {
+        return run_export(&args[1..]).await;
+    }
+    // `coinpeek import <FILE>...` preloads candle archives
+    if args.first().map(String::as_str) == Some("import") {
+        return run_import(&args[1..]).await;
+    }
+
     let mut terminal = init_terminal()?;
-    let result = run_loop(&mut terminal, config).await;
+    let result = run_loop(&mut terminal, config, &source).await;
     cleanup_terminal(&mut terminal)?;
 
     if let Err(err) = result {
@@ -90,6 +145,226 @@ async fn main() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+/// Store the last `DAYS` days of candles for a symbol in the database
+async fn run_backfill<S: MarketDataSource>(source: &S, args: &[String]) -> Result<(), Box<dyn Error>> {
+    let [symbol, interval, days] = args else {
+        return Err("Usage: coinpeek backfill <SYMBOL> <INTERVAL> <DAYS>, e.g. coinpeek backfill BTCUSDT 1h 90".into());
+    };
+    let timeframe = TimeFrame::parse(interval).ok_or_else(|| format!("Invalid interval: {}", interval))?;
+    let days: i64 = days.parse().map_err(|_| format!("Invalid number of days: {}", days))?;
+
+    let db = database::Database::new("coinpeek.db").await?;
+    let end = chrono::Utc::now();
+    let start = end - chrono::Duration::days(days);
+
+    println!("Backfilling {} {} candles from {} ...", symbol, interval, start.format("%Y-%m-%d"));
+    let stored = sync::backfill_candles(source, &db, symbol, timeframe, start, end).await?;
+    println!("Stored {} candles", stored);
+    Ok(())
+}
+
+/// Prune old data in the background every `cleanup_interval_minutes`,
+/// starting shortly after launch so it doesn't hold up the first refresh.
+/// Failed runs are sent through `errors`.
+fn spawn_retention_cleanup(db: database::Database, retention: config::RetentionConfig, errors: BackgroundErrors) {
+    let period = Duration::from_secs(retention.cleanup_interval_minutes * 60);
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + CLEANUP_STARTUP_DELAY, period);
+        loop {
+            ticker.tick().await;
+            match db.cleanup_old_data(&retention, chrono::Utc::now()).await {
+                Ok(report) => log::info!(
+                    "Retention cleanup pruned {} prices ({} candles kept from them) and {} candles, reclaimed {} bytes",
+                    report.prices_pruned, report.downsampled_candles, report.candles_pruned, report.bytes_reclaimed
+                ),
+                Err(e) => {
+                    if errors.send(("Retention cleanup failed".to_string(), e)).is_err() {
+                        return; // The app has shut down
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Load the detail chart for `symbol` from the selected chart source.
+/// Exchange candles may have to be downloaded first, so they load in a
+/// background task and come back through `loads`; a slow or rate-limited
+/// exchange can't freeze the dashboard.
+async fn load_chart_candles(
+    source: &binance::BinanceClient,
+    db: &database::Database,
+    app: &mut app::App,
+    symbol: &str,
+    loads: &CandleLoads,
+    errors: &BackgroundErrors,
+) {
+    match app.chart_source {
+        ChartSource::Exchange => {
+            app.chart_loading = Some(symbol.to_string());
+            let (source, db, symbol) = (source.clone(), db.clone(), symbol.to_string());
+            let (loads, errors) = (loads.clone(), errors.clone());
+            tokio::spawn(async move {
+                // Download whatever the cache is missing, then chart from the cache
+                if let Err(e) = sync::fill_candle_gaps(&source, &db, &symbol, CHART_TIMEFRAME, CHART_CANDLES, chrono::Utc::now()).await {
+                    let _ = errors.send((format!("Failed to update candles for {}", symbol), e));
+                }
+
+                let candles = match db.get_candles_with_rollup(&symbol, CHART_TIMEFRAME, CHART_CANDLES).await {
+                    Ok(candles) if !candles.is_empty() => candles,
+                    // Fallback to API if the database can't serve them
+                    _ => source.fetch_candles(&symbol, CHART_TIMEFRAME.as_str(), CHART_CANDLES as u8).await.unwrap_or_default(),
+                };
+                let _ = loads.send(CandleLoad::Chart { symbol, candles });
+            });
+        }
+        ChartSource::Ticks => {
+            match db.get_tick_candles(symbol, app.config.chart.tick_bar_seconds, CHART_CANDLES).await {
+                Ok(candles) => app.update_candles_for_selected(candles),
+                Err(e) => app.report_error(format!("Failed to load tick bars for {}", symbol), &e),
+            }
+        }
+    }
+}
+
+/// Bring the candles indicator alerts are computed from up to date, going
+/// through the database cache like the chart does. Each due series loads in
+/// a background task and comes back through `loads`.
+fn load_indicator_candles(
+    source: &binance::BinanceClient,
+    db: &database::Database,
+    app: &mut app::App,
+    loads: &CandleLoads,
+    errors: &BackgroundErrors,
+) {
+    for (symbol, timeframe, count) in app.take_due_indicator_series(chrono::Utc::now()) {
+        let (source, db) = (source.clone(), db.clone());
+        let (loads, errors) = (loads.clone(), errors.clone());
+        tokio::spawn(async move {
+            if let Err(e) = sync::fill_candle_gaps(&source, &db, &symbol, timeframe, count, chrono::Utc::now()).await {
+                let _ = errors.send((format!("Failed to update {} candles for {}", timeframe.as_str(), symbol), e));
+            }
+            let candles = match db.get_candles_with_rollup(&symbol, timeframe, count).await {
+                Ok(candles) => Some(candles),
+                Err(e) => {
+                    let _ = errors.send((format!("Failed to load {} candles for {}", timeframe.as_str(), symbol), e));
+                    None
+                }
+            };
+            let _ = loads.send(CandleLoad::Indicator { symbol, timeframe, candles });
+        });
+    }
+}
+
+/// Write stored prices or candles to `--output`, or stdout without it
+async fn run_export(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let (request, output) = export::ExportRequest::from_args(args, chrono::Utc::now())?;
+    let db = database::Database::new("coinpeek.db").await?;
+
+    let exported = match &output {
+        Some(path) => export::export(&db, &request, io::BufWriter::new(std::fs::File::create(path)?)).await?,
+        None => export::export(&db, &request, io::BufWriter::new(io::stdout())).await?,
+    };
+    // stderr, so piping the data elsewhere stays clean
+    eprintln!("Exported {} records", exported);
+    Ok(())
+}
+
+/// Load candle CSV files into the database. Binance dump file names give
+/// the symbol and interval; `--symbol` and `--timeframe` override them, and
+/// `--columns` switches to a generic CSV with a header row.
+async fn run_import(args: &[String]) -> Result<(), Box<dyn Error>> {
+    const USAGE: &str = "Usage: coinpeek import <FILE>... [--symbol BTCUSDT] [--timeframe 1m] \
+        [--columns timestamp=Date,open=Open,high=High,low=Low,close=Close,volume=Volume]";
+
+    let mut files = Vec::new();
+    let mut symbol = None;
+    let mut timeframe = None;
+    let mut layout = import::CsvLayout::BinanceKlines;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--symbol" | "--timeframe" | "--columns" => {
+                let value = args.next().ok_or_else(|| format!("Missing value for {}", arg))?;
+                match arg.as_str() {
+                    "--symbol" => symbol = Some(value.to_uppercase()),
+                    "--timeframe" => {
+                        timeframe = Some(TimeFrame::parse(value).ok_or_else(|| format!("Invalid interval: {}", value))?)
+                    }
+                    _ => layout = import::CsvLayout::Mapped(import::ColumnMapping::parse(value)?),
+                }
+            }
+            flag if flag.starts_with("--") => return Err(format!("Unknown option {}\n{}", flag, USAGE).into()),
+            file => files.push(std::path::PathBuf::from(file)),
+        }
+    }
+    if files.is_empty() {
+        return Err(USAGE.into());
+    }
+
+    let db = database::Database::new("coinpeek.db").await?;
+    for path in &files {
+        let from_name = import::binance_file_info(path);
+        let (Some(symbol), Some(timeframe)) = (
+            symbol.clone().or_else(|| from_name.as_ref().map(|(s, _)| s.clone())),
+            timeframe.or_else(|| from_name.as_ref().map(|(_, t)| *t)),
+        ) else {
+            return Err(format!("Can't tell the symbol and interval of {}; pass --symbol and --timeframe", path.display()).into());
+        };
+
+        let mut progress = |p: &import::ImportProgress| eprint!("\r{}: {}/{} candles", path.display(), p.stored, p.total);
+        let report = import::import_file(&db, path, &symbol, timeframe, &layout, &mut progress).await?;
+        eprintln!("\r{}: stored {} {} {} candles, skipped {} rows", path.display(), report.stored, symbol, timeframe.as_str(), report.skipped);
+        for error in &report.errors {
+            eprintln!("  line {}: {}", error.line, error.reason);
+        }
+    }
+    Ok(())
+}
+
+/// Write the selected symbol's stored candles over the detail chart's
+/// timeframe and window to a CSV file in the working directory. The file is
+/// written on a blocking thread so the dashboard keeps redrawing.
+async fn export_selected_candles(db: &database::Database, app: &mut app::App) {
+    let Some(symbol) = app.get_selected_symbol().map(|selected| selected.symbol.clone()) else {
+        return;
+    };
+
+    let now = chrono::Utc::now();
+    let window = chrono::Duration::milliseconds((CHART_TIMEFRAME.duration_ms() * CHART_CANDLES as u64) as i64);
+    let request = export::ExportRequest {
+        data: export::ExportData::Candles(vec![CHART_TIMEFRAME]),
+        symbols: vec![symbol.clone()],
+        start: now - window,
+        end: now,
+        format: export::ExportFormat::Csv,
+    };
+    let path = format!("coinpeek-{}-{}.{}", symbol, now.format("%Y%m%d-%H%M%S"), request.format.extension());
+
+    let mut contents = Vec::new();
+    let result = match export::export(db, &request, &mut contents).await {
+        Ok(exported) => {
+            let target = path.clone();
+            match tokio::task::spawn_blocking(move || std::fs::write(target, contents)).await {
+                Ok(Ok(())) => Ok(exported),
+                Ok(Err(e)) => Err(e.into()),
+                Err(e) => Err(error::CoinPeekError::Io(e.to_string())),
+            }
+        }
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(exported) => app.set_status_message(format!(
+            "Exported {} {} {} candles to {}",
+            exported,
+            symbol,
+            CHART_TIMEFRAME.as_str(),
+            path
+        )),
+        Err(e) => app.report_error(format!("Failed to export {}", symbol), &e),
+    }
+}
+
 /// Initializes the terminal in raw mode with alternate screen and mouse capture
 fn init_terminal() -> Result<Terminal<CrosstermBackend<io::Stdout>>, Box<dyn Error>> {
     enable_raw_mode()?;
@@ -114,18 +389,88 @@ fn cleanup_terminal(
     Ok(())
 }
 
+/// Load the cached symbol metadata, refetching it from the exchange once it
+/// is a day old. Falls back to the stale copy if the exchange can't be reached.
+async fn load_symbol_registry<S: MarketDataSource>(
+    db: &database::Database,
+    source: &S,
+    app: &mut app::App,
+) -> symbols::SymbolRegistry {
+    let cached = match db.load_symbol_registry().await {
+        Ok(registry) => registry,
+        Err(e) => {
+            app.report_error("Failed to load cached symbol metadata".to_string(), &e);
+            symbols::SymbolRegistry::default()
+        }
+    };
+
+    let now = chrono::Utc::now();
+    if !cached.is_stale(now) {
+        return cached;
+    }
+
+    match source.fetch_symbols().await {
+        Ok(symbols) => {
+            if let Err(e) = db.store_symbols(&symbols, now).await {
+                app.report_error("Failed to cache symbol metadata".to_string(), &e);
+            }
+            symbols::SymbolRegistry::new(symbols, now)
+        }
+        Err(e) => {
+            app.report_error("Failed to refresh symbol metadata".to_string(), &e);
+            cached
+        }
+    }
+}
+
 /// Main application loop
 async fn run_loop<B: ratatui::backend::Backend>(
     terminal: &mut Terminal<B>,
-    config: config::Config,
+    mut config: config::Config,
+    source: &binance::BinanceClient,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // Initialize database
     let db = database::Database::new("coinpeek.db").await?;
     println!("Database initialized successfully");
+    let (background_errors, mut background_error_rx) = tokio::sync::mpsc::unbounded_channel();
+    let (candle_loads, mut candle_load_rx) = tokio::sync::mpsc::unbounded_channel();
+    spawn_retention_cleanup(db.clone(), config.retention.clone(), background_errors.clone());
+    let notifiers = Arc::new(notify::Notifiers::from_config(&config.notifications)?);
 
-    let symbols: Vec<&str> = config.symbols.iter().map(|s| s.as_str()).collect();
     let mut app = app::App::new(config.clone());
 
+    // Exchange metadata decides which configured symbols are usable; the
+    // others are left out with a warning
+    let registry = load_symbol_registry(&db, source, &mut app).await;
+    let dropped = config.retain_usable_symbols(&registry).map_err(|e| format!("{}. Please fix coinpeek.json", e))?;
+    for e in &dropped {
+        let symbol = match e {
+            error::CoinPeekError::InvalidSymbol { symbol, .. } => symbol.as_str(),
+            _ => "a symbol",
+        };
+        app.report_error(format!("Leaving out {} from coinpeek.json", symbol), e);
+    }
+    app.config.symbols = config.symbols.clone();
+    app.set_symbol_registry(registry);
+    let symbols: Vec<&str> = config.symbols.iter().map(|s| s.as_str()).collect();
+
+    // Recent recorded prices, so windowed alerts can look back past startup
+    let now = chrono::Utc::now();
+    let history_start = now - chrono::Duration::minutes(app::MAX_ALERT_WINDOW_MINUTES as i64);
+    for symbol in &symbols {
+        let seed = match db.get_price_at(symbol, history_start).await {
+            Ok(seed) => seed,
+            Err(e) => {
+                app.report_error(format!("Failed to load price history for {}", symbol), &e);
+                continue;
+            }
+        };
+        match db.get_price_history(symbol, history_start, now).await {
+            Ok(points) => app.load_price_history(symbol, seed.into_iter().chain(points).collect()),
+            Err(e) => app.report_error(format!("Failed to load price history for {}", symbol), &e),
+        }
+    }
+
     // Try to load cached price data first
     let mut cached_prices = Vec::new();
     for symbol in &symbols {
@@ -139,34 +484,63 @@ async fn run_loop<B: ratatui::backend::Backend>(
         app.update_prices(cached_prices);
     }
 
-    // Create some sample alerts for demonstration
-    app.create_alert("BTCUSDT".to_string(), crate::app::AlertCondition::PriceAbove(55000.0), Some("BTC breaking resistance!".to_string()));
-    app.create_alert("ETHUSDT".to_string(), crate::app::AlertCondition::PercentChangeAbove(5.0), Some("ETH pumping!".to_string()));
-    app.create_alert("ADAUSDT".to_string(), crate::app::AlertCondition::PriceBelow(0.4), Some("ADA dip opportunity".to_string()));
+    // Alerts saved in earlier sessions
+    match db.get_alerts().await {
+        Ok(alerts) => app.load_alerts(alerts),
+        Err(e) => app.report_error("Failed to load saved alerts".to_string(), &e),
+    }
+    // Rules from coinpeek.json join the saved alerts the first time they're seen
+    // Without the record of earlier imports they'd all be created again
+    let imports = match db.get_imported_alert_rules().await {
+        Ok(rules) => {
+            app.load_imported_rules(rules);
+            app.add_configured_alerts()
+        }
+        Err(e) => {
+            app.report_error("Failed to load imported alert rules".to_string(), &e);
+            Vec::new()
+        }
+    };
+    for (rule, id) in imports {
+        if let Some(id) = id {
+            save_alert(&db, &mut app, id).await;
+        }
+        if let Err(e) = db.record_alert_rule_import(&rule).await {
+            app.report_error("Failed to record imported alert rule".to_string(), &e);
+        }
+    }
+    load_indicator_candles(source, &db, &mut app, &candle_loads, &background_errors);
 
     // Initial API fetch for fresh data
-    if let Ok(price_infos) = binance::fetch_price_infos(&symbols).await {
-        // Store in database
-        if let Err(e) = db.store_price_infos(&price_infos).await {
-            app.add_database_error(
-                "Failed to store initial price data".to_string(),
-                Some(format!("Database error: {}", e)),
-            );
+    match app.refresh_prices(source).await {
+        Ok(price_infos) => {
+            // Store in database
+            if let Err(e) = db.store_price_infos(&price_infos).await {
+                app.report_error("Failed to store initial price data".to_string(), &e);
+            }
         }
-        app.record_successful_sync();
-        app.update_prices(price_infos);
-    } else {
-        app.record_sync_failure();
-        app.add_api_error(
-            "Failed to fetch initial price data".to_string(),
-            Some("Check your internet connection and try again".to_string()),
-        );
+        Err(e) => app.report_error("Failed to fetch initial price data".to_string(), &e),
     }
 
+    // Live ticker stream; REST polling takes over whenever it is down
+    let mut ticker_stream = if config.api.enable_streaming {
+        match source.subscribe_tickers(&config.symbols) {
+            Ok(stream) => Some(stream),
+            Err(e) => {
+                app.report_error("Live price stream unavailable, falling back to polling".to_string(), &e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
     let mut last_tick = Instant::now();
     let tick_rate = Duration::from_secs(config.refresh_interval_seconds);
 
     loop {
+        let mut prices_refreshed = false;
+
         terminal.draw(|f| {
             let size = f.area();
             ui::render_dashboard(f, size, &app);
@@ -188,10 +562,7 @@ async fn run_loop<B: ratatui::backend::Backend>(
 
                 // Handle alert management mode
                 if app.show_alert_management {
-                    match key.code {
-                        KeyCode::Esc => app.show_alert_management = false,
-                        _ => {} // Ignore other keys in alert management mode for now
-                    }
+                    handle_alert_key(&db, &mut app, key.code).await;
                     continue;
                 }
 
@@ -211,6 +582,8 @@ async fn run_loop<B: ratatui::backend::Backend>(
                     KeyCode::Char('c') => app.clear_all_filters(),
                     KeyCode::Char('o') => app.toggle_offline_mode(),
                     KeyCode::Char('p') => app.toggle_pause(),
+                    KeyCode::Char('b') => app.next_chart_source(),
+                    KeyCode::Char('e') => export_selected_candles(&db, &mut app).await,
                     KeyCode::Char('/') => app.enter_search_mode(),
                     KeyCode::Char('a') if key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL) => {
                         // Ctrl+A for alert management
@@ -219,22 +592,17 @@ async fn run_loop<B: ratatui::backend::Backend>(
                     KeyCode::Char('?') => app.toggle_help(),
                     KeyCode::Char('r') => {
                         // Manual refresh
-                        if let Ok(price_infos) = binance::fetch_price_infos(&symbols).await {
-                            // Store in database
-                            if let Err(e) = db.store_price_infos(&price_infos).await {
-                                app.add_database_error(
-                                    "Failed to store manually refreshed data".to_string(),
-                                    Some(format!("Database error: {}", e)),
-                                );
+                        match app.refresh_prices(source).await {
+                            Ok(price_infos) => {
+                                // Store in database
+                                if let Err(e) = db.store_price_infos(&price_infos).await {
+                                    app.report_error("Failed to store manually refreshed data".to_string(), &e);
+                                }
                             }
-                            app.record_successful_sync();
-                            app.update_prices(price_infos);
-                        } else {
-                            app.record_sync_failure();
-                            app.add_api_error(
-                                "Manual refresh failed".to_string(),
-                                Some("Press 'r' to retry or check your internet connection".to_string()),
-                            );
+                            Err(e) if !app.is_throttled() => {
+                                app.report_error("Manual refresh failed".to_string(), &e);
+                            }
+                            Err(_) => {}
                         }
                     }
                     _ => {}
@@ -247,55 +615,173 @@ async fn run_loop<B: ratatui::backend::Backend>(
             }
         }
 
-        if !app.paused && last_tick.elapsed() >= tick_rate {
-            if let Ok(price_infos) = binance::fetch_price_infos(&symbols).await {
-                // Store in database
-                if let Err(e) = db.store_price_infos(&price_infos).await {
-                    app.add_database_error(
-                        "Failed to store updated price data".to_string(),
-                        Some(format!("Database error: {}", e)),
-                    );
+        // Failures from background tasks go to the error panel
+        while let Ok((message, e)) = background_error_rx.try_recv() {
+            app.report_error(message, &e);
+        }
+
+        // Candles loaded in the background
+        while let Ok(load) = candle_load_rx.try_recv() {
+            match load {
+                CandleLoad::Chart { symbol, candles } => app.finish_chart_load(&symbol, candles),
+                CandleLoad::Indicator { symbol, timeframe, candles } => {
+                    app.finish_indicator_load(&symbol, timeframe, candles)
                 }
-                app.record_successful_sync();
-                app.update_prices(price_infos);
-            } else {
-                app.record_sync_failure();
-                app.add_api_error(
-                    "Failed to refresh price data".to_string(),
-                    Some("Automatic updates will retry - check connection if persistent".to_string()),
-                );
             }
-            last_tick = Instant::now();
         }
 
-        // Fetch candle data for selected symbol if needed
-        if let Some(symbol) = app.should_fetch_candles() {
-            // Try to load from cache first
-            if let Ok(cached_candles) = db.get_candles(&symbol, "5m", 50).await {
-                if !cached_candles.is_empty() {
-                    app.update_candles_for_selected(cached_candles);
-                } else {
-                    // Fetch from API if not in cache
-                    if let Ok(candles) = binance::fetch_candles(&symbol, "5m", 50).await {
-                        // Store in database
-                        if let Err(e) = db.store_candles(&symbol, "5m", &candles).await {
-                            eprintln!("Failed to store candles: {}", e);
-                        }
-                        app.update_candles_for_selected(candles);
+        // Drain live ticker updates
+        if let Some(stream) = ticker_stream.as_mut() {
+            let mut updates = Vec::new();
+            while let Ok(Some(event)) = stream.try_next() {
+                match event {
+                    exchange::StreamEvent::Connected => app.set_stream_connected(true),
+                    exchange::StreamEvent::Disconnected(_) => app.set_stream_connected(false),
+                    exchange::StreamEvent::Ticker(price_info) => updates.push(price_info),
+                    exchange::StreamEvent::Skipped(e) => {
+                        app.report_error("Skipped an unreadable ticker stream message".to_string(), &e);
                     }
                 }
-            } else {
-                // Fallback to API if database query fails
-                if let Ok(candles) = binance::fetch_candles(&symbol, "5m", 50).await {
+            }
+
+            if !app.paused && !updates.is_empty() {
+                app.record_successful_sync();
+                app.apply_price_updates(updates);
+            }
+        }
+
+        if !app.paused && last_tick.elapsed() >= tick_rate && app.data_status.streaming {
+            // Stream keeps prices fresh; just snapshot them at the refresh interval
+            if let Err(e) = db.store_price_infos(&app.all_price_infos).await {
+                app.report_error("Failed to store streamed price data".to_string(), &e);
+            }
+            last_tick = Instant::now();
+            prices_refreshed = true;
+        } else if !app.paused && last_tick.elapsed() >= tick_rate {
+            match app.refresh_prices(source).await {
+                Ok(price_infos) => {
                     // Store in database
-                    if let Err(e) = db.store_candles(&symbol, "5m", &candles).await {
-                        eprintln!("Failed to store candles: {}", e);
+                    if let Err(e) = db.store_price_infos(&price_infos).await {
+                        app.report_error("Failed to store updated price data".to_string(), &e);
                     }
-                    app.update_candles_for_selected(candles);
                 }
+                Err(e) if !app.is_throttled() => {
+                    app.report_error("Failed to refresh price data".to_string(), &e);
+                }
+                Err(_) => {}
+            }
+            last_tick = Instant::now();
+            prices_refreshed = true;
+        }
+
+        // Fetch candle data for selected symbol if needed. Tick bars grow
+        // with every recorded refresh, so those are reloaded each time, as
+        // is a chart that came back empty.
+        let reload = app.chart_source == ChartSource::Ticks || app.selected_candles.is_empty();
+        let symbol = match app.should_fetch_candles() {
+            Some(symbol) => Some(symbol),
+            None if prices_refreshed && reload && app.chart_loading.is_none() => {
+                app.get_selected_symbol().map(|selected| selected.symbol.clone())
             }
+            None => None,
+        };
+        if let Some(symbol) = symbol {
+            load_chart_candles(source, &db, &mut app, &symbol, &candle_loads, &background_errors).await;
         }
+        if prices_refreshed {
+            load_indicator_candles(source, &db, &mut app, &candle_loads, &background_errors);
+        }
+
+        handle_alert_triggers(&db, &mut app, &notifiers, &background_errors).await;
     }
 
     Ok(())
 }
+
+/// Keys of the alert manager: the open editor form takes every key,
+/// otherwise they select, create, edit, toggle and delete alerts. Changes
+/// are saved right away.
+async fn handle_alert_key(db: &database::Database, app: &mut app::App, code: KeyCode) {
+    if let Some(editor) = app.alert_editor.as_mut() {
+        match code {
+            KeyCode::Esc => app.close_alert_editor(),
+            KeyCode::Tab | KeyCode::Down => editor.next_field(),
+            KeyCode::BackTab | KeyCode::Up => editor.previous_field(),
+            KeyCode::Left => editor.cycle_choice(false),
+            KeyCode::Right => editor.cycle_choice(true),
+            KeyCode::Backspace => editor.backspace(),
+            KeyCode::Char(c) => editor.input(c),
+            KeyCode::Enter => {
+                if let Some(id) = app.submit_alert_editor() {
+                    save_alert(db, app, id).await;
+                }
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    let selected = app.get_selected_alert().map(|alert| alert.id);
+    match code {
+        KeyCode::Esc => app.show_alert_management = false,
+        KeyCode::Up => app.select_previous_alert(),
+        KeyCode::Down => app.select_next_alert(),
+        KeyCode::Char('n') => app.open_new_alert_editor(),
+        KeyCode::Enter | KeyCode::Char('e') => app.open_alert_editor(),
+        KeyCode::Char(' ') | KeyCode::Char('t') => {
+            if let Some(id) = selected
+                && app.toggle_alert(id)
+            {
+                let enabled = app.get_selected_alert().is_some_and(|alert| alert.enabled);
+                if let Err(e) = db.set_alert_enabled(id, enabled).await {
+                    app.report_error("Failed to save alert".to_string(), &e);
+                }
+            }
+        }
+        KeyCode::Delete | KeyCode::Char('x') => {
+            if let Some(id) = selected
+                && app.delete_alert(id)
+                && let Err(e) = db.delete_alert(id).await
+            {
+                app.report_error("Failed to delete saved alert".to_string(), &e);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Write an alert's current state to the database
+async fn save_alert(db: &database::Database, app: &mut app::App, id: u32) {
+    let Some(alert) = app.alerts.iter().find(|alert| alert.id == id).cloned() else {
+        return;
+    };
+    if let Err(e) = db.save_alert(&alert).await {
+        app.report_error("Failed to save alert".to_string(), &e);
+    }
+}
+
+/// Write alerts that fired since the last call to the alert history and
+/// send them to their notification sinks. Deliveries run in the background
+/// so a slow webhook or its retries don't hold up the dashboard; sinks that
+/// still fail after their retries are sent back through `errors`.
+async fn handle_alert_triggers(
+    db: &database::Database,
+    app: &mut app::App,
+    notifiers: &Arc<notify::Notifiers>,
+    errors: &BackgroundErrors,
+) {
+    for trigger in app.take_alert_triggers() {
+        if let Err(e) = db.record_alert_trigger(&trigger).await {
+            app.report_error("Failed to save alert history".to_string(), &e);
+        }
+
+        let sinks = app.alerts.iter().find(|a| a.id == trigger.alert_id).map(|a| a.sinks.clone()).unwrap_or_default();
+        let notifiers = Arc::clone(notifiers);
+        let errors = errors.clone();
+        tokio::spawn(async move {
+            for (sink, e) in notifiers.deliver(&trigger, &sinks).await {
+                let _ = errors.send((format!("Failed to send alert notification to {}", sink), e));
+            }
+        });
+    }
+}
@@ -0,0 +1,358 @@
+use rusqlite::{Connection, Result as SqlResult, Transaction, params};
+use crate::error::CoinPeekError;
+
+/// One schema change, applied in its own transaction
+struct Migration {
+    version: u32,
+    description: &'static str,
+    apply: fn(&Transaction) -> SqlResult<()>,
+}
+
+/// Every schema change in order. Append new migrations at the end and never
+/// edit one that has shipped.
+///
+/// Databases created before versioning report version 0 even though they
+/// already contain some of these changes, so each migration has to tolerate
+/// finding its change already applied.
+const MIGRATIONS: &[Migration] = &[
+    Migration { version: 1, description: "prices, candles and sync metadata tables", apply: create_initial_tables },
+    Migration { version: 2, description: "bid/ask prices and quote volume", apply: add_bid_ask_columns },
+    Migration { version: 3, description: "exact decimal price columns", apply: convert_prices_to_text },
+    Migration { version: 4, description: "exchange symbol metadata", apply: create_symbols_table },
+    Migration { version: 5, description: "unique candles", apply: make_candles_unique },
+    Migration { version: 6, description: "candle coverage ranges", apply: create_candle_coverage_table },
+    Migration { version: 7, description: "price alerts and alert history", apply: create_alert_tables },
+    Migration { version: 8, description: "alert cooldown and mode", apply: add_alert_schedule_columns },
+    Migration { version: 9, description: "alert notification sinks", apply: add_alert_sinks_column },
+    Migration { version: 10, description: "imported config alert rules", apply: create_alert_imports_table },
+    Migration { version: 11, description: "downsampled price candles", apply: create_downsampled_candles_table },
+];
+
+/// Schema version this build writes
+pub const LATEST_VERSION: u32 = 11;
+
+/// Schema version recorded in the database; 0 if it predates versioning or is new
+pub fn schema_version(conn: &Connection) -> SqlResult<u32> {
+    let tracked: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'schema_version')",
+        [],
+        |row| row.get(0),
+    )?;
+    if !tracked {
+        return Ok(0);
+    }
+
+    conn.query_row("SELECT COALESCE(MAX(version), 0) FROM schema_version", [], |row| row.get(0))
+}
+
+/// Bring the database up to `LATEST_VERSION`, returning the version it was at.
+///
+/// Refuses to touch a database written by a newer build, since this one
+/// can't know what those migrations changed.
+pub fn migrate(conn: &mut Connection) -> Result<u32, CoinPeekError> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            description TEXT NOT NULL,
+            applied_at INTEGER DEFAULT (strftime('%s', 'now'))
+        )",
+        [],
+    )?;
+
+    let found = schema_version(conn)?;
+    if found > LATEST_VERSION {
+        return Err(CoinPeekError::Database(format!(
+            "database schema version {} is newer than this build supports ({}); upgrade coinpeek to open it",
+            found, LATEST_VERSION
+        )));
+    }
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > found) {
+        let tx = conn.transaction()?;
+        (migration.apply)(&tx).map_err(|e| {
+            CoinPeekError::Database(format!("migration {} ({}) failed: {}", migration.version, migration.description, e))
+        })?;
+        tx.execute(
+            "INSERT INTO schema_version (version, description) VALUES (?, ?)",
+            params![migration.version, migration.description],
+        )?;
+        tx.commit()?;
+    }
+
+    Ok(found)
+}
+
+fn create_initial_tables(tx: &Transaction) -> SqlResult<()> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS prices (
+            id INTEGER PRIMARY KEY,
+            symbol TEXT NOT NULL,
+            price REAL NOT NULL,
+            price_change_percent REAL,
+            volume REAL,
+            high_24h REAL,
+            low_24h REAL,
+            prev_close_price REAL,
+            timestamp INTEGER NOT NULL,
+            exchange TEXT DEFAULT 'binance',
+            created_at INTEGER DEFAULT (strftime('%s', 'now'))
+        );
+
+        CREATE TABLE IF NOT EXISTS candles (
+            id INTEGER PRIMARY KEY,
+            symbol TEXT NOT NULL,
+            timeframe TEXT NOT NULL,
+            open REAL NOT NULL,
+            high REAL NOT NULL,
+            low REAL NOT NULL,
+            close REAL NOT NULL,
+            volume REAL,
+            timestamp INTEGER NOT NULL,
+            exchange TEXT DEFAULT 'binance',
+            created_at INTEGER DEFAULT (strftime('%s', 'now'))
+        );
+
+        CREATE TABLE IF NOT EXISTS sync_metadata (
+            key TEXT PRIMARY KEY,
+            value TEXT,
+            updated_at INTEGER DEFAULT (strftime('%s', 'now'))
+        );",
+    )?;
+    create_lookup_indexes(tx)
+}
+
+fn add_bid_ask_columns(tx: &Transaction) -> SqlResult<()> {
+    add_column_if_missing(tx, "prices", "bid_price", "REAL")?;
+    add_column_if_missing(tx, "prices", "ask_price", "REAL")?;
+    add_column_if_missing(tx, "prices", "quote_volume", "REAL")
+}
+
+/// Prices are stored as TEXT so decimal strings round-trip exactly; a REAL
+/// column would make SQLite coerce them back to floating point. SQLite can't
+/// change a column's type, so the tables are rebuilt.
+fn convert_prices_to_text(tx: &Transaction) -> SqlResult<()> {
+    if column_type(tx, "prices", "price")?.is_some_and(|t| t.eq_ignore_ascii_case("REAL")) {
+        tx.execute_batch(
+            "ALTER TABLE prices RENAME TO prices_legacy;
+
+            CREATE TABLE prices (
+                id INTEGER PRIMARY KEY,
+                symbol TEXT NOT NULL,
+                price TEXT NOT NULL,
+                price_change_percent REAL,
+                volume REAL,
+                high_24h TEXT,
+                low_24h TEXT,
+                prev_close_price TEXT,
+                bid_price TEXT,
+                ask_price TEXT,
+                quote_volume REAL,
+                timestamp INTEGER NOT NULL,
+                exchange TEXT DEFAULT 'binance',
+                created_at INTEGER DEFAULT (strftime('%s', 'now'))
+            );
+
+            INSERT INTO prices (
+                id, symbol, price, price_change_percent, volume, high_24h, low_24h,
+                prev_close_price, bid_price, ask_price, quote_volume, timestamp, exchange, created_at
+            )
+            SELECT id, symbol, CAST(price AS TEXT), price_change_percent, volume,
+                   CAST(high_24h AS TEXT), CAST(low_24h AS TEXT), CAST(prev_close_price AS TEXT),
+                   CAST(bid_price AS TEXT), CAST(ask_price AS TEXT), quote_volume, timestamp, exchange, created_at
+            FROM prices_legacy;
+
+            DROP TABLE prices_legacy;",
+        )?;
+    }
+
+    if column_type(tx, "candles", "open")?.is_some_and(|t| t.eq_ignore_ascii_case("REAL")) {
+        tx.execute_batch(
+            "ALTER TABLE candles RENAME TO candles_legacy;
+
+            CREATE TABLE candles (
+                id INTEGER PRIMARY KEY,
+                symbol TEXT NOT NULL,
+                timeframe TEXT NOT NULL,
+                open TEXT NOT NULL,
+                high TEXT NOT NULL,
+                low TEXT NOT NULL,
+                close TEXT NOT NULL,
+                volume REAL,
+                timestamp INTEGER NOT NULL,
+                exchange TEXT DEFAULT 'binance',
+                created_at INTEGER DEFAULT (strftime('%s', 'now'))
+            );
+
+            INSERT INTO candles (id, symbol, timeframe, open, high, low, close, volume, timestamp, exchange, created_at)
+            SELECT id, symbol, timeframe, CAST(open AS TEXT), CAST(high AS TEXT), CAST(low AS TEXT),
+                   CAST(close AS TEXT), volume, timestamp, exchange, created_at
+            FROM candles_legacy;
+
+            DROP TABLE candles_legacy;",
+        )?;
+    }
+
+    // The indexes went away with the old tables
+    create_lookup_indexes(tx)
+}
+
+fn create_symbols_table(tx: &Transaction) -> SqlResult<()> {
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS symbols (
+            symbol TEXT PRIMARY KEY,
+            base_asset TEXT NOT NULL,
+            quote_asset TEXT NOT NULL,
+            status TEXT NOT NULL,
+            tick_size TEXT NOT NULL,
+            step_size TEXT NOT NULL,
+            min_qty TEXT NOT NULL,
+            updated_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Earlier versions stored the same bar again on every refresh. Drop the
+/// duplicates, keeping the most recently written copy of each bar, so the
+/// unique index can be built.
+fn make_candles_unique(tx: &Transaction) -> SqlResult<()> {
+    tx.execute_batch(
+        "DELETE FROM candles WHERE id NOT IN (
+            SELECT MAX(id) FROM candles GROUP BY exchange, symbol, timeframe, timestamp
+        );
+
+        CREATE UNIQUE INDEX IF NOT EXISTS idx_candles_unique
+        ON candles(exchange, symbol, timeframe, timestamp);",
+    )
+}
+
+/// Time ranges whose candles have been fetched from the exchange, so gaps
+/// can be told apart from periods without trading
+fn create_candle_coverage_table(tx: &Transaction) -> SqlResult<()> {
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS candle_coverage (
+            symbol TEXT NOT NULL,
+            timeframe TEXT NOT NULL,
+            start_ms INTEGER NOT NULL,
+            end_ms INTEGER NOT NULL,
+            PRIMARY KEY (symbol, timeframe, start_ms)
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Alerts keep their condition as JSON so new kinds of condition don't need
+/// a schema change. History rows copy the symbol and message so they still
+/// read correctly after the alert is edited.
+fn create_alert_tables(tx: &Transaction) -> SqlResult<()> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS alerts (
+            id INTEGER PRIMARY KEY,
+            symbol TEXT NOT NULL,
+            condition TEXT NOT NULL,
+            enabled INTEGER NOT NULL,
+            message TEXT,
+            created_at INTEGER NOT NULL,
+            last_triggered INTEGER,
+            trigger_count INTEGER NOT NULL DEFAULT 0
+        );
+
+        CREATE TABLE IF NOT EXISTS alert_history (
+            id INTEGER PRIMARY KEY,
+            alert_id INTEGER NOT NULL,
+            symbol TEXT NOT NULL,
+            price TEXT NOT NULL,
+            message TEXT NOT NULL,
+            triggered_at INTEGER NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_alert_history_alert_time
+        ON alert_history(alert_id, triggered_at);",
+    )
+}
+
+/// Alerts saved before these columns existed keep the hourly cooldown they
+/// always had
+fn add_alert_schedule_columns(tx: &Transaction) -> SqlResult<()> {
+    add_column_if_missing(tx, "alerts", "cooldown_minutes", "INTEGER NOT NULL DEFAULT 60")?;
+    add_column_if_missing(tx, "alerts", "mode", "TEXT NOT NULL DEFAULT 'recurring'")
+}
+
+/// Comma-separated sink names; empty sends to the configured defaults, as
+/// alerts always did
+fn add_alert_sinks_column(tx: &Transaction) -> SqlResult<()> {
+    add_column_if_missing(tx, "alerts", "sinks", "TEXT NOT NULL DEFAULT ''")
+}
+
+/// Rules from the config file that were turned into alerts, kept apart from
+/// the alerts themselves so deleting or editing one doesn't bring it back
+fn create_alert_imports_table(tx: &Transaction) -> SqlResult<()> {
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS alert_rule_imports (
+            rule TEXT PRIMARY KEY,
+            imported_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Candles built from old price refreshes, kept apart from exchange klines
+/// so they're never mistaken for one or stop a real kline from being
+/// stored. Earlier versions wrote them into `candles` with zero volume, so
+/// those rows outside any fetched range move over; the gap fill downloads
+/// the real bars.
+fn create_downsampled_candles_table(tx: &Transaction) -> SqlResult<()> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS downsampled_candles (
+            symbol TEXT NOT NULL,
+            timeframe TEXT NOT NULL,
+            open TEXT NOT NULL,
+            high TEXT NOT NULL,
+            low TEXT NOT NULL,
+            close TEXT NOT NULL,
+            timestamp INTEGER NOT NULL,
+            PRIMARY KEY (symbol, timeframe, timestamp)
+        );
+
+        CREATE TEMP TABLE downsampled_ids AS
+        SELECT id FROM candles c
+        WHERE volume = 0 AND NOT EXISTS (
+            SELECT 1 FROM candle_coverage v
+            WHERE v.symbol = c.symbol AND v.timeframe = c.timeframe AND c.timestamp BETWEEN v.start_ms AND v.end_ms
+        );
+
+        INSERT OR IGNORE INTO downsampled_candles (symbol, timeframe, open, high, low, close, timestamp)
+        SELECT symbol, timeframe, open, high, low, close, timestamp FROM candles
+        WHERE id IN (SELECT id FROM downsampled_ids);
+
+        DELETE FROM candles WHERE id IN (SELECT id FROM downsampled_ids);
+        DROP TABLE downsampled_ids;",
+    )
+}
+
+fn create_lookup_indexes(tx: &Transaction) -> SqlResult<()> {
+    tx.execute_batch(
+        "CREATE INDEX IF NOT EXISTS idx_prices_symbol_timestamp ON prices(symbol, timestamp);
+        CREATE INDEX IF NOT EXISTS idx_candles_symbol_timeframe_timestamp ON candles(symbol, timeframe, timestamp);
+        CREATE INDEX IF NOT EXISTS idx_prices_timestamp ON prices(timestamp);",
+    )
+}
+
+/// Declared type of a table column, e.g. "REAL"
+fn column_type(conn: &Connection, table: &str, column: &str) -> SqlResult<Option<String>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let columns = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(1)?, row.get::<_, String>(2)?)))?
+        .collect::<SqlResult<Vec<_>>>()?;
+    Ok(columns.into_iter().find(|(name, _)| name == column).map(|(_, declared)| declared))
+}
+
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, definition: &str) -> SqlResult<()> {
+    if column_type(conn, table, column)?.is_none() {
+        conn.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition), [])?;
+    }
+    Ok(())
+}
@@ -0,0 +1,245 @@
+use std::io::Write;
+use std::time::Duration;
+use futures::future::{BoxFuture, FutureExt, join_all};
+use serde_json::{Value, json};
+use tokio::io::AsyncWriteExt;
+use crate::app::AlertTrigger;
+use crate::config::{NotificationConfig, SinkConfig, WebhookFormat};
+use crate::error::CoinPeekError;
+
+/// Whole-request timeout for webhook deliveries
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Somewhere fired alerts are announced. Implementations are boxed so the
+/// sinks named in the config can be mixed freely.
+pub trait Notifier: Send + Sync {
+    /// Deliver one notification. An error means it didn't arrive and may be
+    /// worth retrying.
+    fn notify<'a>(&'a self, trigger: &'a AlertTrigger) -> BoxFuture<'a, Result<(), CoinPeekError>>;
+}
+
+/// Rings the terminal bell
+pub struct TerminalBell;
+
+impl Notifier for TerminalBell {
+    fn notify<'a>(&'a self, _trigger: &'a AlertTrigger) -> BoxFuture<'a, Result<(), CoinPeekError>> {
+        async move {
+            let mut stdout = std::io::stdout();
+            stdout.write_all(b"\x07")?;
+            stdout.flush()?;
+            Ok(())
+        }
+        .boxed()
+    }
+}
+
+/// Runs a shell command, e.g. `notify-send` for a desktop notification. The
+/// alert is passed in the COINPEEK_ALERT_ID, COINPEEK_SYMBOL, COINPEEK_PRICE,
+/// COINPEEK_MESSAGE and COINPEEK_TRIGGERED_AT environment variables rather
+/// than pasted into the command, so messages can't inject shell syntax.
+pub struct CommandNotifier {
+    command: String,
+}
+
+impl CommandNotifier {
+    pub fn new(command: impl Into<String>) -> Self {
+        Self { command: command.into() }
+    }
+}
+
+impl Notifier for CommandNotifier {
+    fn notify<'a>(&'a self, trigger: &'a AlertTrigger) -> BoxFuture<'a, Result<(), CoinPeekError>> {
+        async move {
+            let (shell, flag) = if cfg!(windows) { ("cmd", "/C") } else { ("sh", "-c") };
+            // Output would scribble over the dashboard
+            let status = tokio::process::Command::new(shell)
+                .arg(flag)
+                .arg(&self.command)
+                .env("COINPEEK_ALERT_ID", trigger.alert_id.to_string())
+                .env("COINPEEK_SYMBOL", &trigger.symbol)
+                .env("COINPEEK_PRICE", trigger.price.to_string())
+                .env("COINPEEK_MESSAGE", &trigger.message)
+                .env("COINPEEK_TRIGGERED_AT", trigger.triggered_at.to_rfc3339())
+                .stdin(std::process::Stdio::null())
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .status()
+                .await?;
+
+            if status.success() {
+                Ok(())
+            } else {
+                Err(CoinPeekError::Io(format!("notification command '{}' failed with {}", self.command, status)))
+            }
+        }
+        .boxed()
+    }
+}
+
+/// POSTs a JSON body to a webhook, shaped for Slack, Discord, ntfy or as
+/// plain alert fields
+pub struct WebhookNotifier {
+    url: String,
+    format: WebhookFormat,
+    topic: Option<String>,  // ntfy topic
+    http: reqwest::Client,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: impl Into<String>, format: WebhookFormat, topic: Option<String>) -> Result<Self, CoinPeekError> {
+        let http = reqwest::Client::builder()
+            .timeout(WEBHOOK_TIMEOUT)
+            .user_agent(format!("coinpeek/{}", env!("CARGO_PKG_VERSION")))
+            .build()?;
+
+        Ok(Self { url: url.into(), format, topic, http })
+    }
+
+    /// Body sent for `trigger`
+    pub fn payload(&self, trigger: &AlertTrigger) -> Value {
+        match self.format {
+            WebhookFormat::Json => json!({
+                "alert_id": trigger.alert_id,
+                "symbol": trigger.symbol,
+                "price": trigger.price.to_string(),
+                "message": trigger.message,
+                "triggered_at": trigger.triggered_at.to_rfc3339(),
+            }),
+            WebhookFormat::Slack => json!({ "text": trigger.message }),
+            WebhookFormat::Discord => json!({ "content": trigger.message }),
+            WebhookFormat::Ntfy => json!({
+                "topic": self.topic.as_deref().unwrap_or_default(),
+                "title": format!("{} alert", trigger.symbol),
+                "message": trigger.message,
+                "tags": ["chart_with_upwards_trend"],
+            }),
+        }
+    }
+}
+
+impl Notifier for WebhookNotifier {
+    fn notify<'a>(&'a self, trigger: &'a AlertTrigger) -> BoxFuture<'a, Result<(), CoinPeekError>> {
+        async move {
+            let response = self.http.post(&self.url).json(&self.payload(trigger)).send().await?;
+            let status = response.status();
+            if status.is_success() {
+                return Ok(());
+            }
+
+            let body = response.text().await.unwrap_or_default();
+            Err(CoinPeekError::HttpStatus { status: status.as_u16(), message: body.trim().chars().take(200).collect() })
+        }
+        .boxed()
+    }
+}
+
+/// Appends a line per notification to a file: time, symbol, price and message,
+/// tab separated
+pub struct LogFileNotifier {
+    path: String,
+}
+
+impl LogFileNotifier {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl Notifier for LogFileNotifier {
+    fn notify<'a>(&'a self, trigger: &'a AlertTrigger) -> BoxFuture<'a, Result<(), CoinPeekError>> {
+        async move {
+            let line = format!(
+                "{}\t{}\t{}\t{}\n",
+                trigger.triggered_at.to_rfc3339(),
+                trigger.symbol,
+                trigger.price,
+                trigger.message.replace(['\n', '\t'], " ")
+            );
+            let mut file = tokio::fs::OpenOptions::new().create(true).append(true).open(&self.path).await?;
+            file.write_all(line.as_bytes()).await?;
+            file.flush().await?;
+            Ok(())
+        }
+        .boxed()
+    }
+}
+
+/// The configured sinks and how alerts are routed to them
+pub struct Notifiers {
+    sinks: Vec<(String, Box<dyn Notifier>)>,
+    default_sinks: Vec<String>,
+    retries: u32,
+    retry_delay: Duration,
+}
+
+impl Notifiers {
+    /// No sinks yet; failed deliveries are tried `retries` more times
+    pub fn new(retries: u32, retry_delay: Duration) -> Self {
+        Self { sinks: Vec::new(), default_sinks: Vec::new(), retries, retry_delay }
+    }
+
+    /// Build the sinks in the notifications section of the configuration
+    pub fn from_config(config: &NotificationConfig) -> Result<Self, CoinPeekError> {
+        let mut notifiers = Self::new(config.retries, Duration::from_millis(config.retry_delay_ms));
+        for (name, sink) in &config.sinks {
+            let notifier: Box<dyn Notifier> = match sink {
+                SinkConfig::Bell => Box::new(TerminalBell),
+                SinkConfig::Command { command } => Box::new(CommandNotifier::new(command.clone())),
+                SinkConfig::Webhook { url, format, topic } => {
+                    Box::new(WebhookNotifier::new(url.clone(), *format, topic.clone())?)
+                }
+                SinkConfig::Log { path } => Box::new(LogFileNotifier::new(path.clone())),
+            };
+            notifiers.add(name.clone(), notifier);
+        }
+        notifiers.set_default_sinks(config.default_sinks.clone());
+        Ok(notifiers)
+    }
+
+    /// Register a sink, replacing any with the same name
+    pub fn add(&mut self, name: impl Into<String>, notifier: Box<dyn Notifier>) {
+        let name = name.into();
+        self.sinks.retain(|(existing, _)| *existing != name);
+        self.sinks.push((name, notifier));
+    }
+
+    /// Sinks used by alerts that don't choose their own
+    pub fn set_default_sinks(&mut self, names: Vec<String>) {
+        self.default_sinks = names;
+    }
+
+    /// Sinks an alert with the chosen `sinks` is sent to
+    pub fn route<'a>(&'a self, sinks: &'a [String]) -> &'a [String] {
+        if sinks.is_empty() { &self.default_sinks } else { sinks }
+    }
+
+    /// Send `trigger` to each of its sinks at once, retrying failures with a
+    /// doubling delay. Returns the sinks it couldn't be delivered to and why.
+    pub async fn deliver(&self, trigger: &AlertTrigger, sinks: &[String]) -> Vec<(String, CoinPeekError)> {
+        let deliveries = self.route(sinks).iter().map(|name| async move {
+            let result = match self.sinks.iter().find(|(existing, _)| existing == name) {
+                Some((_, notifier)) => self.deliver_to(notifier.as_ref(), trigger).await,
+                None => Err(CoinPeekError::Io(format!("no notification sink named {}", name))),
+            };
+            result.err().map(|e| (name.clone(), e))
+        });
+
+        join_all(deliveries).await.into_iter().flatten().collect()
+    }
+
+    async fn deliver_to(&self, notifier: &dyn Notifier, trigger: &AlertTrigger) -> Result<(), CoinPeekError> {
+        let mut delay = self.retry_delay;
+        let mut attempt = 0;
+        loop {
+            match notifier.notify(trigger).await {
+                Ok(()) => return Ok(()),
+                Err(e) if attempt >= self.retries => return Err(e),
+                Err(_) => {
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+}
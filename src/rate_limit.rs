@@ -0,0 +1,191 @@
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use std::sync::Mutex;
+
+use crate::error::CoinPeekError;
+
+/// Stop issuing requests once this share of the per-minute weight is used,
+/// leaving headroom for requests already in flight
+const WEIGHT_SAFETY_RATIO: f64 = 0.9;
+
+/// First retry delay after a failure; doubles on every consecutive failure
+const BASE_BACKOFF_MS: i64 = 1_000;
+
+/// Upper bound for the exponential backoff
+const MAX_BACKOFF_MS: i64 = 5 * 60 * 1_000;
+
+#[derive(Debug, Default)]
+struct LimiterState {
+    used_weight: u32,
+    window_start: Option<DateTime<Utc>>, // Minute the used weight belongs to
+    blocked_until: Option<DateTime<Utc>>,
+    block_reason: String,
+    consecutive_failures: u32,
+}
+
+impl LimiterState {
+    /// Binance resets request weight at each calendar minute
+    fn roll_window(&mut self, now: DateTime<Utc>) {
+        let minute = now.duration_trunc(Duration::minutes(1)).unwrap_or(now);
+        if self.window_start != Some(minute) {
+            self.window_start = Some(minute);
+            self.used_weight = 0;
+        }
+    }
+
+    fn block(&mut self, until: DateTime<Utc>, reason: String) {
+        // Never shorten an existing block, e.g. a ban followed by a 5xx
+        if self.blocked_until.is_none_or(|current| until > current) {
+            self.blocked_until = Some(until);
+            self.block_reason = reason;
+        }
+    }
+}
+
+/// Client-side view of the exchange's request weight limit.
+///
+/// Requests reserve weight with `acquire` before they are sent, and the
+/// `X-MBX-USED-WEIGHT-1M` header of every response corrects the local count.
+/// 429/418 responses and server errors block further requests until the
+/// `Retry-After` time or an exponential backoff with jitter has passed.
+/// Callers get `CoinPeekError::RateLimited` immediately rather than being made to wait.
+#[derive(Debug)]
+pub struct RateLimiter {
+    weight_limit: u32,
+    state: Mutex<LimiterState>,
+}
+
+impl RateLimiter {
+    pub fn new(weight_limit_per_minute: u32) -> Self {
+        Self {
+            weight_limit: weight_limit_per_minute,
+            state: Mutex::new(LimiterState::default()),
+        }
+    }
+
+    pub fn weight_limit(&self) -> u32 {
+        self.weight_limit
+    }
+
+    /// Weight used in the current minute, as last reported or estimated
+    pub fn used_weight(&self, now: DateTime<Utc>) -> u32 {
+        let mut state = self.state.lock().unwrap();
+        state.roll_window(now);
+        state.used_weight
+    }
+
+    /// Reserve `weight` for a request, or report when requests may resume
+    pub fn acquire(&self, weight: u32, now: DateTime<Utc>) -> Result<(), CoinPeekError> {
+        let mut state = self.state.lock().unwrap();
+
+        if let Some(until) = state.blocked_until {
+            if until > now {
+                return Err(CoinPeekError::RateLimited { reason: state.block_reason.clone(), retry_at: until });
+            }
+            state.blocked_until = None;
+        }
+
+        state.roll_window(now);
+        let budget = (self.weight_limit as f64 * WEIGHT_SAFETY_RATIO) as u32;
+        // A single oversized request is still allowed into an empty window
+        if state.used_weight > 0 && state.used_weight + weight > budget {
+            let next_window = state.window_start.unwrap_or(now) + Duration::minutes(1);
+            return Err(CoinPeekError::RateLimited {
+                reason: format!("Request weight budget used ({}/{})", state.used_weight, self.weight_limit),
+                retry_at: next_window,
+            });
+        }
+
+        state.used_weight += weight;
+        Ok(())
+    }
+
+    /// Record the authoritative used weight from a response header
+    pub fn record_used_weight(&self, used_weight: u32, now: DateTime<Utc>) {
+        let mut state = self.state.lock().unwrap();
+        state.roll_window(now);
+        state.used_weight = used_weight;
+    }
+
+    /// Reset the backoff after a successful response
+    pub fn record_success(&self) {
+        self.state.lock().unwrap().consecutive_failures = 0;
+    }
+
+    /// Handle a 429 (too many requests) or 418 (IP banned) response
+    pub fn record_rate_limited(&self, status: u16, retry_after_seconds: Option<u64>, now: DateTime<Utc>) -> CoinPeekError {
+        let mut state = self.state.lock().unwrap();
+        state.consecutive_failures += 1;
+
+        let wait = match retry_after_seconds {
+            Some(seconds) => Duration::seconds(seconds as i64),
+            None => backoff_delay(state.consecutive_failures, jitter_seed(now)),
+        };
+        let reason = if status == 418 {
+            "IP temporarily banned by exchange (HTTP 418)".to_string()
+        } else {
+            format!("Rate limited by exchange (HTTP {})", status)
+        };
+
+        state.block(now + wait, reason);
+        CoinPeekError::RateLimited {
+            reason: state.block_reason.clone(),
+            retry_at: state.blocked_until.unwrap_or(now + wait),
+        }
+    }
+
+    /// Back off after a server error or failed connection
+    pub fn record_failure(&self, now: DateTime<Utc>) {
+        let mut state = self.state.lock().unwrap();
+        state.consecutive_failures += 1;
+        let wait = backoff_delay(state.consecutive_failures, jitter_seed(now));
+        let reason = format!("Backing off after {} failed requests", state.consecutive_failures);
+        state.block(now + wait, reason);
+    }
+
+    /// When requests may resume, if they are currently being held back
+    pub fn throttled_until(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let state = self.state.lock().unwrap();
+        let budget = (self.weight_limit as f64 * WEIGHT_SAFETY_RATIO) as u32;
+        let window_full = state.used_weight >= budget
+            && state.window_start.is_some_and(|start| start + Duration::minutes(1) > now);
+
+        match state.blocked_until {
+            Some(until) if until > now => Some(until),
+            _ if window_full => state.window_start.map(|start| start + Duration::minutes(1)),
+            _ => None,
+        }
+    }
+}
+
+/// Wait until `until`, for long-running jobs that should ride out a rate
+/// limit instead of failing
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn sleep_until(until: DateTime<Utc>) {
+    if let Ok(wait) = (until - Utc::now()).to_std() {
+        tokio::time::sleep(wait).await;
+    }
+}
+
+/// Wait until `until`, for long-running jobs that should ride out a rate
+/// limit instead of failing
+#[cfg(target_arch = "wasm32")]
+pub async fn sleep_until(until: DateTime<Utc>) {
+    if let Ok(wait) = (until - Utc::now()).to_std() {
+        gloo_timers::future::sleep(wait).await;
+    }
+}
+
+/// Exponential backoff (1s, 2s, 4s, ... capped at 5 minutes) with "equal
+/// jitter": half of the delay is fixed and the other half is spread by
+/// `jitter_seed`, so clients that failed together don't retry together.
+pub fn backoff_delay(consecutive_failures: u32, jitter_seed: u32) -> Duration {
+    let exponent = consecutive_failures.saturating_sub(1).min(20);
+    let full = BASE_BACKOFF_MS.saturating_mul(1 << exponent).min(MAX_BACKOFF_MS);
+    let half = full / 2;
+    Duration::milliseconds(half + jitter_seed as i64 % (half + 1))
+}
+
+/// Cheap per-call randomness without pulling in an RNG crate
+fn jitter_seed(now: DateTime<Utc>) -> u32 {
+    now.timestamp_subsec_nanos().wrapping_mul(2_654_435_761)
+}
@@ -0,0 +1,597 @@
+use crate::binance::PriceInfo;
+use crate::config::Config;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::FromPrimitive;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Ticker value a rule reads for a symbol, e.g. `ETHUSDT.volume`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    Price,        // The default when no field is given
+    Change,       // 24h change in percent
+    Volume,       // 24h volume in the base asset
+    QuoteVolume,  // 24h volume in the quote asset
+    High,         // 24h high
+    Low,          // 24h low
+    Bid,
+    Ask,
+}
+
+impl Metric {
+    const ALL: [Metric; 8] = [
+        Metric::Price,
+        Metric::Change,
+        Metric::Volume,
+        Metric::QuoteVolume,
+        Metric::High,
+        Metric::Low,
+        Metric::Bid,
+        Metric::Ask,
+    ];
+
+    /// Field name used in rule text
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Metric::Price => "price",
+            Metric::Change => "change",
+            Metric::Volume => "volume",
+            Metric::QuoteVolume => "quote_volume",
+            Metric::High => "high",
+            Metric::Low => "low",
+            Metric::Bid => "bid",
+            Metric::Ask => "ask",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Metric> {
+        Self::ALL.into_iter().find(|metric| metric.as_str().eq_ignore_ascii_case(name))
+    }
+
+    fn read(&self, price_info: &PriceInfo) -> Option<Decimal> {
+        match self {
+            Metric::Price => Some(price_info.price),
+            Metric::Change => Decimal::from_f64(price_info.price_change_percent),
+            Metric::Volume => Decimal::from_f64(price_info.volume),
+            Metric::QuoteVolume => Decimal::from_f64(price_info.quote_volume),
+            Metric::High => Some(price_info.high_24h),
+            Metric::Low => Some(price_info.low_24h),
+            Metric::Bid => Some(price_info.bid_price),
+            Metric::Ask => Some(price_info.ask_price),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arithmetic {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+/// A number computed from the latest tickers, e.g. `ETHUSDT / BTCUSDT`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(Decimal),
+    Metric { symbol: String, metric: Metric },
+    Binary { op: Arithmetic, left: Box<Expr>, right: Box<Expr> },
+}
+
+impl Expr {
+    /// The value for the given tickers; `None` if a symbol has no ticker
+    /// or it divides by zero
+    fn value(&self, price_infos: &[PriceInfo]) -> Option<Decimal> {
+        match self {
+            Expr::Number(value) => Some(*value),
+            Expr::Metric { symbol, metric } => metric.read(price_infos.iter().find(|p| p.symbol == *symbol)?),
+            Expr::Binary { op, left, right } => {
+                let (left, right) = (left.value(price_infos)?, right.value(price_infos)?);
+                match op {
+                    Arithmetic::Add => left.checked_add(right),
+                    Arithmetic::Subtract => left.checked_sub(right),
+                    Arithmetic::Multiply => left.checked_mul(right),
+                    Arithmetic::Divide => left.checked_div(right),
+                }
+            }
+        }
+    }
+
+    fn symbols<'a>(&'a self, symbols: &mut Vec<&'a str>) {
+        match self {
+            Expr::Number(_) => {}
+            Expr::Metric { symbol, .. } => {
+                if !symbols.contains(&symbol.as_str()) {
+                    symbols.push(symbol);
+                }
+            }
+            Expr::Binary { left, right, .. } => {
+                left.symbols(symbols);
+                right.symbols(symbols);
+            }
+        }
+    }
+
+    /// Point the placeholder of an `any(...)`/`all(...)` group at `symbol`
+    fn with_group_symbol(&self, symbol: &str) -> Expr {
+        match self {
+            Expr::Metric { symbol: placeholder, metric } if placeholder.is_empty() => {
+                Expr::Metric { symbol: symbol.to_string(), metric: *metric }
+            }
+            Expr::Binary { op, left, right } => Expr::Binary {
+                op: *op,
+                left: Box::new(left.with_group_symbol(symbol)),
+                right: Box::new(right.with_group_symbol(symbol)),
+            },
+            other => other.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Above,    // >
+    AtLeast,  // >=
+    Below,    // <
+    AtMost,   // <=
+}
+
+/// Parsed form of an alert rule
+#[derive(Debug, Clone, PartialEq)]
+pub enum Rule {
+    Compare { left: Expr, comparison: Comparison, right: Expr },
+    All(Vec<Rule>),  // and
+    Any(Vec<Rule>),  // or
+    Not(Box<Rule>),
+}
+
+impl Rule {
+    /// Whether the rule holds for the given tickers. `None` if that can't be
+    /// told because a symbol it needs has no ticker yet.
+    pub fn evaluate(&self, price_infos: &[PriceInfo]) -> Option<bool> {
+        match self {
+            Rule::Compare { left, comparison, right } => {
+                let (left, right) = (left.value(price_infos)?, right.value(price_infos)?);
+                Some(match comparison {
+                    Comparison::Above => left > right,
+                    Comparison::AtLeast => left >= right,
+                    Comparison::Below => left < right,
+                    Comparison::AtMost => left <= right,
+                })
+            }
+            Rule::All(rules) => {
+                let results: Vec<Option<bool>> = rules.iter().map(|r| r.evaluate(price_infos)).collect();
+                if results.contains(&Some(false)) {
+                    Some(false)
+                } else {
+                    results.into_iter().collect::<Option<Vec<_>>>().map(|_| true)
+                }
+            }
+            Rule::Any(rules) => {
+                let results: Vec<Option<bool>> = rules.iter().map(|r| r.evaluate(price_infos)).collect();
+                if results.contains(&Some(true)) {
+                    Some(true)
+                } else {
+                    results.into_iter().collect::<Option<Vec<_>>>().map(|_| false)
+                }
+            }
+            Rule::Not(rule) => rule.evaluate(price_infos).map(|holds| !holds),
+        }
+    }
+
+    /// Symbols the rule reads, in order of first appearance
+    pub fn symbols(&self) -> Vec<&str> {
+        let mut symbols = Vec::new();
+        self.collect_symbols(&mut symbols);
+        symbols
+    }
+
+    fn collect_symbols<'a>(&'a self, symbols: &mut Vec<&'a str>) {
+        match self {
+            Rule::Compare { left, right, .. } => {
+                left.symbols(symbols);
+                right.symbols(symbols);
+            }
+            Rule::All(rules) | Rule::Any(rules) => rules.iter().for_each(|r| r.collect_symbols(symbols)),
+            Rule::Not(rule) => rule.collect_symbols(symbols),
+        }
+    }
+}
+
+/// An alert rule together with the text it was written as, which is how it
+/// is shown, stored and configured.
+///
+/// The syntax compares ticker values of one or more symbols:
+///
+/// ```text
+/// ETHUSDT < 2000 and ETHUSDT.volume > 500000
+/// any(BTCUSDT, ETHUSDT, SOLUSDT).change <= -5
+/// ETHUSDT / BTCUSDT > 0.055 or not (BTCUSDT.bid >= 60000)
+/// ```
+///
+/// A bare symbol means its price; `.change`, `.volume`, `.quote_volume`,
+/// `.high`, `.low`, `.bid` and `.ask` read other ticker fields. Values can
+/// be combined with `+ - * /`, compared with `< <= > >=` and comparisons
+/// joined with `and`, `or`, `not` and parentheses. `any(...)` and `all(...)`
+/// repeat a comparison for each listed symbol.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct AlertRule {
+    text: String,
+    rule: Rule,
+}
+
+impl AlertRule {
+    pub fn parse(text: &str) -> Result<AlertRule, String> {
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let rule = Parser::new(&text)?.parse()?;
+        if rule.symbols().is_empty() {
+            return Err("The rule doesn't read any symbol".to_string());
+        }
+        Ok(AlertRule { text, rule })
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn rule(&self) -> &Rule {
+        &self.rule
+    }
+
+    /// Whether the rule holds for the latest tickers; `false` while a symbol
+    /// it needs has no ticker
+    pub fn holds(&self, price_infos: &[PriceInfo]) -> bool {
+        self.rule.evaluate(price_infos) == Some(true)
+    }
+
+    pub fn symbols(&self) -> Vec<&str> {
+        self.rule.symbols()
+    }
+
+    /// Reject rules reading symbols outside `tracked`, since no prices would
+    /// ever arrive for them
+    pub fn check_symbols(&self, tracked: &[String]) -> Result<(), String> {
+        match self.symbols().into_iter().find(|symbol| !tracked.iter().any(|t| t == symbol)) {
+            Some(symbol) => Err(format!("{} isn't a tracked symbol", symbol)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for AlertRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl TryFrom<String> for AlertRule {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        AlertRule::parse(&text)
+    }
+}
+
+impl From<AlertRule> for String {
+    fn from(rule: AlertRule) -> String {
+        rule.text
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),  // Symbol, number, keyword or `.field`
+    Op(&'static str),
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    const OPS: [&str; 11] = ["<=", ">=", "<", ">", "(", ")", ",", "+", "-", "*", "/"];
+
+    let mut tokens = Vec::new();
+    let mut rest = text.trim_start();
+    while let Some(c) = rest.chars().next() {
+        if let Some(op) = OPS.iter().find(|op| rest.starts_with(**op)) {
+            tokens.push(Token::Op(op));
+            rest = &rest[op.len()..];
+        } else if c.is_ascii_alphanumeric() || c == '.' || c == '_' {
+            let end = rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '.' || c == '_')).unwrap_or(rest.len());
+            tokens.push(Token::Word(rest[..end].to_string()));
+            rest = &rest[end..];
+        } else {
+            return Err(format!("Unexpected '{}' in rule", c));
+        }
+        rest = rest.trim_start();
+    }
+    Ok(tokens)
+}
+
+/// What a stretch of rule text parsed to: a whole rule, or a value still
+/// waiting to be compared, which only a parenthesis may leave unfinished
+enum Operand {
+    Rule(Rule),
+    Value(Expr),
+}
+
+/// Recursive descent over the tokens. `or` binds loosest, then `and`,
+/// `not`, comparisons, `+ -` and finally `* /`.
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    // Symbols of the `any(...)`/`all(...)` group in the comparison being
+    // parsed; its values are read through a placeholder with no symbol
+    group: Option<(bool, Vec<String>)>,
+}
+
+impl Parser {
+    fn new(text: &str) -> Result<Parser, String> {
+        Ok(Parser { tokens: tokenize(text)?, position: 0, group: None })
+    }
+
+    fn parse(mut self) -> Result<Rule, String> {
+        if self.tokens.is_empty() {
+            return Err("The rule is empty".to_string());
+        }
+        let operand = self.parse_or()?;
+        let rule = self.expect_rule(operand)?;
+        match self.peek() {
+            None => Ok(rule),
+            Some(token) => Err(format!("Unexpected '{}' in rule", token_text(token))),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn at_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.at_keyword(keyword);
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn eat_op(&mut self, op: &str) -> bool {
+        match self.peek() {
+            Some(Token::Op(found)) if *found == op => {
+                self.position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// The rule an operand must be where a comparison is required
+    fn expect_rule(&self, operand: Operand) -> Result<Rule, String> {
+        match (operand, self.peek()) {
+            (Operand::Rule(rule), _) => Ok(rule),
+            (Operand::Value(_), Some(token)) => Err(format!("Expected <, <=, > or >= but found '{}'", token_text(token))),
+            (Operand::Value(_), None) => Err("Expected <, <=, > or >= at the end of the rule".to_string()),
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Operand, String> {
+        let first = self.parse_and()?;
+        if !self.at_keyword("or") {
+            return Ok(first);
+        }
+        let mut rules = vec![self.expect_rule(first)?];
+        while self.eat_keyword("or") {
+            let operand = self.parse_and()?;
+            rules.push(self.expect_rule(operand)?);
+        }
+        Ok(Operand::Rule(Rule::Any(rules)))
+    }
+
+    fn parse_and(&mut self) -> Result<Operand, String> {
+        let first = self.parse_not()?;
+        if !self.at_keyword("and") {
+            return Ok(first);
+        }
+        let mut rules = vec![self.expect_rule(first)?];
+        while self.eat_keyword("and") {
+            let operand = self.parse_not()?;
+            rules.push(self.expect_rule(operand)?);
+        }
+        Ok(Operand::Rule(Rule::All(rules)))
+    }
+
+    fn parse_not(&mut self) -> Result<Operand, String> {
+        if self.eat_keyword("not") {
+            let operand = self.parse_not()?;
+            return Ok(Operand::Rule(Rule::Not(Box::new(self.expect_rule(operand)?))));
+        }
+
+        // `(` opens either a nested rule or arithmetic such as
+        // `(BTCUSDT + ETHUSDT) > 100000`. Its contents are parsed once and
+        // whatever they turn out to be decides which.
+        let left = if self.eat_op("(") {
+            let inner = self.parse_or()?;
+            if !self.eat_op(")") {
+                return Err("Missing ')' in rule".to_string());
+            }
+            match inner {
+                Operand::Rule(rule) => return Ok(Operand::Rule(rule)),
+                Operand::Value(expr) => {
+                    let product = self.continue_product(expr)?;
+                    self.continue_sum(product)?
+                }
+            }
+        } else {
+            self.group = None;
+            self.parse_sum()?
+        };
+        self.parse_comparison(left)
+    }
+
+    /// The comparison `left` is the first side of, or `left` alone if no
+    /// comparison follows, as inside `(BTCUSDT + ETHUSDT)`
+    fn parse_comparison(&mut self, left: Expr) -> Result<Operand, String> {
+        let comparison = match self.peek() {
+            Some(Token::Op("<")) => Comparison::Below,
+            Some(Token::Op("<=")) => Comparison::AtMost,
+            Some(Token::Op(">")) => Comparison::Above,
+            Some(Token::Op(">=")) => Comparison::AtLeast,
+            _ => return Ok(Operand::Value(left)),
+        };
+        self.position += 1;
+        let right = self.parse_sum()?;
+
+        Ok(Operand::Rule(match self.group.take() {
+            None => Rule::Compare { left, comparison, right },
+            Some((all, symbols)) => {
+                let rules = symbols
+                    .iter()
+                    .map(|symbol| Rule::Compare {
+                        left: left.with_group_symbol(symbol),
+                        comparison,
+                        right: right.with_group_symbol(symbol),
+                    })
+                    .collect();
+                if all { Rule::All(rules) } else { Rule::Any(rules) }
+            }
+        }))
+    }
+
+    fn parse_sum(&mut self) -> Result<Expr, String> {
+        let first = self.parse_product()?;
+        self.continue_sum(first)
+    }
+
+    /// Add or subtract whatever follows `expr`
+    fn continue_sum(&mut self, mut expr: Expr) -> Result<Expr, String> {
+        loop {
+            let op = match self.peek() {
+                Some(Token::Op("+")) => Arithmetic::Add,
+                Some(Token::Op("-")) => Arithmetic::Subtract,
+                _ => return Ok(expr),
+            };
+            self.position += 1;
+            expr = Expr::Binary { op, left: Box::new(expr), right: Box::new(self.parse_product()?) };
+        }
+    }
+
+    fn parse_product(&mut self) -> Result<Expr, String> {
+        let first = self.parse_value()?;
+        self.continue_product(first)
+    }
+
+    /// Multiply or divide whatever follows `expr`
+    fn continue_product(&mut self, mut expr: Expr) -> Result<Expr, String> {
+        loop {
+            let op = match self.peek() {
+                Some(Token::Op("*")) => Arithmetic::Multiply,
+                Some(Token::Op("/")) => Arithmetic::Divide,
+                _ => return Ok(expr),
+            };
+            self.position += 1;
+            expr = Expr::Binary { op, left: Box::new(expr), right: Box::new(self.parse_value()?) };
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Op("-")) => match self.parse_value()? {
+                Expr::Number(value) => Ok(Expr::Number(-value)),
+                expr => Ok(Expr::Binary {
+                    op: Arithmetic::Subtract,
+                    left: Box::new(Expr::Number(Decimal::ZERO)),
+                    right: Box::new(expr),
+                }),
+            },
+            Some(Token::Op("(")) => {
+                let expr = self.parse_sum()?;
+                if !self.eat_op(")") {
+                    return Err("Missing ')' in rule".to_string());
+                }
+                Ok(expr)
+            }
+            Some(Token::Word(word)) if self.peek() == Some(&Token::Op("(")) => {
+                let all = match word.to_ascii_lowercase().as_str() {
+                    "any" => false,
+                    "all" => true,
+                    _ => return Err(format!("Unknown function '{}'. Use any(...) or all(...)", word)),
+                };
+                self.position += 1;
+                self.parse_group(all)
+            }
+            Some(Token::Word(word)) => {
+                if let Ok(value) = word.parse::<Decimal>() {
+                    return Ok(Expr::Number(value));
+                }
+                let (symbol, field) = word.split_once('.').unwrap_or((&word, ""));
+                Ok(Expr::Metric { symbol: parse_symbol(symbol)?, metric: parse_field(field)? })
+            }
+            Some(Token::Op(op)) => Err(format!("Expected a symbol or number but found '{}'", op)),
+            None => Err("Expected a symbol or number at the end of the rule".to_string()),
+        }
+    }
+
+    /// The symbols and optional `.field` of `any(...)`/`all(...)`, after the `(`
+    fn parse_group(&mut self, all: bool) -> Result<Expr, String> {
+        if self.group.is_some() {
+            return Err("Only one any(...) or all(...) per comparison".to_string());
+        }
+
+        let mut symbols = Vec::new();
+        loop {
+            match self.next() {
+                Some(Token::Word(symbol)) => symbols.push(parse_symbol(&symbol)?),
+                _ => return Err("any(...) and all(...) take a list of symbols".to_string()),
+            }
+            if self.eat_op(")") {
+                break;
+            }
+            if !self.eat_op(",") {
+                return Err("Symbols in any(...) and all(...) are separated by commas".to_string());
+            }
+        }
+
+        let metric = match self.peek() {
+            Some(Token::Word(word)) if word.starts_with('.') => {
+                let metric = parse_field(&word[1..])?;
+                self.position += 1;
+                metric
+            }
+            _ => Metric::Price,
+        };
+        self.group = Some((all, symbols));
+        Ok(Expr::Metric { symbol: String::new(), metric })
+    }
+}
+
+fn parse_symbol(symbol: &str) -> Result<String, String> {
+    if Config::is_valid_symbol(symbol) {
+        Ok(symbol.to_string())
+    } else {
+        Err(format!("Invalid symbol '{}'. Use uppercase pairs like BTCUSDT", symbol))
+    }
+}
+
+fn parse_field(field: &str) -> Result<Metric, String> {
+    if field.is_empty() {
+        return Ok(Metric::Price);
+    }
+    Metric::parse(field).ok_or_else(|| {
+        let names: Vec<&str> = Metric::ALL.iter().map(Metric::as_str).collect();
+        format!("Unknown field '{}'. Use one of {}", field, names.join(", "))
+    })
+}
+
+fn token_text(token: &Token) -> &str {
+    match token {
+        Token::Word(word) => word,
+        Token::Op(op) => op,
+    }
+}
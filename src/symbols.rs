@@ -0,0 +1,98 @@
+use crate::error::CoinPeekError;
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Refetch exchange metadata once the cached copy is older than this
+pub const SYMBOL_CACHE_MAX_AGE_HOURS: i64 = 24;
+
+/// Exchange status of a symbol that accepts orders
+pub const TRADING_STATUS: &str = "TRADING";
+
+/// Trading rules and asset names for one symbol, from the exchange's metadata
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SymbolInfo {
+    pub symbol: String,
+    pub base_asset: String,   // e.g. "BTC"
+    pub quote_asset: String,  // e.g. "USDT"
+    pub status: String,       // "TRADING", "BREAK", "HALT", ...
+    pub tick_size: Decimal,   // Smallest price increment
+    pub step_size: Decimal,   // Smallest quantity increment (lot size)
+    pub min_qty: Decimal,
+}
+
+impl SymbolInfo {
+    pub fn is_trading(&self) -> bool {
+        self.status == TRADING_STATUS
+    }
+
+    /// "BTC/USDT" style pair name
+    pub fn pair_name(&self) -> String {
+        format!("{}/{}", self.base_asset, self.quote_asset)
+    }
+}
+
+/// Symbols known to the exchange, keyed by symbol name.
+///
+/// Built from the exchange's metadata endpoint and cached in SQLite, so the
+/// app can check configured symbols and format prices on each symbol's tick
+/// grid without guessing from the symbol's spelling.
+#[derive(Debug, Clone, Default)]
+pub struct SymbolRegistry {
+    symbols: HashMap<String, SymbolInfo>,
+    updated_at: Option<DateTime<Utc>>,
+}
+
+impl SymbolRegistry {
+    pub fn new(symbols: Vec<SymbolInfo>, updated_at: DateTime<Utc>) -> Self {
+        Self {
+            symbols: symbols.into_iter().map(|info| (info.symbol.clone(), info)).collect(),
+            updated_at: Some(updated_at),
+        }
+    }
+
+    pub fn get(&self, symbol: &str) -> Option<&SymbolInfo> {
+        self.symbols.get(symbol)
+    }
+
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    /// True until metadata has been loaded, in which case nothing can be checked
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    pub fn updated_at(&self) -> Option<DateTime<Utc>> {
+        self.updated_at
+    }
+
+    /// Whether the metadata should be fetched again
+    pub fn is_stale(&self, now: DateTime<Utc>) -> bool {
+        self.updated_at
+            .is_none_or(|updated| now - updated > Duration::hours(SYMBOL_CACHE_MAX_AGE_HOURS))
+    }
+
+    pub fn tick_size(&self, symbol: &str) -> Option<Decimal> {
+        self.get(symbol).map(|info| info.tick_size)
+    }
+
+    /// Check that a symbol is listed and currently trading
+    pub fn validate(&self, symbol: &str) -> Result<&SymbolInfo, CoinPeekError> {
+        let info = self.get(symbol).ok_or_else(|| CoinPeekError::InvalidSymbol {
+            symbol: symbol.to_string(),
+            reason: "not listed on the exchange".to_string(),
+        })?;
+
+        if !info.is_trading() {
+            return Err(CoinPeekError::InvalidSymbol {
+                symbol: symbol.to_string(),
+                reason: format!("not currently trading (status {})", info.status),
+            });
+        }
+
+        Ok(info)
+    }
+}
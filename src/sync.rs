@@ -0,0 +1,144 @@
+use crate::database::Database;
+use crate::error::CoinPeekError;
+use crate::exchange::MarketDataSource;
+use crate::timeframe::TimeFrame;
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use std::ops::RangeInclusive;
+
+/// Download candles opening between `start` and `end` and store them page by
+/// page as they arrive, waiting out rate limits. Returns the number of
+/// candles stored.
+///
+/// If a page fails, the pages before it are already saved and recorded as
+/// covered, so a later run only fetches what is still missing.
+pub async fn backfill_candles<S: MarketDataSource>(
+    source: &S,
+    db: &Database,
+    symbol: &str,
+    timeframe: TimeFrame,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<usize, CoinPeekError> {
+    let closed_end = last_closed_ms(timeframe, Utc::now());
+    store_range(source, db, symbol, timeframe, to_ms(start)..=to_ms(end), closed_end, true).await
+}
+
+/// Make sure the last `count` candles up to `now` are in the database,
+/// fetching only the ranges that haven't been downloaded yet. Closed bars
+/// that can be rolled up from stored lower-timeframe candles count as there
+/// already, so higher timeframes need few or no extra requests; read them
+/// back with `Database::get_candles_with_rollup`. The candle that is still
+/// open is always fetched again since it keeps changing. Rate limits are
+/// returned rather than waited out. Returns the number of candles stored.
+pub async fn fill_candle_gaps<S: MarketDataSource>(
+    source: &S,
+    db: &Database,
+    symbol: &str,
+    timeframe: TimeFrame,
+    count: usize,
+    now: DateTime<Utc>,
+) -> Result<usize, CoinPeekError> {
+    let now_ms = to_ms(now);
+    let open_bar = timeframe.bar_start(now_ms);
+    let window_start = open_bar.saturating_sub(count.saturating_sub(1) as u64 * timeframe.duration_ms());
+    let closed_end = open_bar.saturating_sub(1);
+
+    let mut covered = db.get_candle_coverage(symbol, timeframe.as_str()).await?;
+    for bar in db.get_rolled_up_candles(symbol, timeframe, count).await? {
+        if bar.timestamp < open_bar {
+            covered.push((bar.timestamp, bar.timestamp + timeframe.duration_ms() - 1));
+        }
+    }
+    let covered = merge_ranges(covered);
+
+    let mut stored = 0;
+    for (start_ms, end_ms) in missing_ranges(&covered, window_start, now_ms) {
+        stored += store_range(source, db, symbol, timeframe, start_ms..=end_ms, closed_end, false).await?;
+    }
+
+    Ok(stored)
+}
+
+/// Sort ranges by start and join the ones that overlap or touch
+fn merge_ranges(mut ranges: Vec<(u64, u64)>) -> Vec<(u64, u64)> {
+    ranges.sort_unstable();
+    let mut merged: Vec<(u64, u64)> = Vec::new();
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1.saturating_add(1) => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// Parts of `start..=end` not inside any of the `covered` ranges, which must
+/// be sorted by start and not overlap
+pub fn missing_ranges(covered: &[(u64, u64)], start: u64, end: u64) -> Vec<(u64, u64)> {
+    let mut missing = Vec::new();
+    let mut cursor = start;
+    for &(covered_start, covered_end) in covered {
+        if cursor > end {
+            break;
+        }
+        if covered_end < cursor {
+            continue;
+        }
+        if covered_start > cursor {
+            missing.push((cursor, end.min(covered_start - 1)));
+        }
+        cursor = covered_end.saturating_add(1);
+    }
+    if cursor <= end {
+        missing.push((cursor, end));
+    }
+    missing
+}
+
+/// Fetch and store candles opening in `range`, recording what was
+/// stored as covered. Coverage never extends past `closed_end`, so candles
+/// that were still open when fetched are downloaded again next time.
+async fn store_range<S: MarketDataSource>(
+    source: &S,
+    db: &Database,
+    symbol: &str,
+    timeframe: TimeFrame,
+    range: RangeInclusive<u64>,
+    closed_end: u64,
+    wait_on_rate_limit: bool,
+) -> Result<usize, CoinPeekError> {
+    let (start_ms, end_ms) = range.into_inner();
+    let interval = timeframe.as_str();
+    let pages = source.fetch_candle_range(symbol, interval, from_ms(start_ms), from_ms(end_ms), wait_on_rate_limit);
+    futures::pin_mut!(pages);
+
+    let mut stored = 0;
+    while let Some(page) = pages.next().await {
+        let page = page?;
+        db.store_candles(symbol, interval, &page).await?;
+        stored += page.len();
+        if let Some(last) = page.last() {
+            let page_end = (last.timestamp + timeframe.duration_ms() - 1).min(closed_end);
+            db.record_candle_coverage(symbol, interval, start_ms, page_end).await?;
+        }
+        log::info!("Backfilled {} {} candles for {}", stored, interval, symbol);
+    }
+
+    // Reaching the end without an error means nothing else traded in the range
+    db.record_candle_coverage(symbol, interval, start_ms, end_ms.min(closed_end)).await?;
+    Ok(stored)
+}
+
+/// Last millisecond of the most recent candle that has closed by `now`
+fn last_closed_ms(timeframe: TimeFrame, now: DateTime<Utc>) -> u64 {
+    timeframe.bar_start(to_ms(now)).saturating_sub(1)
+}
+
+fn to_ms(time: DateTime<Utc>) -> u64 {
+    time.timestamp_millis().max(0) as u64
+}
+
+fn from_ms(ms: u64) -> DateTime<Utc> {
+    DateTime::from_timestamp_millis(ms as i64).unwrap_or_default()
+}
@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+
+/// Candle intervals supported by the chart and the history tools. Serialized
+/// by their interval names, like `as_str`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TimeFrame {
+    #[serde(rename = "1m")]
+    M1,   // 1 minute
+    #[serde(rename = "5m")]
+    M5,   // 5 minutes
+    #[serde(rename = "15m")]
+    M15,  // 15 minutes
+    #[serde(rename = "30m")]
+    M30,  // 30 minutes
+    #[serde(rename = "1h")]
+    H1,   // 1 hour
+    #[serde(rename = "4h")]
+    H4,   // 4 hours
+    #[serde(rename = "1d")]
+    D1,   // 1 day
+    #[serde(rename = "1w")]
+    W1,   // 1 week
+}
+
+const MINUTE_MS: u64 = 60_000;
+
+/// Weekly candles open on Monday 00:00 UTC; the Unix epoch was a Thursday
+const WEEK_OFFSET_MS: u64 = 4 * 24 * 60 * MINUTE_MS;
+
+impl TimeFrame {
+    pub const ALL: [TimeFrame; 8] = [
+        TimeFrame::M1,
+        TimeFrame::M5,
+        TimeFrame::M15,
+        TimeFrame::M30,
+        TimeFrame::H1,
+        TimeFrame::H4,
+        TimeFrame::D1,
+        TimeFrame::W1,
+    ];
+
+    /// Interval name used by the exchange API and stored with each candle
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimeFrame::M1 => "1m",
+            TimeFrame::M5 => "5m",
+            TimeFrame::M15 => "15m",
+            TimeFrame::M30 => "30m",
+            TimeFrame::H1 => "1h",
+            TimeFrame::H4 => "4h",
+            TimeFrame::D1 => "1d",
+            TimeFrame::W1 => "1w",
+        }
+    }
+
+    pub fn parse(interval: &str) -> Option<TimeFrame> {
+        Self::ALL.into_iter().find(|timeframe| timeframe.as_str() == interval)
+    }
+
+    /// Number of candles shown in a chart
+    pub fn limit(&self) -> u8 {
+        match self {
+            TimeFrame::M1 => 100,
+            TimeFrame::M5 => 100,
+            TimeFrame::M15 => 100,
+            TimeFrame::M30 => 100,
+            TimeFrame::H1 => 100,
+            TimeFrame::H4 => 100,
+            TimeFrame::D1 => 100,
+            TimeFrame::W1 => 100,
+        }
+    }
+
+    /// Length of one candle in milliseconds
+    pub fn duration_ms(&self) -> u64 {
+        MINUTE_MS * match self {
+            TimeFrame::M1 => 1,
+            TimeFrame::M5 => 5,
+            TimeFrame::M15 => 15,
+            TimeFrame::M30 => 30,
+            TimeFrame::H1 => 60,
+            TimeFrame::H4 => 4 * 60,
+            TimeFrame::D1 => 24 * 60,
+            TimeFrame::W1 => 7 * 24 * 60,
+        }
+    }
+
+    /// Open time of the candle containing `timestamp_ms`
+    pub fn bar_start(&self, timestamp_ms: u64) -> u64 {
+        let offset = if *self == TimeFrame::W1 { WEEK_OFFSET_MS } else { 0 };
+        let shifted = timestamp_ms.saturating_sub(offset);
+        shifted - shifted % self.duration_ms() + offset
+    }
+}
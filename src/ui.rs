@@ -8,7 +8,10 @@ use ratatui::{
     Frame,
 };
 
-use crate::app::App;
+use rust_decimal::prelude::{Decimal, ToPrimitive};
+
+use crate::app::{AlertCondition, AlertEditor, AlertField, AlertMode, App};
+use crate::config::ChartSource;
 
 /// Draws the main crypto dashboard UI
 pub fn render_dashboard(
@@ -88,12 +91,15 @@ pub fn render_dashboard(
     // Right panel: Detailed view of selected crypto
     render_crypto_details(f, main_layout[1], app);
 
-    // Footer hint
-    let footer_text = Text::from(Line::from(vec![
-        Span::styled("Press ", Style::default().fg(Color::Gray)),
-        Span::styled("?", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
-        Span::styled(" for help", Style::default().fg(Color::Gray)),
-    ]));
+    // Footer hint, or the outcome of the last action
+    let footer_text = match app.current_status_message() {
+        Some(message) => Text::from(Line::from(Span::styled(message, Style::default().fg(Color::Green)))),
+        None => Text::from(Line::from(vec![
+            Span::styled("Press ", Style::default().fg(Color::Gray)),
+            Span::styled("?", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::styled(" for help", Style::default().fg(Color::Gray)),
+        ])),
+    };
     let footer_widget = Paragraph::new(footer_text)
         .alignment(ratatui::layout::Alignment::Center);
     f.render_widget(footer_widget, vertical_layout[1]);
@@ -197,7 +203,7 @@ fn render_crypto_list(f: &mut Frame, area: Rect, app: &App) {
             ),
             Span::raw(": "),
             Span::styled(
-                format!("${:.2}", price_info.price),
+                format!("${}", app.format_price(&price_info.symbol, price_info.price)),
                 Style::default()
                     .fg(if is_selected { Color::Yellow } else { Color::White })
                     .add_modifier(Modifier::BOLD)
@@ -219,7 +225,11 @@ fn render_crypto_list(f: &mut Frame, area: Rect, app: &App) {
             ),
             Span::raw("  "),
             Span::styled(
-                format!("H:{:.2} L:{:.2}", price_info.high_24h, price_info.low_24h),
+                format!(
+                    "H:{} L:{}",
+                    app.format_price(&price_info.symbol, price_info.high_24h),
+                    app.format_price(&price_info.symbol, price_info.low_24h)
+                ),
                 Style::default()
                     .fg(if is_selected { Color::Gray } else { Color::Gray })
                     .add_modifier(if is_selected { Modifier::DIM } else { Modifier::empty() })
@@ -261,36 +271,29 @@ fn render_crypto_details(f: &mut Frame, area: Rect, app: &App) {
                 Constraint::Length(2), // 24h change
                 Constraint::Length(2), // 24h high/low
                 Constraint::Length(2), // Volume
+                Constraint::Length(2), // Bid/ask
             ])
             .split(details_area);
 
-        // Symbol and name (mock full name for now)
-        let symbol_name = match selected_crypto.symbol.as_str() {
-            "BTCUSDT" => "Bitcoin",
-            "ETHUSDT" => "Ethereum",
-            "BNBUSDT" => "Binance Coin",
-            "ADAUSDT" => "Cardano",
-            "SOLUSDT" => "Solana",
-            "DOTUSDT" => "Polkadot",
-            "DOGEUSDT" => "Dogecoin",
-            "AVAXUSDT" => "Avalanche",
-            "LTCUSDT" => "Litecoin",
-            "LINKUSDT" => "Chainlink",
-            "XRPUSDT" => "XRP",
-            "MATICUSDT" => "Polygon",
-            "UNIUSDT" => "Uniswap",
-            "ALGOUSDT" => "Algorand",
-            "VETUSDT" => "VeChain",
-            _ => "Unknown",
+        // Symbol and trading pair from the exchange's symbol metadata
+        let symbol_info = app.symbol_registry.get(&selected_crypto.symbol);
+        let pair_line = match symbol_info {
+            Some(info) if info.is_trading() => Line::from(vec![
+                Span::styled(info.pair_name(), Style::default().fg(Color::White)),
+                Span::styled(format!("  tick {}", info.tick_size.normalize()), Style::default().fg(Color::Gray)),
+            ]),
+            Some(info) => Line::from(vec![
+                Span::styled(info.pair_name(), Style::default().fg(Color::White)),
+                Span::styled(format!("  {}", info.status), Style::default().fg(Color::Red)),
+            ]),
+            None => Line::from(Span::styled("Symbol metadata unavailable", Style::default().fg(Color::Gray))),
         };
 
         let symbol_text = Text::from(vec![
             Line::from(vec![
                 Span::styled(&selected_crypto.symbol, Style::default().bold().fg(Color::Cyan)),
-                Span::raw(" - "),
-                Span::styled(symbol_name, Style::default().fg(Color::White)),
             ]),
-            Line::from(""),
+            pair_line,
         ]);
         let symbol_widget = Paragraph::new(symbol_text);
         f.render_widget(symbol_widget, details_layout[0]);
@@ -299,12 +302,15 @@ fn render_crypto_details(f: &mut Frame, area: Rect, app: &App) {
         let price_text = Text::from(vec![
             Line::from(vec![
                 Span::styled(
-                    format!("${:.2}", selected_crypto.price),
+                    format!("${}", app.format_price(&selected_crypto.symbol, selected_crypto.price)),
                     Style::default().bold().fg(Color::Yellow),
                 ),
             ]),
             Line::from(vec![
-                Span::styled("USDT", Style::default().fg(Color::Gray)),
+                Span::styled(
+                    symbol_info.map_or("USDT", |info| info.quote_asset.as_str()),
+                    Style::default().fg(Color::Gray),
+                ),
             ]),
         ]);
         let price_widget = Paragraph::new(price_text);
@@ -313,13 +319,17 @@ fn render_crypto_details(f: &mut Frame, area: Rect, app: &App) {
         // Price chart (sparkline) with loading animation
         let chart_area = details_layout[2];
         if !app.selected_candles.is_empty() {
+            // Plot closes relative to the lowest one so sub-cent moves stay visible
+            let min_close = app.selected_candles.iter().map(|c| c.close).min().unwrap_or_default();
+            let max_close = app.selected_candles.iter().map(|c| c.close).max().unwrap_or_default();
+            let range = (max_close - min_close).max(Decimal::new(1, 28));
             let chart_data: Vec<u64> = app.selected_candles.iter()
-                .map(|candle| (candle.close * 100.0) as u64) // Convert to cents for better precision
+                .map(|candle| ((candle.close - min_close) / range * Decimal::from(1000)).to_u64().unwrap_or(0) + 1)
                 .collect();
 
             let sparkline = Sparkline::default()
                 .block(Block::default()
-                    .title("📈 5m Chart")
+                    .title(chart_title(app))
                     .borders(Borders::ALL)
                     .border_type(BorderType::Rounded)
                     .border_style(Style::default().fg(Color::Green)))
@@ -348,7 +358,7 @@ fn render_crypto_details(f: &mut Frame, area: Rect, app: &App) {
 
             let loading_widget = Paragraph::new(loading_text)
                 .block(Block::default()
-                    .title("📈 5m Chart")
+                    .title(chart_title(app))
                     .borders(Borders::ALL)
                     .border_type(BorderType::Rounded)
                     .border_style(Style::default().fg(Color::Yellow)))
@@ -391,12 +401,12 @@ fn render_crypto_details(f: &mut Frame, area: Rect, app: &App) {
             Line::from(vec![
                 Span::raw("24h Range: "),
                 Span::styled(
-                    format!("H: ${:.2}", selected_crypto.high_24h),
+                    format!("H: ${}", app.format_price(&selected_crypto.symbol, selected_crypto.high_24h)),
                     Style::default().fg(Color::Green),
                 ),
                 Span::raw(" / "),
                 Span::styled(
-                    format!("L: ${:.2}", selected_crypto.low_24h),
+                    format!("L: ${}", app.format_price(&selected_crypto.symbol, selected_crypto.low_24h)),
                     Style::default().fg(Color::Red),
                 ),
             ]),
@@ -412,10 +422,38 @@ fn render_crypto_details(f: &mut Frame, area: Rect, app: &App) {
                     format!("{:.0}", selected_crypto.volume),
                     Style::default().fg(Color::Blue).bold(),
                 ),
+                Span::styled(
+                    format!(" (${:.0})", selected_crypto.quote_volume),
+                    Style::default().fg(Color::Gray),
+                ),
             ]),
         ]);
         let volume_widget = Paragraph::new(volume_text);
         f.render_widget(volume_widget, details_layout[5]);
+
+        // Best bid/ask, only known once a ticker with order book data arrived
+        if selected_crypto.bid_price > Decimal::ZERO && selected_crypto.ask_price > Decimal::ZERO {
+            let spread = selected_crypto.ask_price - selected_crypto.bid_price;
+            let bid_ask_text = Text::from(vec![
+                Line::from(vec![
+                    Span::raw("Bid/Ask: "),
+                    Span::styled(
+                        format!("${}", app.format_price(&selected_crypto.symbol, selected_crypto.bid_price)),
+                        Style::default().fg(Color::Green),
+                    ),
+                    Span::raw(" / "),
+                    Span::styled(
+                        format!("${}", app.format_price(&selected_crypto.symbol, selected_crypto.ask_price)),
+                        Style::default().fg(Color::Red),
+                    ),
+                    Span::styled(
+                        format!("  spread {}", app.format_price(&selected_crypto.symbol, spread)),
+                        Style::default().fg(Color::Gray),
+                    ),
+                ]),
+            ]);
+            f.render_widget(Paragraph::new(bid_ask_text), details_layout[6]);
+        }
     } else {
         // No crypto selected (shouldn't happen, but just in case)
         let no_selection_text = Text::from(vec![
@@ -426,6 +464,14 @@ fn render_crypto_details(f: &mut Frame, area: Rect, app: &App) {
     }
 }
 
+/// Chart block title naming where the candles come from
+fn chart_title(app: &App) -> String {
+    match app.chart_source {
+        ChartSource::Exchange => "📈 5m Chart".to_string(),
+        ChartSource::Ticks => format!("📈 {}s Tick Chart", app.config.chart.tick_bar_seconds),
+    }
+}
+
 fn render_help_screen(f: &mut Frame, area: Rect) {
     // Render background overlay first (makes it opaque)
     let background = Block::default()
@@ -533,7 +579,11 @@ fn render_help_screen(f: &mut Frame, area: Rect) {
             Span::styled("o", Style::default().fg(Color::Blue)),
             Span::raw(" Toggle offline | "),
             Span::styled("p", Style::default().fg(Color::Blue)),
-            Span::raw(" Pause/resume"),
+            Span::raw(" Pause/resume | "),
+            Span::styled("b", Style::default().fg(Color::Blue)),
+            Span::raw(" Chart source | "),
+            Span::styled("e", Style::default().fg(Color::Blue)),
+            Span::raw(" Export candles"),
         ]),
     ]);
     let data_widget = Paragraph::new(data_text);
@@ -597,11 +647,12 @@ fn render_alert_management(f: &mut Frame, area: Rect, app: &App) {
 
     let alert_layout = Layout::default()
         .direction(Direction::Vertical)
-        .margin(2)
+        .margin(1)
         .constraints([
             Constraint::Length(2),  // Header with stats
-            Constraint::Length(15), // Alert list
-            Constraint::Length(3),  // Instructions
+            Constraint::Min(5),     // Alert list
+            Constraint::Length(4),  // Recent notifications
+            Constraint::Length(14), // Editor form or instructions
         ])
         .split(alert_area);
 
@@ -616,84 +667,183 @@ fn render_alert_management(f: &mut Frame, area: Rect, app: &App) {
                                enabled_count, total_count, recent_count),
                          Style::default().fg(Color::Cyan).bold()),
         ]),
-        Line::from(vec![
-            Span::styled("Recent Alerts:", Style::default().fg(Color::Yellow)),
-        ]),
     ]);
     let header_widget = Paragraph::new(header_text);
     f.render_widget(header_widget, alert_layout[0]);
 
-    // Alert list and recent notifications
-    if app.alerts.is_empty() && app.get_recent_alerts().is_empty() {
+    // Configured alerts, scrolled to keep the selection visible
+    if app.alerts.is_empty() {
         let empty_text = Text::from(vec![
             Line::from("No alerts configured"),
             Line::from(""),
-            Line::from("Create alerts to get notified when prices hit your targets!"),
+            Line::from("Press n to get notified when prices hit your targets!"),
         ]);
         let empty_widget = Paragraph::new(empty_text)
             .style(Style::default().fg(Color::Gray));
         f.render_widget(empty_widget, alert_layout[1]);
     } else {
-        let mut alert_lines = Vec::new();
-
-        // Show recent alerts first
-        for (alert_msg, timestamp) in app.get_recent_alerts().iter().rev().take(3) {
-            alert_lines.push(Line::from(vec![
-                Span::styled("🔔 ", Style::default().fg(Color::Green)),
-                Span::styled(alert_msg, Style::default().fg(Color::White)),
-            ]));
-        }
+        let visible_rows = alert_layout[1].height as usize;
+        let first_row = app.selected_alert.saturating_sub(visible_rows.saturating_sub(1));
 
-        if !app.get_recent_alerts().is_empty() && !app.alerts.is_empty() {
-            alert_lines.push(Line::from(""));
-        }
-
-        // Show configured alerts
-        for alert in &app.alerts {
+        let mut alert_lines = Vec::new();
+        for (index, alert) in app.alerts.iter().enumerate().skip(first_row).take(visible_rows) {
+            let selected = index == app.selected_alert;
             let status_icon = if alert.enabled { "🟢" } else { "🔴" };
-            let condition_text = match &alert.condition {
-                crate::app::AlertCondition::PriceAbove(threshold) =>
-                    format!("Price > ${:.2}", threshold),
-                crate::app::AlertCondition::PriceBelow(threshold) =>
-                    format!("Price < ${:.2}", threshold),
-                crate::app::AlertCondition::PercentChangeAbove(threshold) =>
-                    format!("Change > {:.1}%", threshold),
-                crate::app::AlertCondition::PercentChangeBelow(threshold) =>
-                    format!("Change < {:.1}%", threshold),
-                crate::app::AlertCondition::VolumeSpike(threshold) =>
-                    format!("Volume > {:.0}", threshold),
+            let row_style = if selected {
+                Style::default().bg(Color::DarkGray)
+            } else {
+                Style::default()
             };
 
             alert_lines.push(Line::from(vec![
+                Span::raw(if selected { "▶ " } else { "  " }),
                 Span::raw(status_icon),
                 Span::raw(" "),
                 Span::styled(&alert.symbol, Style::default().fg(Color::Cyan).bold()),
                 Span::raw(" - "),
-                Span::styled(condition_text, Style::default().fg(Color::Yellow)),
+                Span::styled(condition_text(&alert.condition), Style::default().fg(Color::Yellow)),
                 Span::raw(format!(" ({} triggers)", alert.trigger_count)),
-            ]));
+                Span::styled(
+                    alert.message.as_deref().map(|m| format!(" \"{}\"", m)).unwrap_or_default(),
+                    Style::default().fg(Color::Gray),
+                ),
+            ]).style(row_style));
         }
 
-        let alert_text = Text::from(alert_lines);
-        let alert_widget = Paragraph::new(alert_text);
+        let alert_widget = Paragraph::new(Text::from(alert_lines));
         f.render_widget(alert_widget, alert_layout[1]);
     }
 
-    // Instructions
-    let instructions_text = Text::from(vec![
-        Line::from(vec![
-            Span::styled("Instructions:", Style::default().fg(Color::Yellow).bold()),
-        ]),
+    // Recent notifications
+    let mut recent_lines = vec![
         Line::from(vec![
-            Span::styled("• Create alerts to monitor price movements", Style::default().fg(Color::White)),
-        ]),
-        Line::from(vec![
-            Span::styled("• Alerts trigger terminal notifications", Style::default().fg(Color::White)),
+            Span::styled("Recent Alerts:", Style::default().fg(Color::Yellow)),
         ]),
+    ];
+    for (alert_msg, _) in app.get_recent_alerts().iter().rev().take(3) {
+        recent_lines.push(Line::from(vec![
+            Span::styled("🔔 ", Style::default().fg(Color::Green)),
+            Span::styled(alert_msg, Style::default().fg(Color::White)),
+        ]));
+    }
+    f.render_widget(Paragraph::new(Text::from(recent_lines)), alert_layout[2]);
+
+    match &app.alert_editor {
+        Some(editor) => render_alert_editor(f, alert_layout[3], editor),
+        None => {
+            let instructions_text = Text::from(vec![
+                Line::from(vec![
+                    Span::styled("Instructions:", Style::default().fg(Color::Yellow).bold()),
+                ]),
+                Line::from(vec![
+                    Span::styled("• ↑/↓ select | n new | Enter/e edit", Style::default().fg(Color::White)),
+                ]),
+                Line::from(vec![
+                    Span::styled("• Space/t enable or disable | x/Del delete", Style::default().fg(Color::White)),
+                ]),
+                Line::from(vec![
+                    Span::styled("• Esc to close | Alerts are saved in coinpeek.db", Style::default().fg(Color::Gray)),
+                ]),
+            ]);
+            let instructions_widget = Paragraph::new(instructions_text);
+            f.render_widget(instructions_widget, alert_layout[3]);
+        }
+    }
+}
+
+/// The create/edit form at the bottom of the alert manager
+fn render_alert_editor(f: &mut Frame, area: Rect, editor: &AlertEditor) {
+    let field_line = |field: AlertField, label: &str, value: String| {
+        let active = editor.field == field;
+        let value = match field {
+            _ if !editor.field_applies(field) => "-".to_string(),
+            AlertField::Symbol | AlertField::Condition | AlertField::Timeframe | AlertField::Mode if active => {
+                format!("◀ {} ▶", value)
+            }
+            AlertField::Symbol | AlertField::Condition | AlertField::Timeframe | AlertField::Mode => value,
+            _ if active => format!("{}█", value),
+            _ => value,
+        };
+        let label_style = if active {
+            Style::default().fg(Color::Yellow).bold()
+        } else {
+            Style::default().fg(Color::Gray)
+        };
         Line::from(vec![
-            Span::styled("• Esc to close | This is a preview - full management coming!", Style::default().fg(Color::Gray)),
-        ]),
+            Span::styled(format!("{:<13}", label), label_style),
+            Span::styled(value, Style::default().fg(Color::White)),
+        ])
+    };
+
+    let title = if editor.editing.is_some() { "Edit alert" } else { "New alert" };
+    let status = match &editor.error {
+        Some(error) => Span::styled(format!("⚠️  {}", error), Style::default().fg(Color::Red)),
+        None => Span::styled(
+            format!("{}: Tab/↑↓ field | ←/→ choose | Enter save | Esc cancel", title),
+            Style::default().fg(Color::Gray),
+        ),
+    };
+
+    let form_text = Text::from(vec![
+        field_line(AlertField::Symbol, "Symbol", editor.selected_symbol().unwrap_or("-").to_string()),
+        field_line(AlertField::Condition, "Condition", editor.condition_kind.label().to_string()),
+        field_line(AlertField::Rule, "Rule", editor.rule.clone()),
+        field_line(AlertField::Threshold, "Threshold", editor.threshold.clone()),
+        field_line(AlertField::Hysteresis, "Re-arm %", editor.hysteresis.clone()),
+        field_line(AlertField::Window, "Window min", editor.window.clone()),
+        field_line(AlertField::Period, "Period", editor.period.clone()),
+        field_line(AlertField::Timeframe, "Timeframe", editor.timeframe.as_str().to_string()),
+        field_line(AlertField::Cooldown, "Cooldown min", editor.cooldown.clone()),
+        field_line(AlertField::Mode, "Mode", match editor.mode {
+            AlertMode::Recurring => "Recurring".to_string(),
+            AlertMode::Once => "Once, then disable".to_string(),
+        }),
+        field_line(AlertField::Sinks, "Notify", match editor.sinks.as_str() {
+            "" if editor.field != AlertField::Sinks => "Default sinks".to_string(),
+            sinks => sinks.to_string(),
+        }),
+        field_line(AlertField::Message, "Message", editor.message.clone()),
+        Line::from(""),
+        Line::from(status),
     ]);
-    let instructions_widget = Paragraph::new(instructions_text);
-    f.render_widget(instructions_widget, alert_layout[2]);
+    f.render_widget(Paragraph::new(form_text), area);
+}
+
+/// Short description of an alert condition for the alert list
+fn condition_text(condition: &AlertCondition) -> String {
+    match condition {
+        AlertCondition::PriceAbove(threshold) => format!("Price > ${}", threshold),
+        AlertCondition::PriceBelow(threshold) => format!("Price < ${}", threshold),
+        AlertCondition::CrossesAbove { level, hysteresis_percent } => {
+            format!("Crosses ↑ ${} (re-arm -{}%)", level, hysteresis_percent)
+        }
+        AlertCondition::CrossesBelow { level, hysteresis_percent } => {
+            format!("Crosses ↓ ${} (re-arm +{}%)", level, hysteresis_percent)
+        }
+        AlertCondition::PercentChangeAbove(threshold) => format!("Change > {:.1}%", threshold),
+        AlertCondition::PercentChangeBelow(threshold) => format!("Change < {:.1}%", threshold),
+        AlertCondition::VolumeSpike(threshold) => format!("Volume > {:.0}", threshold),
+        AlertCondition::PercentMove { percent, window_minutes } => format!("Move {:+.1}% in {}m", percent, window_minutes),
+        AlertCondition::PriceMove { amount, window_minutes } if amount.is_sign_negative() => {
+            format!("Move -${} in {}m", amount.abs(), window_minutes)
+        }
+        AlertCondition::PriceMove { amount, window_minutes } => format!("Move +${} in {}m", amount, window_minutes),
+        AlertCondition::RangeBreakout { window_minutes } => format!("Breaks {}m range", window_minutes),
+        AlertCondition::NewHigh { window_minutes } => format!("New {}m high", window_minutes),
+        AlertCondition::NewLow { window_minutes } => format!("New {}m low", window_minutes),
+        AlertCondition::RsiAbove { level, period, timeframe } => format!("{} RSI({}) > {}", timeframe.as_str(), period, level),
+        AlertCondition::RsiBelow { level, period, timeframe } => format!("{} RSI({}) < {}", timeframe.as_str(), period, level),
+        AlertCondition::GoldenCross { fast, slow, timeframe } => format!("{} SMA {} ↑ SMA {}", timeframe.as_str(), fast, slow),
+        AlertCondition::DeathCross { fast, slow, timeframe } => format!("{} SMA {} ↓ SMA {}", timeframe.as_str(), fast, slow),
+        AlertCondition::AboveUpperBand { period, std_devs, timeframe } => {
+            format!("{} > BB({}, {}) upper", timeframe.as_str(), period, std_devs)
+        }
+        AlertCondition::BelowLowerBand { period, std_devs, timeframe } => {
+            format!("{} < BB({}, {}) lower", timeframe.as_str(), period, std_devs)
+        }
+        AlertCondition::VolumeAboveAverage { multiple, period, timeframe } => {
+            format!("{} volume > {}x avg({})", timeframe.as_str(), multiple, period)
+        }
+        AlertCondition::Rule(rule) => rule.to_string(),
+    }
 }
@@ -0,0 +1,45 @@
+use rust_decimal::{Decimal, RoundingStrategy};
+
+/// Never show more decimals than this, even for very small prices
+const MAX_PRICE_DECIMALS: u32 = 8;
+
+/// Decimals implied by a tick size, e.g. 0.00001000 -> 5
+pub fn tick_decimals(tick_size: Decimal) -> u32 {
+    tick_size.normalize().scale()
+}
+
+/// Decimals to show for a price when the symbol's tick size is unknown.
+///
+/// Prices of 1 and up get cents; smaller prices keep four significant
+/// digits, so 0.02345 isn't shown as 0.02.
+pub fn default_price_decimals(price: Decimal) -> u32 {
+    let mut scaled = price.abs();
+    if scaled >= Decimal::ONE || scaled.is_zero() {
+        return 2;
+    }
+
+    let mut leading_zeros = 0;
+    while scaled < Decimal::ONE && leading_zeros < MAX_PRICE_DECIMALS {
+        scaled *= Decimal::TEN;
+        leading_zeros += 1;
+    }
+    (leading_zeros + 3).min(MAX_PRICE_DECIMALS)
+}
+
+/// Round a price to the nearest multiple of the tick size
+pub fn round_to_tick(price: Decimal, tick_size: Decimal) -> Decimal {
+    if tick_size.is_zero() {
+        return price;
+    }
+    ((price / tick_size).round_dp_with_strategy(0, RoundingStrategy::MidpointAwayFromZero) * tick_size).normalize()
+}
+
+/// Format a price for display, on the tick grid when the tick size is known
+pub fn format_price(price: Decimal, tick_size: Option<Decimal>) -> String {
+    let (price, decimals) = match tick_size.filter(|tick| !tick.is_zero()) {
+        Some(tick) => (round_to_tick(price, tick), tick_decimals(tick)),
+        None => (price, default_price_decimals(price)),
+    };
+    let rounded = price.round_dp_with_strategy(decimals, RoundingStrategy::MidpointAwayFromZero);
+    format!("{:.*}", decimals as usize, rounded)
+}
@@ -1,6 +1,12 @@
 use crate::app::{App, SortDirection, AlertCondition};
-use crate::binance::{PriceInfo, Candle};
+use crate::binance::{BinanceClient, PriceInfo, Candle};
 use crate::config::Config;
+use crate::exchange::{MarketDataSource, StreamEvent};
+use crate::symbols::{SymbolInfo, SymbolRegistry};
+use crate::timeframe::TimeFrame;
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use rust_decimal::prelude::ToPrimitive;
 use yew::prelude::*;
 use wasm_bindgen::prelude::*;
 use web_sys::console;
@@ -13,12 +19,20 @@ extern "C" {
     fn updateCoinPeekChart(data: &str);
 }
 
+/// Symbols shown in the browser
+const WEB_SYMBOLS: [&str; 10] = ["BTCUSDT", "ETHUSDT", "BNBUSDT", "ADAUSDT", "SOLUSDT", "DOTUSDT", "DOGEUSDT", "AVAXUSDT", "LTCUSDT", "LINKUSDT"];
+
 // Web-specific storage utilities
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct CoinPeekStorage {
     pub config: Config,
     pub price_data: Vec<PriceInfo>,
     pub last_update: Option<String>,
+    // Exchange metadata for WEB_SYMBOLS, refreshed once a day
+    #[serde(default)]
+    pub symbols: Vec<SymbolInfo>,
+    #[serde(default)]
+    pub symbols_updated_at: Option<DateTime<Utc>>,
 }
 
 impl Default for CoinPeekStorage {
@@ -27,56 +41,19 @@ impl Default for CoinPeekStorage {
             config: Config::default(),
             price_data: Vec::new(),
             last_update: None,
+            symbols: Vec::new(),
+            symbols_updated_at: None,
         }
     }
 }
 
 pub struct WebApp {
     app: App,
+    source: BinanceClient,
     storage: CoinPeekStorage,
     _price_refresh_timer: Option<Interval>,
 }
 
-#[derive(Clone, Debug)]
-pub enum TimeFrame {
-    M1,   // 1 minute
-    M5,   // 5 minutes
-    M15,  // 15 minutes
-    M30,  // 30 minutes
-    H1,   // 1 hour
-    H4,   // 4 hours
-    D1,   // 1 day
-    W1,   // 1 week
-}
-
-impl TimeFrame {
-    pub fn as_str(&self) -> &'static str {
-        match self {
-            TimeFrame::M1 => "1m",
-            TimeFrame::M5 => "5m",
-            TimeFrame::M15 => "15m",
-            TimeFrame::M30 => "30m",
-            TimeFrame::H1 => "1h",
-            TimeFrame::H4 => "4h",
-            TimeFrame::D1 => "1d",
-            TimeFrame::W1 => "1w",
-        }
-    }
-
-    pub fn limit(&self) -> u8 {
-        match self {
-            TimeFrame::M1 => 100,
-            TimeFrame::M5 => 100,
-            TimeFrame::M15 => 100,
-            TimeFrame::M30 => 100,
-            TimeFrame::H1 => 100,
-            TimeFrame::H4 => 100,
-            TimeFrame::D1 => 100,
-            TimeFrame::W1 => 100,
-        }
-    }
-}
-
 pub enum WebMsg {
     LoadFromStorage,
     SaveToStorage,
@@ -90,11 +67,13 @@ pub enum WebMsg {
     TogglePause,
     Search(String),
     RefreshData,
+    LoadSymbols,
+    UpdateSymbols(Vec<SymbolInfo>),
     CreateAlert(String, AlertCondition, Option<String>),
     LoadCandles(String, TimeFrame),
     UpdateCandles(Vec<Candle>),
     ChangeTimeFrame(TimeFrame),
-    WebSocketUpdate(crate::binance::IndividualTickerUpdate),
+    StreamUpdate(StreamEvent),
     ConnectWebSocket,
     DisconnectWebSocket,
 }
@@ -111,6 +90,12 @@ impl Component for WebApp {
         if !storage.price_data.is_empty() {
             app.update_prices(storage.price_data.clone());
         }
+        if let Some(updated_at) = storage.symbols_updated_at {
+            app.set_symbol_registry(SymbolRegistry::new(storage.symbols.clone(), updated_at));
+        }
+        if app.symbol_registry.is_stale(Utc::now()) {
+            ctx.link().send_message(WebMsg::LoadSymbols);
+        }
 
         // Set up automatic price refresh timer (every 10 seconds)
         let link = ctx.link().clone();
@@ -118,7 +103,10 @@ impl Component for WebApp {
             link.send_message(WebMsg::RefreshData);
         }));
 
-        Self { app, storage, _price_refresh_timer: price_refresh_timer }
+        let source = BinanceClient::new(&storage.config.api)
+            .expect("browser Binance client construction is infallible");
+
+        Self { app, source, storage, _price_refresh_timer: price_refresh_timer }
     }
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
@@ -139,8 +127,10 @@ impl Component for WebApp {
                 true
             }
             WebMsg::UpdatePrices(prices) => {
-                self.app.update_prices(prices.clone());
-                self.storage.price_data = prices;
+                self.app.set_throttled_until(self.source.throttled_until());
+                // Merge so symbols that failed this round keep their last price
+                self.app.apply_price_updates(prices);
+                self.storage.price_data = self.app.all_price_infos.clone();
                 self.storage.last_update = Some(chrono::Utc::now().to_rfc3339());
                 let _ = Self::save_to_local_storage(&self.storage);
                 true
@@ -189,10 +179,33 @@ impl Component for WebApp {
                 true
             }
             WebMsg::RefreshData => {
+                // Reflect throttling from earlier requests; the shared limiter
+                // refuses this one locally if we're still held back
+                self.app.set_throttled_until(self.source.throttled_until());
+
                 // Trigger API refresh
-                ctx.link().send_future(async {
-                    match crate::binance::fetch_price_infos(&["BTCUSDT", "ETHUSDT", "BNBUSDT", "ADAUSDT", "SOLUSDT", "DOTUSDT", "DOGEUSDT", "AVAXUSDT", "LTCUSDT", "LINKUSDT"]).await {
-                        Ok(prices) => WebMsg::UpdatePrices(prices),
+                let source = self.source.clone();
+                // Skip symbols the exchange says are delisted or halted
+                let registry = &self.app.symbol_registry;
+                let symbols: Vec<&'static str> = WEB_SYMBOLS
+                    .into_iter()
+                    .filter(|symbol| registry.is_empty() || registry.validate(symbol).is_ok())
+                    .collect();
+                ctx.link().send_future(async move {
+                    match source.fetch_price_infos(&symbols).await {
+                        Ok(results) => {
+                            let prices = results
+                                .into_iter()
+                                .filter_map(|r| match r.result {
+                                    Ok(price_info) => Some(price_info),
+                                    Err(e) => {
+                                        console::log_1(&format!("Failed to fetch {}: {}", r.symbol, e).into());
+                                        None
+                                    }
+                                })
+                                .collect();
+                            WebMsg::UpdatePrices(prices)
+                        }
                         Err(e) => {
                             console::log_1(&format!("API Error: {:?}", e).into());
                             WebMsg::LoadFromStorage
@@ -201,13 +214,42 @@ impl Component for WebApp {
                 });
                 true
             }
+            WebMsg::LoadSymbols => {
+                let source = self.source.clone();
+                ctx.link().send_future(async move {
+                    match source.fetch_symbols().await {
+                        Ok(symbols) => WebMsg::UpdateSymbols(symbols),
+                        Err(e) => {
+                            console::log_1(&format!("Failed to load symbol metadata: {}", e).into());
+                            WebMsg::LoadFromStorage
+                        }
+                    }
+                });
+                false
+            }
+            WebMsg::UpdateSymbols(symbols) => {
+                let now = Utc::now();
+                self.storage.symbols = symbols.into_iter().filter(|info| WEB_SYMBOLS.contains(&info.symbol.as_str())).collect();
+                self.storage.symbols_updated_at = Some(now);
+                let _ = Self::save_to_local_storage(&self.storage);
+
+                let registry = SymbolRegistry::new(self.storage.symbols.clone(), now);
+                for symbol in WEB_SYMBOLS {
+                    if let Err(e) = registry.validate(symbol) {
+                        console::log_1(&format!("Skipping {}", e).into());
+                    }
+                }
+                self.app.set_symbol_registry(registry);
+                true
+            }
             WebMsg::CreateAlert(symbol, condition, message) => {
                 let _ = self.app.create_alert(symbol, condition, message);
                 true
             }
             WebMsg::LoadCandles(symbol, timeframe) => {
+                let source = self.source.clone();
                 ctx.link().send_future(async move {
-                    match crate::binance::fetch_candles(&symbol, timeframe.as_str(), timeframe.limit()).await {
+                    match source.fetch_candles(&symbol, timeframe.as_str(), timeframe.limit()).await {
                         Ok(candles) => WebMsg::UpdateCandles(candles),
                         Err(e) => {
                             web_sys::console::log_1(&format!("Failed to load candles: {:?}", e).into());
@@ -228,10 +270,19 @@ impl Component for WebApp {
                 }
                 true
             }
-            WebMsg::WebSocketUpdate(update) => {
-                // Update price data from WebSocket
-                let price_info = crate::binance::websocket_data_to_price_info(&update);
-                self.app.update_prices(vec![price_info]);
+            WebMsg::StreamUpdate(event) => {
+                match event {
+                    StreamEvent::Ticker(price_info) => {
+                        // Merge price data from WebSocket
+                        self.app.apply_price_updates(vec![price_info]);
+                    }
+                    StreamEvent::Connected => self.app.set_stream_connected(true),
+                    StreamEvent::Skipped(e) => console::log_1(&format!("Skipping ticker update: {}", e).into()),
+                    StreamEvent::Disconnected(reason) => {
+                        self.app.set_stream_connected(false);
+                        console::log_1(&format!("Ticker stream disconnected: {}", reason).into());
+                    }
+                }
                 true
             }
             WebMsg::ConnectWebSocket => {
@@ -249,18 +300,10 @@ impl Component for WebApp {
                     "LINKUSDT".to_string(),
                 ];
 
-                let link = ctx.link().clone();
-                ctx.link().send_future(async move {
-                    let on_message = move |update: crate::binance::IndividualTickerUpdate| {
-                        link.send_message(WebMsg::WebSocketUpdate(update));
-                    };
-
-                    if let Err(e) = crate::binance::create_price_websocket(symbols, on_message) {
-                        console::log_1(&format!("WebSocket connection failed: {:?}", e).into());
-                    }
-
-                    WebMsg::LoadFromStorage // Dummy return
-                });
+                match self.source.subscribe_tickers(&symbols) {
+                    Ok(stream) => ctx.link().send_stream(stream.map(WebMsg::StreamUpdate)),
+                    Err(e) => console::log_1(&format!("WebSocket connection failed: {:?}", e).into()),
+                }
                 true
             }
             WebMsg::DisconnectWebSocket => {
@@ -354,7 +397,7 @@ impl Component for WebApp {
                         html! {
                             <div class={classes!("table-row", if is_selected { "selected" } else { "" })} {onclick}>
                                 <div class="col-symbol">{ &price.symbol }</div>
-                                <div class="col-price">{ format!("${:.2}", price.price) }</div>
+                                <div class="col-price">{ format!("${}", self.app.format_price(&price.symbol, price.price)) }</div>
                                 <div class={classes!("col-change", if price.price_change_percent >= 0.0 { "positive" } else { "negative" })}>
                                     { format!("{:+.2}%", price.price_change_percent) }
                                 </div>
@@ -368,12 +411,17 @@ impl Component for WebApp {
                     html! {
                         <div class="selected-info">
                             <h3>{ format!("📊 {} Details", selected.symbol) }</h3>
+                            { if let Some(info) = self.app.symbol_registry.get(&selected.symbol) {
+                                html! { <div class="pair-info">{ format!("{} · tick {} · {}", info.pair_name(), info.tick_size.normalize(), info.status) }</div> }
+                            } else {
+                                html! {}
+                            } }
                             <div class="details-grid">
-                                <div>{ format!("Price: ${:.4}", selected.price) }</div>
-                                <div>{ format!("High 24h: ${:.4}", selected.high_24h) }</div>
-                                <div>{ format!("Low 24h: ${:.4}", selected.low_24h) }</div>
+                                <div>{ format!("Price: ${}", self.app.format_price(&selected.symbol, selected.price)) }</div>
+                                <div>{ format!("High 24h: ${}", self.app.format_price(&selected.symbol, selected.high_24h)) }</div>
+                                <div>{ format!("Low 24h: ${}", self.app.format_price(&selected.symbol, selected.low_24h)) }</div>
                                 <div>{ format!("Volume: {:.0}", selected.volume) }</div>
-                                <div>{ format!("Prev Close: ${:.4}", selected.prev_close_price) }</div>
+                                <div>{ format!("Prev Close: ${}", self.app.format_price(&selected.symbol, selected.prev_close_price)) }</div>
                                 <div>{ format!("Change: {:+.2}%", selected.price_change_percent) }</div>
                             </div>
 
@@ -467,10 +515,10 @@ impl WebApp {
             .iter()
             .map(|candle| ChartDataPoint {
                 time: candle.timestamp / 1000, // Convert ms to seconds for Lightweight Charts
-                open: candle.open,
-                high: candle.high,
-                low: candle.low,
-                close: candle.close,
+                open: candle.open.to_f64().unwrap_or_default(),
+                high: candle.high.to_f64().unwrap_or_default(),
+                low: candle.low.to_f64().unwrap_or_default(),
+                close: candle.close.to_f64().unwrap_or_default(),
             })
             .collect();
 
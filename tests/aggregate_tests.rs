@@ -0,0 +1,106 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use coinpeek::aggregate::{compare_with_exchange, rollup, rollup_sources, ticks_to_candles};
+use coinpeek::binance::Candle;
+use coinpeek::timeframe::TimeFrame;
+
+/// 2022-01-01 00:00 UTC
+const START: u64 = 1_640_995_200_000;
+const NOW: u64 = START + 365 * 24 * 60 * 60_000;
+
+fn candle(timestamp: u64, open: Decimal, high: Decimal, low: Decimal, close: Decimal, volume: f64) -> Candle {
+    Candle { open, high, low, close, volume, timestamp }
+}
+
+/// Consecutive 5m candles where candle `i` opens at 100 + i and closes one higher
+fn five_minute_candles(count: u64) -> Vec<Candle> {
+    (0..count)
+        .map(|i| {
+            let open = dec!(100) + Decimal::from(i);
+            candle(START + i * 300_000, open, open + dec!(2), open - dec!(1), open + dec!(1), 1.5)
+        })
+        .collect()
+}
+
+#[test]
+fn test_rollup_combines_ohlcv() {
+    let bars = rollup(&five_minute_candles(24), TimeFrame::M5, TimeFrame::H1, NOW);
+    assert_eq!(bars.len(), 2);
+
+    assert_eq!(bars[0].timestamp, START);
+    assert_eq!(bars[0].open, dec!(100));
+    assert_eq!(bars[0].high, dec!(113));
+    assert_eq!(bars[0].low, dec!(99));
+    assert_eq!(bars[0].close, dec!(112));
+    assert_eq!(bars[0].volume, 18.0);
+
+    assert_eq!(bars[1].timestamp, START + 3_600_000);
+    assert_eq!(bars[1].open, dec!(112));
+    assert_eq!(bars[1].close, dec!(124));
+}
+
+#[test]
+fn test_rollup_aligns_to_utc_boundaries() {
+    // Starting at 00:50 the first hour is partial and the second complete
+    let candles: Vec<Candle> = five_minute_candles(26).into_iter().skip(10).collect();
+    let bars = rollup(&candles, TimeFrame::M5, TimeFrame::H1, NOW);
+    assert_eq!(bars.len(), 1);
+    assert_eq!(bars[0].timestamp, START + 3_600_000);
+}
+
+#[test]
+fn test_rollup_skips_bars_with_gaps_except_the_forming_one() {
+    let mut candles = five_minute_candles(30);
+    candles.remove(5);
+
+    // The first hour is missing a candle; the third is still forming
+    let now = START + 2 * 3_600_000 + 1_000_000;
+    let bars = rollup(&candles, TimeFrame::M5, TimeFrame::H1, now);
+    assert_eq!(bars.iter().map(|b| b.timestamp).collect::<Vec<_>>(), vec![START + 3_600_000, START + 7_200_000]);
+    assert_eq!(bars[1].volume, 9.0);
+}
+
+#[test]
+fn test_rollup_sources() {
+    assert_eq!(rollup_sources(TimeFrame::D1), &[TimeFrame::M1, TimeFrame::M5]);
+    assert!(rollup_sources(TimeFrame::M5).is_empty());
+}
+
+#[test]
+fn test_compare_with_exchange_reports_only_differences() {
+    let derived = rollup(&five_minute_candles(36), TimeFrame::M5, TimeFrame::M15, NOW);
+    let mut exchange = derived.clone();
+    // Float sums that differ in the last digits still agree
+    exchange[0].volume += 1e-9;
+    exchange[1].high += dec!(0.01);
+    exchange.remove(2);
+
+    let mismatches = compare_with_exchange(&derived, &exchange);
+    assert_eq!(mismatches.len(), 1);
+    assert_eq!(mismatches[0].timestamp, derived[1].timestamp);
+    assert_eq!(mismatches[0].exchange.high, derived[1].high + dec!(0.01));
+}
+
+#[test]
+fn test_ticks_to_candles() {
+    // 3 second polling, with nothing recorded between 00:00:20 and 00:00:40
+    let ticks = [
+        (START, dec!(100)),
+        (START + 3_000, dec!(102)),
+        (START + 6_000, dec!(99.5)),
+        (START + 9_000, dec!(101)),
+        (START + 12_000, dec!(101.25)),
+        (START + 42_000, dec!(98)),
+    ];
+    let bars = ticks_to_candles(&ticks, 10_000);
+    assert_eq!(bars.iter().map(|b| b.timestamp).collect::<Vec<_>>(), vec![START, START + 10_000, START + 40_000]);
+
+    assert_eq!(bars[0].open, dec!(100));
+    assert_eq!(bars[0].high, dec!(102));
+    assert_eq!(bars[0].low, dec!(99.5));
+    assert_eq!(bars[0].close, dec!(101));
+    assert_eq!(bars[1].open, dec!(101.25));
+    assert_eq!(bars[1].close, dec!(101.25));
+    assert_eq!(bars[2].low, dec!(98));
+}
@@ -1,44 +1,60 @@
-use coinpeek::binance::{PriceInfo, Candle, fetch_price_infos, fetch_candles};
-use mockito::{Server, Mock};
+use coinpeek::binance::{BinanceClient, PriceInfo, Candle};
+use coinpeek::config::ApiConfig;
+use coinpeek::exchange::MarketDataSource;
+use coinpeek::error::CoinPeekError;
+use coinpeek::utils::format_price;
+use mockito::{Matcher, Server};
+use rust_decimal_macros::dec;
+use tokio_test::block_on;
+
+fn mock_client(server: &Server) -> BinanceClient {
+    let config = ApiConfig {
+        base_url: server.url(),
+        user_agent: "coinpeek-test".to_string(),
+        ..ApiConfig::default()
+    };
+    BinanceClient::new(&config).unwrap()
+}
 
 #[test]
 fn test_price_info_parsing() {
     // Test the PriceInfo struct creation and validation
     let price_info = PriceInfo {
         symbol: "BTCUSDT".to_string(),
-        price: 50000.50,
+        price: dec!(50000.50),
         price_change_percent: 2.34,
         volume: 1234.56,
-        high_24h: 51000.00,
-        low_24h: 49000.00,
-        prev_close_price: 48888.88,
+        high_24h: dec!(51000.00),
+        low_24h: dec!(49000.00),
+        prev_close_price: dec!(48888.88),
+        ..Default::default()
     };
 
     assert_eq!(price_info.symbol, "BTCUSDT");
-    assert_eq!(price_info.price, 50000.50);
+    assert_eq!(price_info.price, dec!(50000.50));
     assert_eq!(price_info.price_change_percent, 2.34);
     assert_eq!(price_info.volume, 1234.56);
-    assert_eq!(price_info.high_24h, 51000.00);
-    assert_eq!(price_info.low_24h, 49000.00);
-    assert_eq!(price_info.prev_close_price, 48888.88);
+    assert_eq!(price_info.high_24h, dec!(51000.00));
+    assert_eq!(price_info.low_24h, dec!(49000.00));
+    assert_eq!(price_info.prev_close_price, dec!(48888.88));
 }
 
 #[test]
 fn test_candle_data_structure() {
     // Test the Candle struct creation and validation
     let candle = Candle {
-        open: 50000.0,
-        high: 51000.0,
-        low: 49000.0,
-        close: 50500.0,
+        open: dec!(50000.0),
+        high: dec!(51000.0),
+        low: dec!(49000.0),
+        close: dec!(50500.0),
         volume: 100.5,
         timestamp: 1640995200000,
     };
 
-    assert_eq!(candle.open, 50000.0);
-    assert_eq!(candle.high, 51000.0);
-    assert_eq!(candle.low, 49000.0);
-    assert_eq!(candle.close, 50500.0);
+    assert_eq!(candle.open, dec!(50000.0));
+    assert_eq!(candle.high, dec!(51000.0));
+    assert_eq!(candle.low, dec!(49000.0));
+    assert_eq!(candle.close, dec!(50500.0));
     assert_eq!(candle.volume, 100.5);
     assert_eq!(candle.timestamp, 1640995200000);
 }
@@ -74,10 +90,10 @@ fn test_candle_parsing_from_binance_response() {
 
     assert_eq!(candles.len(), 1);
     let candle = &candles[0];
-    assert_eq!(candle.open, 50000.0);
-    assert_eq!(candle.high, 51000.0);
-    assert_eq!(candle.low, 49000.0);
-    assert_eq!(candle.close, 50500.0);
+    assert_eq!(candle.open, dec!(50000.0));
+    assert_eq!(candle.high, dec!(51000.0));
+    assert_eq!(candle.low, dec!(49000.0));
+    assert_eq!(candle.close, dec!(50500.0));
     assert_eq!(candle.volume, 100.5);
     assert_eq!(candle.timestamp, 1640995200000);
 }
@@ -182,13 +198,329 @@ fn test_incomplete_candle_data() {
 
 #[test]
 fn test_api_mocking_setup() {
-    // Test that we can set up API mocking infrastructure
-    // This test ensures mockito is properly integrated
-    // In a real integration test, we'd make actual HTTP calls to the mock server
+    // Point the client at a local mock server instead of api.binance.com
+    let mut server = Server::new();
+    let mock = server
+        .mock("GET", "/api/v3/ticker/price")
+        .match_query(Matcher::UrlEncoded("symbol".into(), "BTCUSDT".into()))
+        .match_header("user-agent", "coinpeek-test")
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"symbol":"BTCUSDT","price":"50000.50000000"}"#)
+        .create();
+
+    let client = mock_client(&server);
+    let price = block_on(client.fetch_price("BTCUSDT")).unwrap();
+
+    assert_eq!(price, dec!(50000.5));
+    mock.assert();
+}
+
+#[test]
+fn test_fetch_candles_from_mock_server() {
+    let mut server = Server::new();
+    let mock = server
+        .mock("GET", "/api/v3/klines")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("symbol".into(), "ETHUSDT".into()),
+            Matcher::UrlEncoded("interval".into(), "5m".into()),
+            Matcher::UrlEncoded("limit".into(), "2".into()),
+        ]))
+        .with_header("content-type", "application/json")
+        .with_body(r#"[
+            [1640995200000, "3000.0", "3010.0", "2990.0", "3005.0", "12.5", 1640995499999],
+            [1640995500000, "3005.0", "3020.0", "3000.0", "3015.0", "8.0", 1640995799999]
+        ]"#)
+        .create();
+
+    let client = mock_client(&server);
+    let candles = block_on(client.fetch_candles("ETHUSDT", "5m", 2)).unwrap();
+
+    assert_eq!(candles.len(), 2);
+    assert_eq!(candles[0].open, dec!(3000.0));
+    assert_eq!(candles[1].close, dec!(3015.0));
+    assert_eq!(candles[1].timestamp, 1640995500000);
+    mock.assert();
+}
+
+/// Klines JSON for one-minute candles starting at `first_open_ms`
+fn klines_json(first_open_ms: u64, count: u64) -> String {
+    let rows: Vec<String> = (0..count)
+        .map(|i| {
+            let open_time = first_open_ms + i * 60_000;
+            format!(r#"[{}, "100.0", "101.0", "99.0", "100.5", "1.0", {}]"#, open_time, open_time + 59_999)
+        })
+        .collect();
+    format!("[{}]", rows.join(","))
+}
+
+#[test]
+fn test_fetch_candle_range_pages_with_start_time() {
+    use futures::StreamExt;
+
+    let start_ms: u64 = 1_640_995_200_000;
+    let end_ms = start_ms + 1_200 * 60_000;
+    let mut server = Server::new();
+    let first = server
+        .mock("GET", "/api/v3/klines")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("startTime".into(), start_ms.to_string()),
+            Matcher::UrlEncoded("endTime".into(), end_ms.to_string()),
+            Matcher::UrlEncoded("limit".into(), "1000".into()),
+        ]))
+        .with_header("content-type", "application/json")
+        .with_body(klines_json(start_ms, 1000))
+        .create();
+    // The next page starts just after the last candle of the first one
+    let second_start = start_ms + 999 * 60_000 + 1;
+    let second = server
+        .mock("GET", "/api/v3/klines")
+        .match_query(Matcher::UrlEncoded("startTime".into(), second_start.to_string()))
+        .with_header("content-type", "application/json")
+        .with_body(klines_json(start_ms + 1000 * 60_000, 201))
+        .create();
+
+    let client = mock_client(&server);
+    let start = chrono::DateTime::from_timestamp_millis(start_ms as i64).unwrap();
+    let end = chrono::DateTime::from_timestamp_millis(end_ms as i64).unwrap();
+    let pages: Vec<_> = block_on(client.fetch_candle_range("BTCUSDT", "1m", start, end, false).collect());
+
+    let sizes: Vec<usize> = pages.iter().map(|page| page.as_ref().unwrap().len()).collect();
+    assert_eq!(sizes, vec![1000, 201]);
+    assert_eq!(pages[1].as_ref().unwrap().last().unwrap().timestamp, end_ms);
+    first.assert();
+    second.assert();
+}
+
+#[test]
+fn test_http_error_status_is_reported() {
+    let mut server = Server::new();
+    let _mock = server
+        .mock("GET", "/api/v3/ticker/24hr")
+        .match_query(Matcher::Any)
+        .with_status(500)
+        .create();
+
+    let client = mock_client(&server);
+    let result = block_on(client.fetch_24hr_stats("BTCUSDT"));
+
+    assert!(result.is_err(), "Server errors should not be decoded as data");
+    assert!(matches!(result, Err(CoinPeekError::HttpStatus { status: 500, .. })));
+}
+
+/// Minimal 24hr ticker JSON object as returned by `/api/v3/ticker/24hr`
+fn ticker_json(symbol: &str, price: &str, bid: &str, ask: &str) -> String {
+    format!(
+        r#"{{"symbol":"{symbol}","priceChange":"10.0","priceChangePercent":"1.50","weightedAvgPrice":"{price}",
+        "prevClosePrice":"{price}","lastPrice":"{price}","lastQty":"0.1","bidPrice":"{bid}","askPrice":"{ask}",
+        "openPrice":"{price}","highPrice":"{price}","lowPrice":"{price}","volume":"100.0","quoteVolume":"2500000.0",
+        "openTime":1640908800000,"closeTime":1640995200000,"firstId":1,"lastId":2,"count":2}}"#
+    )
+}
+
+#[test]
+fn test_fetch_price_infos_uses_single_batch_request() {
+    let mut server = Server::new();
+    // Out of request order, and missing SOLUSDT entirely
+    let body = format!(
+        "[{},{}]",
+        ticker_json("ETHUSDT", "3000.00", "2999.50", "3000.50"),
+        ticker_json("BTCUSDT", "50000.00", "49999.00", "50001.00"),
+    );
+    let mock = server
+        .mock("GET", "/api/v3/ticker/24hr")
+        .match_query(Matcher::UrlEncoded(
+            "symbols".into(),
+            r#"["BTCUSDT","ETHUSDT","SOLUSDT"]"#.into(),
+        ))
+        .with_header("content-type", "application/json")
+        .with_body(body)
+        .expect(1)
+        .create();
+
+    let client = mock_client(&server);
+    let results = block_on(client.fetch_price_infos(&["BTCUSDT", "ETHUSDT", "SOLUSDT"])).unwrap();
+
+    mock.assert();
+    assert_eq!(results.len(), 3);
+    let btc = results[0].result.as_ref().unwrap();
+    assert_eq!(results[0].symbol, "BTCUSDT");
+    assert_eq!(btc.price, dec!(50000.0));
+    assert_eq!(btc.bid_price, dec!(49999.0));
+    assert_eq!(btc.ask_price, dec!(50001.0));
+    assert_eq!(btc.quote_volume, 2500000.0);
+    assert_eq!(results[1].result.as_ref().unwrap().symbol, "ETHUSDT");
+    assert!(
+        matches!(results[2].result, Err(CoinPeekError::InvalidSymbol { .. })),
+        "Missing symbols should be reported, not zero-filled"
+    );
+}
+
+#[test]
+fn test_rejected_batch_falls_back_to_single_symbol_requests() {
+    let mut server = Server::new();
+    let batch = server
+        .mock("GET", "/api/v3/ticker/24hr")
+        .match_query(Matcher::Regex("symbols=".into()))
+        .with_status(400)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"code":-1121,"msg":"Invalid symbol."}"#)
+        .expect(1)
+        .create();
+    let good = server
+        .mock("GET", "/api/v3/ticker/24hr")
+        .match_query(Matcher::UrlEncoded("symbol".into(), "BTCUSDT".into()))
+        .with_header("content-type", "application/json")
+        .with_body(ticker_json("BTCUSDT", "50000.00", "49999.00", "50001.00"))
+        .create();
+    let bad = server
+        .mock("GET", "/api/v3/ticker/24hr")
+        .match_query(Matcher::UrlEncoded("symbol".into(), "FAKEUSDT".into()))
+        .with_status(400)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"code":-1121,"msg":"Invalid symbol."}"#)
+        .create();
+
+    let client = mock_client(&server);
+    let results = block_on(client.fetch_price_infos(&["BTCUSDT", "FAKEUSDT", "bad"])).unwrap();
+
+    batch.assert();
+    good.assert();
+    bad.assert();
+    assert_eq!(results[0].result.as_ref().unwrap().price, dec!(50000.0));
+    match &results[1].result {
+        Err(CoinPeekError::InvalidSymbol { symbol, reason }) => {
+            assert_eq!(symbol, "FAKEUSDT");
+            assert_eq!(reason, "Invalid symbol.");
+        }
+        other => panic!("expected invalid symbol error, got {:?}", other),
+    }
+    // Rejected locally, never sent
+    assert!(matches!(results[2].result, Err(CoinPeekError::InvalidSymbol { .. })));
+}
+
+#[test]
+fn test_malformed_price_is_a_decode_error() {
+    let mut server = Server::new();
+    let _mock = server
+        .mock("GET", "/api/v3/ticker/price")
+        .match_query(Matcher::Any)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"symbol":"BTCUSDT","price":"not-a-number"}"#)
+        .create();
+
+    let client = mock_client(&server);
+    let err = block_on(client.fetch_price("BTCUSDT")).unwrap_err();
+    assert!(matches!(err, CoinPeekError::Decode(_)), "got {:?}", err);
+}
+
+#[test]
+fn test_fetch_price_infos_chunks_large_symbol_lists() {
+    let mut server = Server::new();
+    let mock = server
+        .mock("GET", "/api/v3/ticker/24hr")
+        .match_query(Matcher::Any)
+        .with_header("content-type", "application/json")
+        .with_body("[]")
+        .expect(2)
+        .create();
+
+    // 150 distinct, well-formed symbols: AAAUSDT, AABUSDT, ...
+    let symbols: Vec<String> = (0..150u8)
+        .map(|i| format!("A{}{}USDT", (b'A' + i / 26) as char, (b'A' + i % 26) as char))
+        .collect();
+    let symbol_refs: Vec<&str> = symbols.iter().map(|s| s.as_str()).collect();
+
+    let client = mock_client(&server);
+    let results = block_on(client.fetch_price_infos(&symbol_refs)).unwrap();
+
+    assert_eq!(results.len(), 150);
+    assert!(results.iter().all(|r| r.result.is_err()));
+    mock.assert();
+}
+
+#[test]
+fn test_rate_limited_response_blocks_further_requests() {
+    let mut server = Server::new();
+    let mock = server
+        .mock("GET", "/api/v3/ticker/price")
+        .match_query(Matcher::Any)
+        .with_status(429)
+        .with_header("retry-after", "30")
+        .with_header("x-mbx-used-weight-1m", "6100")
+        .expect(1)
+        .create();
 
-    // Just verify that mockito types are accessible
-    use mockito::{Server, Mock};
-    assert!(true, "Mockito is properly integrated");
+    let client = mock_client(&server);
+    let err = block_on(client.fetch_price("BTCUSDT")).unwrap_err();
+    let retry_at = err.retry_at().expect("429 should map to a rate limit error");
+    let wait = retry_at - chrono::Utc::now();
+    assert!(wait > chrono::Duration::seconds(25) && wait <= chrono::Duration::seconds(30));
+    assert!(client.throttled_until().is_some());
+
+    // Held back locally, without another request reaching the server
+    let err = block_on(client.fetch_price("BTCUSDT")).unwrap_err();
+    assert!(matches!(err, CoinPeekError::RateLimited { .. }));
+    mock.assert();
+}
+
+#[test]
+fn test_used_weight_header_is_tracked() {
+    let mut server = Server::new();
+    let _mock = server
+        .mock("GET", "/api/v3/ticker/price")
+        .match_query(Matcher::Any)
+        .with_header("content-type", "application/json")
+        .with_header("x-mbx-used-weight-1m", "1234")
+        .with_body(r#"{"symbol":"BTCUSDT","price":"50000.00"}"#)
+        .create();
+
+    let client = mock_client(&server);
+    block_on(client.fetch_price("BTCUSDT")).unwrap();
+
+    assert_eq!(client.rate_limiter().used_weight(chrono::Utc::now()), 1234);
+    assert!(client.throttled_until().is_none());
+}
+
+#[test]
+fn test_fetch_symbols_parses_exchange_info() {
+    let mut server = Server::new();
+    let _mock = server
+        .mock("GET", "/api/v3/exchangeInfo")
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"timezone":"UTC","symbols":[
+            {"symbol":"DOGEUSDT","status":"TRADING","baseAsset":"DOGE","quoteAsset":"USDT","filters":[
+                {"filterType":"PRICE_FILTER","minPrice":"0.00001000","maxPrice":"1000.00000000","tickSize":"0.00001000"},
+                {"filterType":"LOT_SIZE","minQty":"1.00000000","maxQty":"9000000.00000000","stepSize":"1.00000000"},
+                {"filterType":"NOTIONAL","minNotional":"1.00000000"}
+            ]},
+            {"symbol":"LUNAUSDT","status":"BREAK","baseAsset":"LUNA","quoteAsset":"USDT","filters":[]}
+        ]}"#)
+        .create();
+
+    let client = mock_client(&server);
+    let symbols = block_on(client.fetch_symbols()).unwrap();
+    assert_eq!(symbols.len(), 2);
+
+    let doge = &symbols[0];
+    assert_eq!(doge.pair_name(), "DOGE/USDT");
+    assert!(doge.is_trading());
+    assert_eq!(doge.tick_size, dec!(0.00001));
+    assert_eq!(doge.step_size, dec!(1));
+    assert_eq!(doge.min_qty, dec!(1));
+
+    let luna = &symbols[1];
+    assert!(!luna.is_trading());
+    assert!(luna.tick_size.is_zero(), "Missing filters leave the tick size unknown");
+}
+
+#[test]
+fn test_client_trims_trailing_slash_from_base_url() {
+    let config = ApiConfig {
+        base_url: "https://api.binance.us/".to_string(),
+        ..ApiConfig::default()
+    };
+    let client = BinanceClient::new(&config).unwrap();
+    assert_eq!(client.base_url(), "https://api.binance.us");
 }
 
 #[test]
@@ -196,18 +528,19 @@ fn test_price_info_display_formatting() {
     // Test that price formatting works correctly
     let price_info = PriceInfo {
         symbol: "BTCUSDT".to_string(),
-        price: 50000.12345678,
+        price: dec!(50000.12345678),
         price_change_percent: 2.345678,
         volume: 1234.567890,
-        high_24h: 51000.999999,
-        low_24h: 49000.000001,
-        prev_close_price: 48888.888888,
+        high_24h: dec!(51000.999999),
+        low_24h: dec!(49000.000001),
+        prev_close_price: dec!(48888.888888),
+        ..Default::default()
     };
 
     // Test that we can format prices appropriately
-    assert_eq!(format!("${:.2}", price_info.price), "$50000.12");
-    assert_eq!(format!("${:.2}", price_info.high_24h), "$51001.00");
-    assert_eq!(format!("${:.2}", price_info.low_24h), "$49000.00");
+    assert_eq!(format!("${}", format_price(price_info.price, None)), "$50000.12");
+    assert_eq!(format!("${}", format_price(price_info.high_24h, None)), "$51001.00");
+    assert_eq!(format!("${}", format_price(price_info.low_24h, None)), "$49000.00");
     assert_eq!(format!("{:.2}%", price_info.price_change_percent), "2.35%");
 }
 
@@ -215,21 +548,21 @@ fn test_price_info_display_formatting() {
 fn test_candle_price_calculations() {
     // Test basic price calculations on candle data
     let candle = Candle {
-        open: 50000.0,
-        high: 51000.0,
-        low: 49000.0,
-        close: 50500.0,
+        open: dec!(50000.0),
+        high: dec!(51000.0),
+        low: dec!(49000.0),
+        close: dec!(50500.0),
         volume: 100.0,
         timestamp: 1640995200000,
     };
 
     // Test price change calculation
     let price_change = candle.close - candle.open;
-    assert_eq!(price_change, 500.0);
+    assert_eq!(price_change, dec!(500.0));
 
     // Test price range
     let price_range = candle.high - candle.low;
-    assert_eq!(price_range, 2000.0);
+    assert_eq!(price_range, dec!(2000.0));
 
     // Test if candle is bullish (close > open)
     let is_bullish = candle.close > candle.open;
@@ -237,10 +570,10 @@ fn test_candle_price_calculations() {
 
     // Test if candle is bearish (close < open)
     let bearish_candle = Candle {
-        open: 50500.0,
-        high: 51000.0,
-        low: 49000.0,
-        close: 49500.0,
+        open: dec!(50500.0),
+        high: dec!(51000.0),
+        low: dec!(49000.0),
+        close: dec!(49500.0),
         volume: 100.0,
         timestamp: 1640995200000,
     };
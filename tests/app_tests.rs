@@ -1,13 +1,18 @@
-use coinpeek::app::{App, SortMode, SortDirection, FilterPreset, FilterType};
-use coinpeek::config::Config;
+use coinpeek::app::{AlertCondition, AlertField, ConditionFields, ConditionKind, AlertMode, App, SortMode, SortDirection, FilterPreset, FilterType};
+use coinpeek::config::{ChartSource, Config, RuleAlertConfig, SinkConfig};
 use coinpeek::binance::{PriceInfo, Candle};
+use coinpeek::history::PricePoint;
+use coinpeek::rules::AlertRule;
+use coinpeek::timeframe::TimeFrame;
 use crossterm::event::{MouseEvent, MouseEventKind, MouseButton};
+use rust_decimal_macros::dec;
 
 #[test]
 fn test_app_initialization() {
     let config = Config {
         symbols: vec!["BTCUSDT".to_string(), "ETHUSDT".to_string()],
         refresh_interval_seconds: 30,
+        ..Config::default()
     };
 
     let app = App::new(config);
@@ -25,6 +30,7 @@ fn test_price_update_and_sorting() {
     let config = Config {
         symbols: vec!["BTCUSDT".to_string()],
         refresh_interval_seconds: 30,
+        ..Config::default()
     };
 
     let mut app = App::new(config);
@@ -32,21 +38,23 @@ fn test_price_update_and_sorting() {
     let price_infos = vec![
         PriceInfo {
             symbol: "ETHUSDT".to_string(),
-            price: 3000.0,
+            price: dec!(3000.0),
             price_change_percent: -1.2,
             volume: 500.0,
-            high_24h: 3100.0,
-            low_24h: 2900.0,
-            prev_close_price: 3036.0,
+            high_24h: dec!(3100.0),
+            low_24h: dec!(2900.0),
+            prev_close_price: dec!(3036.0),
+            ..Default::default()
         },
         PriceInfo {
             symbol: "BTCUSDT".to_string(),
-            price: 50000.0,
+            price: dec!(50000.0),
             price_change_percent: 2.5,
             volume: 1000.0,
-            high_24h: 51000.0,
-            low_24h: 49000.0,
-            prev_close_price: 48750.0,
+            high_24h: dec!(51000.0),
+            low_24h: dec!(49000.0),
+            prev_close_price: dec!(48750.0),
+            ..Default::default()
         },
     ];
 
@@ -62,6 +70,7 @@ fn test_sort_mode_changes() {
     let config = Config {
         symbols: vec!["BTCUSDT".to_string()],
         refresh_interval_seconds: 30,
+        ..Config::default()
     };
 
     let mut app = App::new(config);
@@ -69,21 +78,23 @@ fn test_sort_mode_changes() {
     let price_infos = vec![
         PriceInfo {
             symbol: "ETHUSDT".to_string(),
-            price: 3000.0,
+            price: dec!(3000.0),
             price_change_percent: -1.2,
             volume: 500.0,
-            high_24h: 3100.0,
-            low_24h: 2900.0,
-            prev_close_price: 3036.0,
+            high_24h: dec!(3100.0),
+            low_24h: dec!(2900.0),
+            prev_close_price: dec!(3036.0),
+            ..Default::default()
         },
         PriceInfo {
             symbol: "BTCUSDT".to_string(),
-            price: 50000.0,
+            price: dec!(50000.0),
             price_change_percent: 2.5,
             volume: 1000.0,
-            high_24h: 51000.0,
-            low_24h: 49000.0,
-            prev_close_price: 48750.0,
+            high_24h: dec!(51000.0),
+            low_24h: dec!(49000.0),
+            prev_close_price: dec!(48750.0),
+            ..Default::default()
         },
     ];
 
@@ -126,6 +137,7 @@ fn test_navigation() {
     let config = Config {
         symbols: vec!["BTCUSDT".to_string()],
         refresh_interval_seconds: 30,
+        ..Config::default()
     };
 
     let mut app = App::new(config);
@@ -133,30 +145,33 @@ fn test_navigation() {
     let price_infos = vec![
         PriceInfo {
             symbol: "BTCUSDT".to_string(),
-            price: 50000.0,
+            price: dec!(50000.0),
             price_change_percent: 2.5,
             volume: 1000.0,
-            high_24h: 51000.0,
-            low_24h: 49000.0,
-            prev_close_price: 48750.0,
+            high_24h: dec!(51000.0),
+            low_24h: dec!(49000.0),
+            prev_close_price: dec!(48750.0),
+            ..Default::default()
         },
         PriceInfo {
             symbol: "ETHUSDT".to_string(),
-            price: 3000.0,
+            price: dec!(3000.0),
             price_change_percent: -1.2,
             volume: 500.0,
-            high_24h: 3100.0,
-            low_24h: 2900.0,
-            prev_close_price: 3036.0,
+            high_24h: dec!(3100.0),
+            low_24h: dec!(2900.0),
+            prev_close_price: dec!(3036.0),
+            ..Default::default()
         },
         PriceInfo {
             symbol: "ADAUSDT".to_string(),
-            price: 1.5,
+            price: dec!(1.5),
             price_change_percent: 0.5,
             volume: 100.0,
-            high_24h: 1.6,
-            low_24h: 1.4,
-            prev_close_price: 1.49,
+            high_24h: dec!(1.6),
+            low_24h: dec!(1.4),
+            prev_close_price: dec!(1.49),
+            ..Default::default()
         },
     ];
 
@@ -196,6 +211,7 @@ fn test_pause_functionality() {
     let config = Config {
         symbols: vec!["BTCUSDT".to_string()],
         refresh_interval_seconds: 30,
+        ..Config::default()
     };
 
     let mut app = App::new(config);
@@ -217,18 +233,20 @@ fn test_candle_data_management() {
     let config = Config {
         symbols: vec!["BTCUSDT".to_string()],
         refresh_interval_seconds: 30,
+        ..Config::default()
     };
 
     let mut app = App::new(config);
 
     let price_infos = vec![PriceInfo {
         symbol: "BTCUSDT".to_string(),
-        price: 50000.0,
+        price: dec!(50000.0),
         price_change_percent: 2.5,
         volume: 1000.0,
-        high_24h: 51000.0,
-        low_24h: 49000.0,
-        prev_close_price: 48750.0,
+        high_24h: dec!(51000.0),
+        low_24h: dec!(49000.0),
+        prev_close_price: dec!(48750.0),
+        ..Default::default()
     }];
 
     app.update_prices(price_infos);
@@ -244,18 +262,18 @@ fn test_candle_data_management() {
     // Update with candle data
     let candles = vec![
         Candle {
-            open: 50000.0,
-            high: 51000.0,
-            low: 49000.0,
-            close: 50500.0,
+            open: dec!(50000.0),
+            high: dec!(51000.0),
+            low: dec!(49000.0),
+            close: dec!(50500.0),
             volume: 100.0,
             timestamp: 1640995200000,
         },
         Candle {
-            open: 50500.0,
-            high: 51500.0,
-            low: 50000.0,
-            close: 51000.0,
+            open: dec!(50500.0),
+            high: dec!(51500.0),
+            low: dec!(50000.0),
+            close: dec!(51000.0),
             volume: 120.0,
             timestamp: 1640995260000,
         },
@@ -278,11 +296,74 @@ fn test_candle_data_management() {
     assert!(should_fetch_changed.is_none());
 }
 
+#[test]
+fn test_switching_chart_source_reloads_candles() {
+    let mut app = App::new(Config::default());
+    assert_eq!(app.chart_source, ChartSource::Exchange);
+
+    app.update_prices(vec![PriceInfo { symbol: "BTCUSDT".to_string(), price: dec!(50000), ..Default::default() }]);
+    app.update_candles_for_selected(vec![Candle {
+        open: dec!(50000),
+        high: dec!(50000),
+        low: dec!(50000),
+        close: dec!(50000),
+        volume: 0.0,
+        timestamp: 1640995200000,
+    }]);
+    assert!(app.should_fetch_candles().is_none());
+
+    app.next_chart_source();
+    assert_eq!(app.chart_source, ChartSource::Ticks);
+    assert!(app.selected_candles.is_empty());
+    assert_eq!(app.should_fetch_candles(), Some("BTCUSDT".to_string()));
+
+    app.next_chart_source();
+    assert_eq!(app.chart_source, ChartSource::Exchange);
+}
+
+#[test]
+fn test_background_chart_loads_only_land_on_their_symbol() {
+    let mut app = App::new(Config::default());
+    app.update_prices(vec![
+        PriceInfo { symbol: "BTCUSDT".to_string(), price: dec!(50000), ..Default::default() },
+        PriceInfo { symbol: "ETHUSDT".to_string(), price: dec!(3000), ..Default::default() },
+    ]);
+    let selected = app.get_selected_symbol().unwrap().symbol.clone();
+    let candle = Candle { open: dec!(1), high: dec!(1), low: dec!(1), close: dec!(1), volume: 0.0, timestamp: 1640995200000 };
+
+    // Nothing is asked for twice while a load is running
+    app.chart_loading = app.should_fetch_candles();
+    assert_eq!(app.chart_loading.as_deref(), Some(selected.as_str()));
+    assert!(app.should_fetch_candles().is_none());
+
+    // The selection moved on before the candles arrived
+    app.select_next();
+    let next = app.should_fetch_candles().unwrap();
+    assert_ne!(next, selected);
+    app.chart_loading = Some(next.clone());
+    app.finish_chart_load(&selected, vec![candle.clone()]);
+    assert!(app.selected_candles.is_empty());
+    assert_eq!(app.chart_loading.as_deref(), Some(next.as_str()));
+
+    app.finish_chart_load(&next, vec![candle]);
+    assert_eq!(app.selected_candles.len(), 1);
+    assert!(app.chart_loading.is_none());
+    assert!(app.should_fetch_candles().is_none());
+
+    // A load that found nothing isn't repeated straight away
+    app.next_chart_source();
+    app.next_chart_source();
+    app.chart_loading = app.should_fetch_candles();
+    app.finish_chart_load(&next, Vec::new());
+    assert!(app.should_fetch_candles().is_none());
+}
+
 #[test]
 fn test_empty_price_list_navigation() {
     let config = Config {
         symbols: vec!["BTCUSDT".to_string()],
         refresh_interval_seconds: 30,
+        ..Config::default()
     };
 
     let mut app = App::new(config);
@@ -333,6 +414,7 @@ fn test_price_sorting_edge_cases() {
     let config = Config {
         symbols: vec!["BTCUSDT".to_string()],
         refresh_interval_seconds: 30,
+        ..Config::default()
     };
 
     let mut app = App::new(config);
@@ -341,21 +423,23 @@ fn test_price_sorting_edge_cases() {
     let price_infos = vec![
         PriceInfo {
             symbol: "BTCUSDT".to_string(),
-            price: 50000.0,
+            price: dec!(50000.0),
             price_change_percent: 2.5,
             volume: 1000.0,
-            high_24h: 51000.0,
-            low_24h: 49000.0,
-            prev_close_price: 48750.0,
+            high_24h: dec!(51000.0),
+            low_24h: dec!(49000.0),
+            prev_close_price: dec!(48750.0),
+            ..Default::default()
         },
         PriceInfo {
             symbol: "ETHUSDT".to_string(),
-            price: 50000.0, // Same price
+            price: dec!(50000.0), // Same price
             price_change_percent: -1.2,
             volume: 500.0,
-            high_24h: 51000.0,
-            low_24h: 49000.0,
-            prev_close_price: 51000.0,
+            high_24h: dec!(51000.0),
+            low_24h: dec!(49000.0),
+            prev_close_price: dec!(51000.0),
+            ..Default::default()
         },
     ];
 
@@ -369,21 +453,23 @@ fn test_price_sorting_edge_cases() {
     let edge_case_infos = vec![
         PriceInfo {
             symbol: "ZEROUSDT".to_string(),
-            price: 0.0,
+            price: dec!(0.0),
             price_change_percent: 0.0,
             volume: 0.0,
-            high_24h: 0.0,
-            low_24h: 0.0,
-            prev_close_price: 0.0,
+            high_24h: dec!(0.0),
+            low_24h: dec!(0.0),
+            prev_close_price: dec!(0.0),
+            ..Default::default()
         },
         PriceInfo {
             symbol: "NEGUSDT".to_string(),
-            price: -100.0,
+            price: dec!(-100.0),
             price_change_percent: -50.0,
             volume: -10.0,
-            high_24h: -50.0,
-            low_24h: -150.0,
-            prev_close_price: -90.0,
+            high_24h: dec!(-50.0),
+            low_24h: dec!(-150.0),
+            prev_close_price: dec!(-90.0),
+            ..Default::default()
         },
     ];
 
@@ -398,6 +484,7 @@ fn test_selection_bounds_checking() {
     let config = Config {
         symbols: vec!["BTCUSDT".to_string()],
         refresh_interval_seconds: 30,
+        ..Config::default()
     };
 
     let mut app = App::new(config);
@@ -406,12 +493,13 @@ fn test_selection_bounds_checking() {
     let price_infos = vec![
         PriceInfo {
             symbol: "BTCUSDT".to_string(),
-            price: 50000.0,
+            price: dec!(50000.0),
             price_change_percent: 2.5,
             volume: 1000.0,
-            high_24h: 51000.0,
-            low_24h: 49000.0,
-            prev_close_price: 48750.0,
+            high_24h: dec!(51000.0),
+            low_24h: dec!(49000.0),
+            prev_close_price: dec!(48750.0),
+            ..Default::default()
         },
     ];
 
@@ -423,12 +511,13 @@ fn test_selection_bounds_checking() {
     // Update prices should fix the index
     app.update_prices(vec![PriceInfo {
         symbol: "ETHUSDT".to_string(),
-        price: 3000.0,
+        price: dec!(3000.0),
         price_change_percent: -1.2,
         volume: 500.0,
-        high_24h: 3100.0,
-        low_24h: 2900.0,
-        prev_close_price: 3036.0,
+        high_24h: dec!(3100.0),
+        low_24h: dec!(2900.0),
+        prev_close_price: dec!(3036.0),
+        ..Default::default()
     }]);
 
     // Should reset to valid index
@@ -440,6 +529,7 @@ fn test_filter_preset_application() {
     let config = Config {
         symbols: vec!["BTCUSDT".to_string()],
         refresh_interval_seconds: 30,
+        ..Config::default()
     };
 
     let mut app = App::new(config);
@@ -447,48 +537,53 @@ fn test_filter_preset_application() {
     let price_infos = vec![
         PriceInfo {
             symbol: "BTCUSDT".to_string(),
-            price: 50000.0,
+            price: dec!(50000.0),
             price_change_percent: 2.5, // Top gainer
             volume: 1000.0,
-            high_24h: 51000.0,
-            low_24h: 49000.0,
-            prev_close_price: 48750.0,
+            high_24h: dec!(51000.0),
+            low_24h: dec!(49000.0),
+            prev_close_price: dec!(48750.0),
+            ..Default::default()
         },
         PriceInfo {
             symbol: "ETHUSDT".to_string(),
-            price: 3000.0,
+            price: dec!(3000.0),
             price_change_percent: -1.2, // Neutral
             volume: 500.0,
-            high_24h: 3100.0,
-            low_24h: 2900.0,
-            prev_close_price: 3036.0,
+            high_24h: dec!(3100.0),
+            low_24h: dec!(2900.0),
+            prev_close_price: dec!(3036.0),
+            ..Default::default()
         },
         PriceInfo {
             symbol: "ADAUSDT".to_string(),
-            price: 1.5,
+            price: dec!(1.5),
             price_change_percent: -8.0, // Top loser
             volume: 100.0,
-            high_24h: 1.6,
-            low_24h: 1.4,
-            prev_close_price: 1.63,
+            high_24h: dec!(1.6),
+            low_24h: dec!(1.4),
+            prev_close_price: dec!(1.63),
+            ..Default::default()
         },
         PriceInfo {
             symbol: "SOLUSDT".to_string(),
-            price: 100.0,
+            price: dec!(100.0),
             price_change_percent: 0.5, // Stable
             volume: 2000.0, // High volume
-            high_24h: 105.0,
-            low_24h: 95.0,
-            prev_close_price: 99.5,
+            high_24h: dec!(105.0),
+            low_24h: dec!(95.0),
+            prev_close_price: dec!(99.5),
+            ..Default::default()
         },
         PriceInfo {
             symbol: "DOTUSDT".to_string(),
-            price: 25.0,
+            price: dec!(25.0),
             price_change_percent: 15.0, // Volatile
             volume: 800.0,
-            high_24h: 30.0,
-            low_24h: 20.0,
-            prev_close_price: 21.7,
+            high_24h: dec!(30.0),
+            low_24h: dec!(20.0),
+            prev_close_price: dec!(21.7),
+            ..Default::default()
         },
     ];
 
@@ -531,6 +626,7 @@ fn test_custom_filter_application() {
     let config = Config {
         symbols: vec!["BTCUSDT".to_string()],
         refresh_interval_seconds: 30,
+        ..Config::default()
     };
 
     let mut app = App::new(config);
@@ -538,30 +634,33 @@ fn test_custom_filter_application() {
     let price_infos = vec![
         PriceInfo {
             symbol: "BTCUSDT".to_string(),
-            price: 50000.0,
+            price: dec!(50000.0),
             price_change_percent: 2.5,
             volume: 1000.0,
-            high_24h: 51000.0,
-            low_24h: 49000.0,
-            prev_close_price: 48750.0,
+            high_24h: dec!(51000.0),
+            low_24h: dec!(49000.0),
+            prev_close_price: dec!(48750.0),
+            ..Default::default()
         },
         PriceInfo {
             symbol: "ETHUSDT".to_string(),
-            price: 3000.0,
+            price: dec!(3000.0),
             price_change_percent: -1.2,
             volume: 500.0,
-            high_24h: 3100.0,
-            low_24h: 2900.0,
-            prev_close_price: 3036.0,
+            high_24h: dec!(3100.0),
+            low_24h: dec!(2900.0),
+            prev_close_price: dec!(3036.0),
+            ..Default::default()
         },
         PriceInfo {
             symbol: "ADAUSDT".to_string(),
-            price: 1.5,
+            price: dec!(1.5),
             price_change_percent: 0.5,
             volume: 100.0,
-            high_24h: 1.6,
-            low_24h: 1.4,
-            prev_close_price: 1.49,
+            high_24h: dec!(1.6),
+            low_24h: dec!(1.4),
+            prev_close_price: dec!(1.49),
+            ..Default::default()
         },
     ];
 
@@ -570,8 +669,8 @@ fn test_custom_filter_application() {
 
     // Test price range filter
     app.add_filter(FilterType::PriceRange {
-        min: Some(1000.0),
-        max: Some(40000.0)
+        min: Some(dec!(1000.0)),
+        max: Some(dec!(40000.0))
     });
     assert_eq!(app.price_infos.len(), 1); // Only ETH (3000)
     assert_eq!(app.price_infos[0].symbol, "ETHUSDT");
@@ -606,6 +705,7 @@ fn test_offline_awareness_tracking() {
     let config = Config {
         symbols: vec!["BTCUSDT".to_string()],
         refresh_interval_seconds: 30,
+        ..Config::default()
     };
 
     let mut app = App::new(config);
@@ -651,6 +751,7 @@ fn test_data_age_calculations() {
     let config = Config {
         symbols: vec!["BTCUSDT".to_string()],
         refresh_interval_seconds: 30,
+        ..Config::default()
     };
 
     let mut app = App::new(config);
@@ -682,6 +783,7 @@ fn test_combined_filtering_and_sorting() {
     let config = Config {
         symbols: vec!["BTCUSDT".to_string()],
         refresh_interval_seconds: 30,
+        ..Config::default()
     };
 
     let mut app = App::new(config);
@@ -689,30 +791,33 @@ fn test_combined_filtering_and_sorting() {
     let price_infos = vec![
         PriceInfo {
             symbol: "BTCUSDT".to_string(),
-            price: 50000.0,
+            price: dec!(50000.0),
             price_change_percent: 2.5,
             volume: 1000.0,
-            high_24h: 51000.0,
-            low_24h: 49000.0,
-            prev_close_price: 48750.0,
+            high_24h: dec!(51000.0),
+            low_24h: dec!(49000.0),
+            prev_close_price: dec!(48750.0),
+            ..Default::default()
         },
         PriceInfo {
             symbol: "ETHUSDT".to_string(),
-            price: 3000.0,
+            price: dec!(3000.0),
             price_change_percent: -1.2,
             volume: 1500.0, // Higher volume than BTC
-            high_24h: 3100.0,
-            low_24h: 2900.0,
-            prev_close_price: 3036.0,
+            high_24h: dec!(3100.0),
+            low_24h: dec!(2900.0),
+            prev_close_price: dec!(3036.0),
+            ..Default::default()
         },
         PriceInfo {
             symbol: "ADAUSDT".to_string(),
-            price: 1.5,
+            price: dec!(1.5),
             price_change_percent: 8.0, // Top gainer
             volume: 500.0,
-            high_24h: 1.6,
-            low_24h: 1.4,
-            prev_close_price: 1.39,
+            high_24h: dec!(1.6),
+            low_24h: dec!(1.4),
+            prev_close_price: dec!(1.39),
+            ..Default::default()
         },
     ];
 
@@ -745,6 +850,7 @@ fn test_mouse_click_crypto_selection() {
     let config = Config {
         symbols: vec!["BTCUSDT".to_string()],
         refresh_interval_seconds: 30,
+        ..Config::default()
     };
 
     let mut app = App::new(config);
@@ -752,30 +858,33 @@ fn test_mouse_click_crypto_selection() {
     let price_infos = vec![
         PriceInfo {
             symbol: "ADAUSDT".to_string(),
-            price: 1.5,
+            price: dec!(1.5),
             price_change_percent: 0.5,
             volume: 100.0,
-            high_24h: 1.6,
-            low_24h: 1.4,
-            prev_close_price: 1.49,
+            high_24h: dec!(1.6),
+            low_24h: dec!(1.4),
+            prev_close_price: dec!(1.49),
+            ..Default::default()
         },
         PriceInfo {
             symbol: "BTCUSDT".to_string(),
-            price: 50000.0,
+            price: dec!(50000.0),
             price_change_percent: 2.5,
             volume: 1000.0,
-            high_24h: 51000.0,
-            low_24h: 49000.0,
-            prev_close_price: 48750.0,
+            high_24h: dec!(51000.0),
+            low_24h: dec!(49000.0),
+            prev_close_price: dec!(48750.0),
+            ..Default::default()
         },
         PriceInfo {
             symbol: "ETHUSDT".to_string(),
-            price: 3000.0,
+            price: dec!(3000.0),
             price_change_percent: -1.2,
             volume: 500.0,
-            high_24h: 3100.0,
-            low_24h: 2900.0,
-            prev_close_price: 3036.0,
+            high_24h: dec!(3100.0),
+            low_24h: dec!(2900.0),
+            prev_close_price: dec!(3036.0),
+            ..Default::default()
         },
     ];
 
@@ -838,6 +947,7 @@ fn test_mouse_click_bounds_checking() {
     let config = Config {
         symbols: vec!["BTCUSDT".to_string()],
         refresh_interval_seconds: 30,
+        ..Config::default()
     };
 
     let mut app = App::new(config);
@@ -845,12 +955,13 @@ fn test_mouse_click_bounds_checking() {
     let price_infos = vec![
         PriceInfo {
             symbol: "BTCUSDT".to_string(),
-            price: 50000.0,
+            price: dec!(50000.0),
             price_change_percent: 2.5,
             volume: 1000.0,
-            high_24h: 51000.0,
-            low_24h: 49000.0,
-            prev_close_price: 48750.0,
+            high_24h: dec!(51000.0),
+            low_24h: dec!(49000.0),
+            prev_close_price: dec!(48750.0),
+            ..Default::default()
         },
     ];
 
@@ -895,6 +1006,7 @@ fn test_mouse_click_empty_list() {
     let config = Config {
         symbols: vec!["BTCUSDT".to_string()],
         refresh_interval_seconds: 30,
+        ..Config::default()
     };
 
     let mut app = App::new(config);
@@ -919,6 +1031,7 @@ fn test_mouse_click_non_left_button() {
     let config = Config {
         symbols: vec!["BTCUSDT".to_string()],
         refresh_interval_seconds: 30,
+        ..Config::default()
     };
 
     let mut app = App::new(config);
@@ -926,12 +1039,13 @@ fn test_mouse_click_non_left_button() {
     let price_infos = vec![
         PriceInfo {
             symbol: "BTCUSDT".to_string(),
-            price: 50000.0,
+            price: dec!(50000.0),
             price_change_percent: 2.5,
             volume: 1000.0,
-            high_24h: 51000.0,
-            low_24h: 49000.0,
-            prev_close_price: 48750.0,
+            high_24h: dec!(51000.0),
+            low_24h: dec!(49000.0),
+            prev_close_price: dec!(48750.0),
+            ..Default::default()
         },
     ];
 
@@ -969,6 +1083,7 @@ fn test_mouse_click_out_of_bounds() {
     let config = Config {
         symbols: vec!["BTCUSDT".to_string()],
         refresh_interval_seconds: 30,
+        ..Config::default()
     };
 
     let mut app = App::new(config);
@@ -976,21 +1091,23 @@ fn test_mouse_click_out_of_bounds() {
     let price_infos = vec![
         PriceInfo {
             symbol: "BTCUSDT".to_string(),
-            price: 50000.0,
+            price: dec!(50000.0),
             price_change_percent: 2.5,
             volume: 1000.0,
-            high_24h: 51000.0,
-            low_24h: 49000.0,
-            prev_close_price: 48750.0,
+            high_24h: dec!(51000.0),
+            low_24h: dec!(49000.0),
+            prev_close_price: dec!(48750.0),
+            ..Default::default()
         },
         PriceInfo {
             symbol: "ETHUSDT".to_string(),
-            price: 3000.0,
+            price: dec!(3000.0),
             price_change_percent: -1.2,
             volume: 500.0,
-            high_24h: 3100.0,
-            low_24h: 2900.0,
-            prev_close_price: 3036.0,
+            high_24h: dec!(3100.0),
+            low_24h: dec!(2900.0),
+            prev_close_price: dec!(3036.0),
+            ..Default::default()
         },
     ];
 
@@ -1016,3 +1133,728 @@ fn test_mouse_click_out_of_bounds() {
     // Verify selection unchanged
     assert_eq!(app.selected_index, initial_selection);
 }
+
+#[test]
+fn test_streamed_price_updates_are_merged() {
+    let config = Config {
+        symbols: vec!["BTCUSDT".to_string(), "ETHUSDT".to_string()],
+        refresh_interval_seconds: 30,
+        ..Config::default()
+    };
+
+    let mut app = App::new(config);
+
+    app.update_prices(vec![
+        PriceInfo {
+            symbol: "BTCUSDT".to_string(),
+            price: dec!(50000.0),
+            price_change_percent: 2.5,
+            volume: 1000.0,
+            high_24h: dec!(51000.0),
+            low_24h: dec!(49000.0),
+            prev_close_price: dec!(48750.0),
+            ..Default::default()
+        },
+        PriceInfo {
+            symbol: "ETHUSDT".to_string(),
+            price: dec!(3000.0),
+            price_change_percent: -1.2,
+            volume: 500.0,
+            high_24h: dec!(3100.0),
+            low_24h: dec!(2900.0),
+            prev_close_price: dec!(3036.0),
+            ..Default::default()
+        },
+    ]);
+
+    // A single-symbol stream update must not drop the other symbols
+    app.apply_price_updates(vec![PriceInfo {
+        symbol: "ETHUSDT".to_string(),
+        price: dec!(3100.0),
+        price_change_percent: 2.1,
+        volume: 550.0,
+        high_24h: dec!(3150.0),
+        low_24h: dec!(2900.0),
+        prev_close_price: dec!(3036.0),
+        ..Default::default()
+    }]);
+
+    assert_eq!(app.all_price_infos.len(), 2);
+    let eth = app.price_infos.iter().find(|p| p.symbol == "ETHUSDT").unwrap();
+    assert_eq!(eth.price, dec!(3100.0));
+
+    app.set_stream_connected(true);
+    assert!(app.get_offline_indicator().starts_with("🟢 live"));
+}
+
+#[test]
+fn test_status_message_expires() {
+    let mut app = App::new(Config::default());
+    assert!(app.current_status_message().is_none());
+
+    app.set_status_message("Exported 10 BTCUSDT candles".to_string());
+    assert_eq!(app.current_status_message(), Some("Exported 10 BTCUSDT candles"));
+
+    let (message, _) = app.status_message.take().unwrap();
+    app.status_message = Some((message, chrono::Utc::now() - chrono::Duration::minutes(1)));
+    assert!(app.current_status_message().is_none());
+}
+
+#[test]
+fn test_alert_ids_stay_unique_after_delete() {
+    let mut app = App::new(Config::default());
+    let first = app.create_alert("BTCUSDT".to_string(), AlertCondition::PriceAbove(dec!(50000)), None);
+    let second = app.create_alert("ETHUSDT".to_string(), AlertCondition::PriceBelow(dec!(3000)), None);
+    assert!(app.delete_alert(first));
+
+    let third = app.create_alert("ADAUSDT".to_string(), AlertCondition::PriceBelow(dec!(0.4)), None);
+    assert_ne!(third, second);
+    assert_eq!(app.alerts.len(), 2);
+}
+
+#[test]
+fn test_alert_triggers_are_queued_for_history() {
+    let mut app = App::new(Config::default());
+    let id = app.create_alert("BTCUSDT".to_string(), AlertCondition::PriceAbove(dec!(50000)), Some("BTC breakout".to_string()));
+    app.create_alert("BTCUSDT".to_string(), AlertCondition::PriceBelow(dec!(40000)), None);
+
+    let btc = PriceInfo { symbol: "BTCUSDT".to_string(), price: dec!(50100.5), ..Default::default() };
+    app.update_prices(vec![btc.clone()]);
+
+    let triggers = app.take_alert_triggers();
+    assert_eq!(triggers.len(), 1);
+    assert_eq!(triggers[0].alert_id, id);
+    assert_eq!(triggers[0].price, dec!(50100.5));
+    assert_eq!(triggers[0].message, "BTC breakout");
+    assert_eq!(app.alerts[0].trigger_count, 1);
+    assert!(app.take_alert_triggers().is_empty());
+
+    // Still above the threshold, but inside the cooldown
+    app.update_prices(vec![btc]);
+    assert!(app.take_alert_triggers().is_empty());
+}
+
+#[test]
+fn test_alert_editor_creates_alert() {
+    let config = Config {
+        symbols: vec!["BTCUSDT".to_string(), "ETHUSDT".to_string()],
+        ..Config::default()
+    };
+    let mut app = App::new(config);
+    app.open_new_alert_editor();
+
+    let editor = app.alert_editor.as_mut().unwrap();
+    assert_eq!(editor.field, AlertField::Symbol);
+    editor.cycle_choice(true);
+    editor.next_field();
+    (0..5).for_each(|_| editor.cycle_choice(true));
+    assert_eq!(editor.condition_kind, ConditionKind::PercentChangeBelow);
+    editor.next_field();
+    "-5x".chars().for_each(|c| editor.input(c));
+
+    // A bad threshold keeps the form open with the reason
+    assert_eq!(app.submit_alert_editor(), None);
+    let editor = app.alert_editor.as_mut().unwrap();
+    assert!(editor.error.is_some());
+    editor.backspace();
+    // The hysteresis field is skipped for level conditions
+    editor.next_field();
+    assert_eq!(editor.field, AlertField::Cooldown);
+    editor.next_field();
+    editor.next_field();
+    editor.next_field();
+    assert_eq!(editor.field, AlertField::Message);
+    "ETH dump".chars().for_each(|c| editor.input(c));
+
+    let id = app.submit_alert_editor().unwrap();
+    assert!(app.alert_editor.is_none());
+    let alert = app.get_selected_alert().unwrap();
+    assert_eq!(alert.id, id);
+    assert_eq!(alert.symbol, "ETHUSDT");
+    assert_eq!(alert.condition, AlertCondition::PercentChangeBelow(-5.0));
+    assert_eq!(alert.message.as_deref(), Some("ETH dump"));
+}
+
+#[test]
+fn test_alert_editor_edits_selected_alert() {
+    let mut app = App::new(Config::default());
+    app.create_alert("BTCUSDT".to_string(), AlertCondition::PriceAbove(dec!(50000)), None);
+    let id = app.create_alert("SOLUSDT".to_string(), AlertCondition::PriceBelow(dec!(20)), Some("SOL dip".to_string()));
+    app.select_next_alert();
+    app.open_alert_editor();
+
+    let editor = app.alert_editor.as_mut().unwrap();
+    assert_eq!(editor.editing, Some(id));
+    assert_eq!(editor.selected_symbol(), Some("SOLUSDT"));
+    assert_eq!(editor.threshold, "20");
+    assert_eq!(editor.field, AlertField::Threshold);
+    editor.backspace();
+    editor.backspace();
+    "18.5".chars().for_each(|c| editor.input(c));
+
+    assert_eq!(app.submit_alert_editor(), Some(id));
+    assert_eq!(app.alerts.len(), 2);
+    assert_eq!(app.alerts[1].condition, AlertCondition::PriceBelow(dec!(18.5)));
+    assert_eq!(app.alerts[1].message.as_deref(), Some("SOL dip"));
+}
+
+#[test]
+fn test_alert_selection_follows_deletes() {
+    let mut app = App::new(Config::default());
+    assert!(app.get_selected_alert().is_none());
+    for symbol in ["BTCUSDT", "ETHUSDT", "ADAUSDT"] {
+        app.create_alert(symbol.to_string(), AlertCondition::PriceAbove(dec!(1)), None);
+    }
+
+    app.select_previous_alert();
+    assert_eq!(app.get_selected_alert().unwrap().symbol, "ADAUSDT");
+    app.delete_alert(app.get_selected_alert().unwrap().id);
+    assert_eq!(app.get_selected_alert().unwrap().symbol, "ETHUSDT");
+    app.select_next_alert();
+    assert_eq!(app.get_selected_alert().unwrap().symbol, "BTCUSDT");
+}
+
+/// Feed `prices` for BTCUSDT one refresh at a time, returning how many alerts fired
+fn run_prices(app: &mut App, prices: &[rust_decimal::Decimal]) -> usize {
+    prices
+        .iter()
+        .map(|&price| {
+            app.update_prices(vec![PriceInfo { symbol: "BTCUSDT".to_string(), price, ..Default::default() }]);
+            app.take_alert_triggers().len()
+        })
+        .sum()
+}
+
+#[test]
+fn test_crossing_alert_rearms_behind_hysteresis_band() {
+    let mut app = App::new(Config::default());
+    let id = app.create_alert(
+        "BTCUSDT".to_string(),
+        AlertCondition::CrossesAbove { level: dec!(100), hysteresis_percent: 2.0 },
+        None,
+    );
+    app.set_alert_schedule(id, 0, AlertMode::Recurring);
+
+    assert_eq!(run_prices(&mut app, &[dec!(99)]), 0);
+    assert_eq!(run_prices(&mut app, &[dec!(101)]), 1);
+    // Hovering around the level inside the band stays quiet
+    assert_eq!(run_prices(&mut app, &[dec!(99.5), dec!(100.5), dec!(98.5), dec!(101)]), 0);
+    // Falling to 98 re-arms it
+    assert_eq!(run_prices(&mut app, &[dec!(98), dec!(100)]), 1);
+    assert_eq!(app.alerts[0].trigger_count, 2);
+}
+
+#[test]
+fn test_crossing_alert_waits_when_created_past_level() {
+    let mut app = App::new(Config::default());
+    let id = app.create_alert(
+        "BTCUSDT".to_string(),
+        AlertCondition::CrossesBelow { level: dec!(100), hysteresis_percent: 0.0 },
+        None,
+    );
+    app.set_alert_schedule(id, 0, AlertMode::Recurring);
+
+    assert_eq!(run_prices(&mut app, &[dec!(95), dec!(94)]), 0);
+    assert_eq!(run_prices(&mut app, &[dec!(100.01), dec!(100)]), 1);
+    let triggers = {
+        run_prices(&mut app, &[dec!(101)]);
+        app.update_prices(vec![PriceInfo { symbol: "BTCUSDT".to_string(), price: dec!(99), ..Default::default() }]);
+        app.take_alert_triggers()
+    };
+    assert_eq!(triggers[0].message, "BTCUSDT crossed below $100 (currently $99)");
+}
+
+#[test]
+fn test_alert_cooldown_and_one_shot_mode() {
+    let mut app = App::new(Config::default());
+    let recurring = app.create_alert("BTCUSDT".to_string(), AlertCondition::PriceAbove(dec!(100)), None);
+    let once = app.create_alert("BTCUSDT".to_string(), AlertCondition::PriceAbove(dec!(100)), None);
+    app.set_alert_schedule(recurring, 30, AlertMode::Recurring);
+    app.set_alert_schedule(once, 0, AlertMode::Once);
+
+    assert_eq!(run_prices(&mut app, &[dec!(101), dec!(102)]), 2);
+    assert!(app.alerts[0].enabled);
+    assert!(!app.alerts[1].enabled);
+
+    // The recurring alert fires again once its cooldown has passed
+    app.alerts[0].last_triggered = Some(chrono::Utc::now() - chrono::Duration::minutes(31));
+    assert_eq!(run_prices(&mut app, &[dec!(103)]), 1);
+    assert_eq!(app.alerts[0].trigger_count, 2);
+    assert_eq!(app.alerts[1].trigger_count, 1);
+}
+
+#[test]
+fn test_alert_editor_crossing_fields() {
+    let config = Config { symbols: vec!["BTCUSDT".to_string()], ..Config::default() };
+    let mut app = App::new(config);
+    app.open_new_alert_editor();
+
+    let editor = app.alert_editor.as_mut().unwrap();
+    editor.next_field();
+    editor.cycle_choice(true);
+    editor.cycle_choice(true);
+    assert_eq!(editor.condition_kind, ConditionKind::CrossesAbove);
+    editor.next_field();
+    "65000".chars().for_each(|c| editor.input(c));
+    editor.next_field();
+    assert_eq!(editor.field, AlertField::Hysteresis);
+    "1.5".chars().for_each(|c| editor.input(c));
+    editor.next_field();
+    editor.backspace();
+    editor.backspace();
+    editor.input('5');
+    editor.next_field();
+    editor.cycle_choice(true);
+
+    let id = app.submit_alert_editor().unwrap();
+    let alert = app.alerts.iter().find(|a| a.id == id).unwrap();
+    assert_eq!(alert.condition, AlertCondition::CrossesAbove { level: dec!(65000), hysteresis_percent: 1.5 });
+    assert_eq!(alert.cooldown_minutes, 5);
+    assert_eq!(alert.mode, AlertMode::Once);
+
+    // Reopening shows the saved values
+    app.open_alert_editor();
+    let editor = app.alert_editor.as_ref().unwrap();
+    assert_eq!(editor.hysteresis, "1.5");
+    assert_eq!(editor.cooldown, "5");
+}
+
+#[test]
+fn test_alert_condition_from_kind_validation() {
+    let build = |kind, fields: ConditionFields| AlertCondition::from_kind(kind, &fields);
+    assert!(build(ConditionKind::PriceAbove, ConditionFields { threshold: "-5", ..Default::default() }).is_err());
+    assert!(build(ConditionKind::CrossesAbove, ConditionFields { threshold: "100", hysteresis: "150", ..Default::default() }).is_err());
+    assert!(build(ConditionKind::VolumeSpike, ConditionFields { threshold: "-1", ..Default::default() }).is_err());
+    assert_eq!(
+        build(ConditionKind::CrossesBelow, ConditionFields { threshold: "100", hysteresis: "2%", ..Default::default() }).unwrap(),
+        AlertCondition::CrossesBelow { level: dec!(100), hysteresis_percent: 2.0 }
+    );
+    assert_eq!(build(ConditionKind::PercentChangeAbove, ConditionFields { threshold: "5%", ..Default::default() }).unwrap(), AlertCondition::PercentChangeAbove(5.0));
+
+    assert!(build(ConditionKind::PercentMove, ConditionFields { threshold: "0", window: "15", ..Default::default() }).is_err());
+    assert!(build(ConditionKind::PercentMove, ConditionFields { threshold: "-2", window: "0", ..Default::default() }).is_err());
+    assert!(build(ConditionKind::RangeBreakout, ConditionFields { window: "1441", ..Default::default() }).is_err());
+    assert_eq!(
+        build(ConditionKind::PercentMove, ConditionFields { threshold: "-2%", window: "15", ..Default::default() }).unwrap(),
+        AlertCondition::PercentMove { percent: -2.0, window_minutes: 15 }
+    );
+    assert_eq!(
+        build(ConditionKind::PriceMove, ConditionFields { threshold: "250.5", window: "60", ..Default::default() }).unwrap(),
+        AlertCondition::PriceMove { amount: dec!(250.5), window_minutes: 60 }
+    );
+    assert_eq!(build(ConditionKind::NewHigh, ConditionFields { window: "30", ..Default::default() }).unwrap(), AlertCondition::NewHigh { window_minutes: 30 });
+
+    assert!(build(ConditionKind::RsiAbove, ConditionFields { threshold: "120", ..Default::default() }).is_err());
+    assert!(build(ConditionKind::GoldenCross, ConditionFields { period: "200/50", timeframe: TimeFrame::D1, ..Default::default() }).is_err());
+    assert!(build(ConditionKind::AboveUpperBand, ConditionFields { period: "500", ..Default::default() }).is_err());
+    assert_eq!(
+        build(ConditionKind::RsiAbove, ConditionFields { threshold: "70", timeframe: TimeFrame::H4, ..Default::default() }).unwrap(),
+        AlertCondition::RsiAbove { level: 70.0, period: 14, timeframe: TimeFrame::H4 }
+    );
+    assert_eq!(
+        build(ConditionKind::DeathCross, ConditionFields { timeframe: TimeFrame::D1, ..Default::default() }).unwrap(),
+        AlertCondition::DeathCross { fast: 50, slow: 200, timeframe: TimeFrame::D1 }
+    );
+    assert_eq!(
+        build(ConditionKind::BelowLowerBand, ConditionFields { timeframe: TimeFrame::M15, ..Default::default() }).unwrap(),
+        AlertCondition::BelowLowerBand { period: 20, std_devs: 2.0, timeframe: TimeFrame::M15 }
+    );
+    assert_eq!(
+        build(ConditionKind::VolumeAboveAverage, ConditionFields { threshold: "3", period: "10", timeframe: TimeFrame::M5, ..Default::default() }).unwrap(),
+        AlertCondition::VolumeAboveAverage { multiple: 3.0, period: 10, timeframe: TimeFrame::M5 }
+    );
+}
+
+#[test]
+fn test_condition_kinds_cycle_through_every_kind() {
+    let mut kind = ConditionKind::ALL[0];
+    let mut labels = std::collections::HashSet::new();
+    for expected in ConditionKind::ALL {
+        assert_eq!(kind, expected);
+        assert!(labels.insert(kind.label()), "Duplicate label {}", kind.label());
+        kind = kind.step(true);
+    }
+    assert_eq!(kind, ConditionKind::ALL[0]);
+    assert_eq!(kind.step(false), ConditionKind::Rule);
+
+    // A condition's kind builds that same condition back
+    let fields = ConditionFields { window: "15", ..Default::default() };
+    assert_eq!(AlertCondition::from_kind(ConditionKind::NewLow, &fields).unwrap().kind(), ConditionKind::NewLow);
+}
+
+/// Hourly candles closing at `closes`, oldest first
+fn hourly_candles(closes: &[rust_decimal::Decimal]) -> Vec<Candle> {
+    closes
+        .iter()
+        .enumerate()
+        .map(|(i, &close)| Candle { open: close, high: close, low: close, close, volume: 100.0, timestamp: i as u64 * 3_600_000 })
+        .collect()
+}
+
+#[test]
+fn test_rsi_alert_uses_candles_of_its_timeframe() {
+    let mut app = App::new(Config::default());
+    app.create_alert("BTCUSDT".to_string(), AlertCondition::RsiAbove { level: 70.0, period: 3, timeframe: TimeFrame::H1 }, None);
+    let rising = hourly_candles(&[dec!(100), dec!(101), dec!(102), dec!(103), dec!(104)]);
+
+    // Candles of another timeframe don't count
+    app.update_indicator_candles("BTCUSDT", TimeFrame::M5, rising.clone());
+    assert_eq!(run_prices(&mut app, &[dec!(104)]), 0);
+
+    app.update_indicator_candles("BTCUSDT", TimeFrame::H1, rising);
+    assert_eq!(run_prices(&mut app, &[dec!(104)]), 1);
+    assert_eq!(app.recent_alerts.last().unwrap().0, "🔔 BTCUSDT 1h RSI(3) above 70 (currently 100.0)");
+}
+
+#[test]
+fn test_moving_average_cross_fires_once_per_cross() {
+    let mut app = App::new(Config::default());
+    let id = app.create_alert("BTCUSDT".to_string(), AlertCondition::GoldenCross { fast: 2, slow: 4, timeframe: TimeFrame::H1 }, None);
+    app.set_alert_schedule(id, 0, AlertMode::Recurring);
+
+    app.update_indicator_candles("BTCUSDT", TimeFrame::H1, hourly_candles(&[dec!(10), dec!(10), dec!(9), dec!(8)]));
+    assert_eq!(run_prices(&mut app, &[dec!(8)]), 0);
+    app.update_indicator_candles("BTCUSDT", TimeFrame::H1, hourly_candles(&[dec!(10), dec!(9), dec!(8), dec!(12)]));
+    assert_eq!(run_prices(&mut app, &[dec!(12), dec!(12)]), 1);
+}
+
+#[test]
+fn test_bollinger_and_volume_alerts() {
+    let mut app = App::new(Config::default());
+    let band = app.create_alert("BTCUSDT".to_string(), AlertCondition::AboveUpperBand { period: 4, std_devs: 2.0, timeframe: TimeFrame::H1 }, None);
+    let volume = app.create_alert("BTCUSDT".to_string(), AlertCondition::VolumeAboveAverage { multiple: 3.0, period: 3, timeframe: TimeFrame::H1 }, None);
+    app.set_alert_schedule(band, 0, AlertMode::Recurring);
+    app.set_alert_schedule(volume, 0, AlertMode::Recurring);
+
+    // Closes 99, 101, 99, 101: mean 100, one standard deviation of 1
+    let mut candles = hourly_candles(&[dec!(99), dec!(101), dec!(99), dec!(101)]);
+    app.update_indicator_candles("BTCUSDT", TimeFrame::H1, candles.clone());
+    assert_eq!(run_prices(&mut app, &[dec!(101), dec!(102.5)]), 1);
+    assert_eq!(app.recent_alerts.last().unwrap().0, "🔔 BTCUSDT above its 1h upper Bollinger band $102 (currently $102.5)");
+
+    candles[3].volume = 300.0;
+    app.update_indicator_candles("BTCUSDT", TimeFrame::H1, candles);
+    assert_eq!(run_prices(&mut app, &[dec!(101)]), 1);
+    assert_eq!(app.recent_alerts.last().unwrap().0, "🔔 BTCUSDT 1h volume at 3.0x its 3-candle average");
+}
+
+#[test]
+fn test_volume_alert_leaves_out_the_open_bar() {
+    let mut app = App::new(Config::default());
+    app.create_alert("BTCUSDT".to_string(), AlertCondition::VolumeAboveAverage { multiple: 3.0, period: 3, timeframe: TimeFrame::H1 }, None);
+
+    // The last closed bar spiked; the one just opened has barely traded
+    let open_bar = TimeFrame::H1.bar_start(chrono::Utc::now().timestamp_millis() as u64);
+    let mut candles = hourly_candles(&[dec!(100); 5]);
+    for (i, candle) in candles.iter_mut().enumerate() {
+        candle.timestamp = open_bar - (4 - i as u64) * 3_600_000;
+    }
+    candles[3].volume = 300.0;
+    candles[4].volume = 10.0;
+    app.update_indicator_candles("BTCUSDT", TimeFrame::H1, candles);
+
+    assert_eq!(run_prices(&mut app, &[dec!(100)]), 1);
+    assert_eq!(app.recent_alerts.last().unwrap().0, "🔔 BTCUSDT 1h volume at 3.0x its 3-candle average");
+}
+
+#[test]
+fn test_indicator_series_are_reloaded_once_due() {
+    let mut app = App::new(Config::default());
+    app.create_alert("BTCUSDT".to_string(), AlertCondition::RsiBelow { level: 30.0, period: 14, timeframe: TimeFrame::H1 }, None);
+    let start = chrono::DateTime::from_timestamp(1640995200, 0).unwrap();
+    let series = vec![("BTCUSDT".to_string(), TimeFrame::H1, 57)];
+    let seconds = |s: i64| start + chrono::Duration::seconds(s);
+
+    assert_eq!(app.take_due_indicator_series(start), series);
+    // Nothing is scheduled again while its load is still running
+    assert!(app.take_due_indicator_series(seconds(90)).is_empty());
+    app.finish_indicator_load("BTCUSDT", TimeFrame::H1, Some(hourly_candles(&[dec!(100)])));
+    assert_eq!(app.indicator_candles[&("BTCUSDT".to_string(), TimeFrame::H1)].len(), 1);
+
+    assert!(app.take_due_indicator_series(seconds(30)).is_empty());
+    assert_eq!(app.take_due_indicator_series(seconds(60)), series);
+    app.finish_indicator_load("BTCUSDT", TimeFrame::H1, None);
+    // A failed read keeps the candles already loaded
+    assert_eq!(app.indicator_candles[&("BTCUSDT".to_string(), TimeFrame::H1)].len(), 1);
+
+    // A longer period needs more candles than were loaded
+    app.create_alert("BTCUSDT".to_string(), AlertCondition::RsiAbove { level: 70.0, period: 20, timeframe: TimeFrame::H1 }, None);
+    assert_eq!(app.take_due_indicator_series(seconds(61)), vec![("BTCUSDT".to_string(), TimeFrame::H1, 81)]);
+    app.finish_indicator_load("BTCUSDT", TimeFrame::H1, None);
+
+    // A new bar opening doesn't wait out the minute
+    let before_close = start + chrono::Duration::minutes(59) + chrono::Duration::seconds(50);
+    assert_eq!(app.take_due_indicator_series(before_close).len(), 1);
+    app.finish_indicator_load("BTCUSDT", TimeFrame::H1, None);
+    assert_eq!(app.take_due_indicator_series(before_close + chrono::Duration::seconds(20)).len(), 1);
+}
+
+#[test]
+fn test_indicator_series_covers_enabled_alerts() {
+    let mut app = App::new(Config::default());
+    app.create_alert("BTCUSDT".to_string(), AlertCondition::RsiBelow { level: 30.0, period: 14, timeframe: TimeFrame::H1 }, None);
+    app.create_alert("BTCUSDT".to_string(), AlertCondition::GoldenCross { fast: 50, slow: 200, timeframe: TimeFrame::H1 }, None);
+    let off = app.create_alert("ETHUSDT".to_string(), AlertCondition::RsiAbove { level: 70.0, period: 14, timeframe: TimeFrame::D1 }, None);
+    app.create_alert("ETHUSDT".to_string(), AlertCondition::PriceAbove(dec!(5000)), None);
+    app.toggle_alert(off);
+
+    assert_eq!(app.indicator_series(), vec![("BTCUSDT".to_string(), TimeFrame::H1, 200)]);
+}
+
+#[test]
+fn test_alert_editor_indicator_fields() {
+    let config = Config { symbols: vec!["BTCUSDT".to_string()], ..Config::default() };
+    let mut app = App::new(config);
+    app.open_new_alert_editor();
+
+    let editor = app.alert_editor.as_mut().unwrap();
+    editor.next_field();
+    (0..14).for_each(|_| editor.cycle_choice(true));
+    assert_eq!(editor.condition_kind, ConditionKind::GoldenCross);
+    editor.next_field();
+    assert_eq!(editor.field, AlertField::Period);
+    "20/50".chars().for_each(|c| editor.input(c));
+    editor.next_field();
+    assert_eq!(editor.field, AlertField::Timeframe);
+    editor.cycle_choice(true);
+
+    let id = app.submit_alert_editor().unwrap();
+    let alert = app.alerts.iter().find(|a| a.id == id).unwrap();
+    assert_eq!(alert.condition, AlertCondition::GoldenCross { fast: 20, slow: 50, timeframe: TimeFrame::H4 });
+
+    app.open_alert_editor();
+    let editor = app.alert_editor.as_ref().unwrap();
+    assert_eq!(editor.field, AlertField::Period);
+    assert_eq!(editor.period, "20/50");
+    assert_eq!(editor.timeframe, TimeFrame::H4);
+}
+
+/// BTCUSDT prices `(minutes ago, price)`, oldest first, as windowed alerts see them
+fn btc_history(prices: &[(i64, rust_decimal::Decimal)]) -> Vec<PricePoint> {
+    let now = chrono::Utc::now();
+    prices
+        .iter()
+        .map(|&(minutes_ago, price)| PricePoint { timestamp: now - chrono::Duration::minutes(minutes_ago), price })
+        .collect()
+}
+
+#[test]
+fn test_percent_move_alert_looks_back_over_window() {
+    let mut app = App::new(Config::default());
+    app.create_alert("BTCUSDT".to_string(), AlertCondition::PercentMove { percent: -2.0, window_minutes: 15 }, None);
+
+    // The 60000 high is older than the window; 59500 was still in effect at its start
+    app.load_price_history("BTCUSDT", btc_history(&[(30, dec!(60000)), (20, dec!(59500)), (5, dec!(59000))]));
+    assert_eq!(run_prices(&mut app, &[dec!(58500)]), 0);
+    assert_eq!(run_prices(&mut app, &[dec!(58310)]), 1);
+    assert_eq!(
+        app.recent_alerts.last().unwrap().0,
+        "🔔 BTCUSDT down 2.0% in 15m (currently $58310)"
+    );
+}
+
+#[test]
+fn test_price_move_alert() {
+    let mut app = App::new(Config::default());
+    let id = app.create_alert("BTCUSDT".to_string(), AlertCondition::PriceMove { amount: dec!(500), window_minutes: 10 }, None);
+    app.set_alert_schedule(id, 0, AlertMode::Recurring);
+
+    app.load_price_history("BTCUSDT", btc_history(&[(8, dec!(60000)), (4, dec!(59800))]));
+    assert_eq!(run_prices(&mut app, &[dec!(60200), dec!(60299), dec!(60300)]), 1);
+}
+
+#[test]
+fn test_breakout_and_new_extremes_need_a_full_window() {
+    let mut app = App::new(Config::default());
+    for condition in [
+        AlertCondition::RangeBreakout { window_minutes: 15 },
+        AlertCondition::NewHigh { window_minutes: 15 },
+        AlertCondition::NewLow { window_minutes: 15 },
+    ] {
+        let id = app.create_alert("BTCUSDT".to_string(), condition, None);
+        app.set_alert_schedule(id, 0, AlertMode::Recurring);
+    }
+
+    // Without prices from before the window a new high means nothing yet
+    assert_eq!(run_prices(&mut app, &[dec!(100), dec!(101)]), 0);
+
+    app.load_price_history("BTCUSDT", btc_history(&[(20, dec!(100)), (10, dec!(105)), (5, dec!(95))]));
+    assert_eq!(run_prices(&mut app, &[dec!(104)]), 0);
+    assert_eq!(run_prices(&mut app, &[dec!(106)]), 2);
+    assert_eq!(app.recent_alerts[0].0, "🔔 BTCUSDT broke out of its 15m range $95-$105 (currently $106)");
+    assert_eq!(run_prices(&mut app, &[dec!(94)]), 2);
+}
+
+#[test]
+fn test_price_history_keeps_changes_within_longest_window() {
+    let mut app = App::new(Config::default());
+    app.load_price_history("BTCUSDT", btc_history(&[(2000, dec!(1)), (1500, dec!(2)), (60, dec!(3))]));
+
+    run_prices(&mut app, &[dec!(4), dec!(4)]);
+    let prices: Vec<_> = app.price_history["BTCUSDT"].iter().map(|p| p.price).collect();
+    assert_eq!(prices, vec![dec!(2), dec!(3), dec!(4)]);
+}
+
+#[test]
+fn test_alert_editor_window_fields() {
+    let config = Config { symbols: vec!["BTCUSDT".to_string()], ..Config::default() };
+    let mut app = App::new(config);
+    app.open_new_alert_editor();
+
+    let editor = app.alert_editor.as_mut().unwrap();
+    editor.next_field();
+    (0..9).for_each(|_| editor.cycle_choice(true));
+    assert_eq!(editor.condition_kind, ConditionKind::RangeBreakout);
+    assert!(!editor.field_applies(AlertField::Threshold));
+    editor.next_field();
+    assert_eq!(editor.field, AlertField::Window);
+    editor.backspace();
+    editor.backspace();
+    "30".chars().for_each(|c| editor.input(c));
+
+    let id = app.submit_alert_editor().unwrap();
+    let alert = app.alerts.iter().find(|a| a.id == id).unwrap();
+    assert_eq!(alert.condition, AlertCondition::RangeBreakout { window_minutes: 30 });
+
+    app.open_alert_editor();
+    let editor = app.alert_editor.as_ref().unwrap();
+    assert_eq!(editor.field, AlertField::Window);
+    assert_eq!(editor.window, "30");
+}
+
+#[test]
+fn test_rule_alert_across_symbols() {
+    let mut app = App::new(Config::default());
+    let rule = AlertRule::parse("ETHUSDT / BTCUSDT < 0.05 and ETHUSDT.volume > 1000").unwrap();
+    app.create_alert("ETHUSDT".to_string(), AlertCondition::Rule(rule), None);
+
+    let tickers = |eth, volume| vec![
+        PriceInfo { symbol: "BTCUSDT".to_string(), price: dec!(60000), ..Default::default() },
+        PriceInfo { symbol: "ETHUSDT".to_string(), price: eth, volume, ..Default::default() },
+    ];
+    app.update_prices(tickers(dec!(2900), 500.0));
+    assert!(app.take_alert_triggers().is_empty());
+    app.update_prices(tickers(dec!(2900), 5000.0));
+
+    let triggers = app.take_alert_triggers();
+    assert_eq!(triggers.len(), 1);
+    assert_eq!(triggers[0].price, dec!(2900));
+    assert_eq!(triggers[0].message, "Rule met: ETHUSDT / BTCUSDT < 0.05 and ETHUSDT.volume > 1000");
+}
+
+#[test]
+fn test_alert_editor_rule_field() {
+    let config = Config { symbols: vec!["BTCUSDT".to_string(), "ETHUSDT".to_string()], ..Config::default() };
+    let mut app = App::new(config);
+    app.open_new_alert_editor();
+
+    let editor = app.alert_editor.as_mut().unwrap();
+    editor.next_field();
+    editor.cycle_choice(false);
+    assert_eq!(editor.condition_kind, ConditionKind::Rule);
+    assert!(!editor.field_applies(AlertField::Symbol));
+    editor.next_field();
+    assert_eq!(editor.field, AlertField::Rule);
+    "any(BTCUSDT, SOLUSDT).change < -5".chars().for_each(|c| editor.input(c));
+
+    // Every symbol the rule reads has to be tracked
+    assert_eq!(app.submit_alert_editor(), None);
+    let editor = app.alert_editor.as_mut().unwrap();
+    assert_eq!(editor.error.as_deref(), Some("SOLUSDT isn't a tracked symbol"));
+    (0..20).for_each(|_| editor.backspace());
+    "ETHUSDT).change < -5".chars().for_each(|c| editor.input(c));
+
+    let id = app.submit_alert_editor().unwrap();
+    let alert = app.alerts.iter().find(|a| a.id == id).unwrap();
+    assert_eq!(alert.symbol, "BTCUSDT");
+    assert_eq!(alert.condition.rule_text(), "any(BTCUSDT, ETHUSDT).change < -5");
+
+    app.open_alert_editor();
+    let editor = app.alert_editor.as_ref().unwrap();
+    assert_eq!(editor.field, AlertField::Rule);
+    assert_eq!(editor.rule, "any(BTCUSDT, ETHUSDT).change < -5");
+}
+
+#[test]
+fn test_alert_editor_notify_field() {
+    let mut config = Config { symbols: vec!["BTCUSDT".to_string()], ..Config::default() };
+    config.notifications.sinks.insert("log".to_string(), SinkConfig::Log { path: "alerts.log".to_string() });
+    let mut app = App::new(config);
+    app.open_new_alert_editor();
+
+    let editor = app.alert_editor.as_mut().unwrap();
+    "50000".chars().for_each(|c| editor.threshold.push(c));
+    while editor.field != AlertField::Sinks {
+        editor.next_field();
+    }
+    "log, pager".chars().for_each(|c| editor.input(c));
+
+    // Only configured sinks can be picked
+    assert_eq!(app.submit_alert_editor(), None);
+    let editor = app.alert_editor.as_mut().unwrap();
+    assert_eq!(editor.error.as_deref(), Some("Unknown notification sink: pager"));
+    (0..7).for_each(|_| editor.backspace());
+    ",bell".chars().for_each(|c| editor.input(c));
+
+    let id = app.submit_alert_editor().unwrap();
+    let alert = app.alerts.iter().find(|a| a.id == id).unwrap();
+    assert_eq!(alert.sinks, vec!["log".to_string(), "bell".to_string()]);
+
+    app.open_alert_editor();
+    assert_eq!(app.alert_editor.as_ref().unwrap().sinks, "log, bell");
+
+    // Clearing the field goes back to the default sinks
+    let editor = app.alert_editor.as_mut().unwrap();
+    editor.sinks.clear();
+    app.submit_alert_editor().unwrap();
+    assert!(app.alerts[0].sinks.is_empty());
+}
+
+#[test]
+fn test_configured_rule_alerts_are_created_once() {
+    let config = Config {
+        symbols: vec!["BTCUSDT".to_string(), "ETHUSDT".to_string()],
+        alerts: vec![RuleAlertConfig {
+            rule: "ETHUSDT < 2000".to_string(),
+            message: Some("ETH cheap".to_string()),
+            cooldown_minutes: 5,
+            once: true,
+            sinks: vec!["bell".to_string()],
+        }],
+        ..Config::default()
+    };
+    let mut app = App::new(config);
+
+    let imported = app.add_configured_alerts();
+    assert_eq!(imported, vec![("ETHUSDT < 2000".to_string(), Some(app.alerts[0].id))]);
+    let alert = &app.alerts[0];
+    assert_eq!(alert.symbol, "ETHUSDT");
+    assert_eq!(alert.cooldown_minutes, 5);
+    assert_eq!(alert.mode, AlertMode::Once);
+    assert_eq!(alert.message.as_deref(), Some("ETH cheap"));
+    assert_eq!(alert.sinks, vec!["bell".to_string()]);
+
+    // Already imported in an earlier run
+    assert!(app.add_configured_alerts().is_empty());
+    assert_eq!(app.alerts.len(), 1);
+
+    // Deleting or editing the imported alert sticks across restarts
+    let mut restarted = App::new(app.config.clone());
+    restarted.load_imported_rules(vec!["ETHUSDT < 2000".to_string()]);
+    assert!(restarted.add_configured_alerts().is_empty());
+    assert!(restarted.alerts.is_empty());
+
+    let mut edited = App::new(app.config.clone());
+    let mut alert = app.alerts[0].clone();
+    alert.condition = AlertCondition::Rule(AlertRule::parse("ETHUSDT < 1800").unwrap());
+    edited.load_alerts(vec![alert]);
+    edited.load_imported_rules(vec!["ETHUSDT < 2000".to_string()]);
+    assert!(edited.add_configured_alerts().is_empty());
+    assert_eq!(edited.alerts.len(), 1);
+
+    // Alerts created before imports were recorded are marked, not duplicated
+    let mut upgraded = App::new(app.config.clone());
+    upgraded.load_alerts(app.alerts.clone());
+    assert_eq!(upgraded.add_configured_alerts(), vec![("ETHUSDT < 2000".to_string(), None)]);
+    assert_eq!(upgraded.alerts.len(), 1);
+}
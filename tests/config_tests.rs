@@ -1,7 +1,12 @@
 use std::fs;
 use std::path::Path;
 
-use coinpeek::config::Config;
+use chrono::Utc;
+use rust_decimal_macros::dec;
+
+use coinpeek::config::{ChartSource, Config, SinkConfig, WebhookFormat};
+use coinpeek::symbols::{SymbolInfo, SymbolRegistry};
+use coinpeek::timeframe::TimeFrame;
 
 /// Guard to restore the working directory when dropped
 struct DirGuard {
@@ -65,6 +70,7 @@ fn test_config_load_existing_file() {
     let custom_config = Config {
         symbols: vec!["BTCUSDT".to_string(), "ETHUSDT".to_string()],
         refresh_interval_seconds: 10,
+        ..Config::default()
     };
 
     let json = serde_json::to_string_pretty(&custom_config).unwrap();
@@ -83,6 +89,7 @@ fn test_config_json_serialization() {
     let config = Config {
         symbols: vec!["BTCUSDT".to_string(), "ETHUSDT".to_string()],
         refresh_interval_seconds: 15,
+        ..Config::default()
     };
 
     // Serialize to JSON
@@ -102,6 +109,7 @@ fn test_config_validation() {
     let config = Config {
         symbols: vec![],
         refresh_interval_seconds: 30,
+        ..Config::default()
     };
 
     // Should serialize/deserialize fine
@@ -113,6 +121,7 @@ fn test_config_validation() {
     let config = Config {
         symbols: vec!["BTCUSDT".to_string()],
         refresh_interval_seconds: 3600, // 1 hour
+        ..Config::default()
     };
 
     let json = serde_json::to_string(&config).unwrap();
@@ -125,6 +134,7 @@ fn test_config_pretty_json_formatting() {
     let config = Config {
         symbols: vec!["BTCUSDT".to_string()],
         refresh_interval_seconds: 5,
+        ..Config::default()
     };
 
     let json = serde_json::to_string_pretty(&config).unwrap();
@@ -137,3 +147,228 @@ fn test_config_pretty_json_formatting() {
     let deserialized: Config = serde_json::from_str(&json).unwrap();
     assert_eq!(deserialized.symbols, config.symbols);
 }
+
+#[test]
+fn test_api_config_defaults_and_validation() {
+    // Older config files without an "api" section still load with defaults
+    let config: Config = serde_json::from_str(r#"{"symbols":["BTCUSDT"],"refresh_interval_seconds":5}"#).unwrap();
+    assert_eq!(config.api.base_url, "https://api.binance.com");
+    assert!(config.validate().is_ok());
+
+    let mut config = Config::default();
+    config.api.base_url = "ftp://example.com".to_string();
+    assert!(config.validate().is_err());
+
+    let mut config = Config::default();
+    config.api.timeout_seconds = 0;
+    assert!(config.validate().is_err());
+
+    let mut config = Config::default();
+    config.api.base_url = "http://127.0.0.1:8080".to_string();
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn test_chart_config_defaults_and_validation() {
+    let config: Config = serde_json::from_str(r#"{"symbols":["BTCUSDT"],"refresh_interval_seconds":5}"#).unwrap();
+    assert_eq!(config.chart.source, ChartSource::Exchange);
+    assert_eq!(config.chart.tick_bar_seconds, 10);
+
+    let config: Config = serde_json::from_str(
+        r#"{"symbols":["BTCUSDT"],"refresh_interval_seconds":5,"chart":{"source":"ticks","tick_bar_seconds":30}}"#,
+    )
+    .unwrap();
+    assert_eq!(config.chart.source, ChartSource::Ticks);
+    assert_eq!(config.chart.tick_bar_seconds, 30);
+    assert!(config.validate().is_ok());
+
+    let mut config = Config::default();
+    config.chart.tick_bar_seconds = 0;
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_retention_config_defaults_and_validation() {
+    let config: Config = serde_json::from_str(r#"{"symbols":["BTCUSDT"],"refresh_interval_seconds":5}"#).unwrap();
+    assert_eq!(config.retention.price_days, 30);
+    assert_eq!(config.retention.downsample_timeframe(), TimeFrame::M1);
+    assert_eq!(config.retention.candle_days_for(TimeFrame::M1), 90);
+    assert_eq!(config.retention.candle_days_for(TimeFrame::H1), 90);
+    assert_eq!(config.retention.candle_days_for(TimeFrame::D1), 0);
+
+    let config: Config = serde_json::from_str(
+        r#"{"symbols":["BTCUSDT"],"refresh_interval_seconds":5,"retention":{"price_days":7,"candle_days":{"5m":14}}}"#,
+    )
+    .unwrap();
+    assert_eq!(config.retention.price_days, 7);
+    assert_eq!(config.retention.candle_days_for(TimeFrame::M5), 14);
+    assert_eq!(config.retention.candle_days_for(TimeFrame::M1), 90, "Listing intervals replaces the defaults");
+    assert!(config.validate().is_ok());
+
+    let mut config = Config::default();
+    config.retention.downsample_interval = "1w".to_string();
+    assert!(config.validate().is_err());
+
+    let mut config = Config::default();
+    config.retention.candle_days.insert("2m".to_string(), 10);
+    assert!(config.validate().is_err());
+
+    let mut config = Config::default();
+    config.retention.price_days = 0;
+    assert!(config.validate().is_err());
+
+    // Downsampled candles have to outlive the refreshes they're built from
+    let mut config = Config::default();
+    config.retention.candle_days.insert("1m".to_string(), 30);
+    assert!(config.validate().unwrap_err().contains("1m candles are kept for 30 days"));
+    config.retention.candle_days.insert("1m".to_string(), 0);
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn test_alert_rules_in_config() {
+    let config: Config = serde_json::from_str(
+        r#"{"symbols":["BTCUSDT","ETHUSDT"],"refresh_interval_seconds":5,
+            "alerts":[{"rule":"ETHUSDT / BTCUSDT < 0.05","once":true}]}"#,
+    )
+    .unwrap();
+    assert_eq!(config.alerts[0].cooldown_minutes, 60);
+    assert!(config.alerts[0].once);
+    assert!(config.validate().is_ok());
+
+    let mut config = config;
+    config.alerts[0].rule = "SOLUSDT > 100".to_string();
+    assert!(config.validate().unwrap_err().contains("SOLUSDT isn't a tracked symbol"));
+    config.alerts[0].rule = "ETHUSDT >".to_string();
+    assert!(config.validate().is_err());
+
+    // Configs without rules are written without the field
+    assert!(!serde_json::to_string(&Config::default()).unwrap().contains("alerts"));
+}
+
+#[test]
+fn test_notification_sinks_in_config() {
+    // The bell alone until other sinks are configured
+    let config = Config::default();
+    assert_eq!(config.notifications.sinks.get("bell"), Some(&SinkConfig::Bell));
+    assert_eq!(config.notifications.default_sinks, vec!["bell".to_string()]);
+
+    let config: Config = serde_json::from_str(
+        r#"{"symbols":["BTCUSDT","ETHUSDT"],"refresh_interval_seconds":5,
+            "notifications":{
+                "sinks":{
+                    "desktop":{"type":"command","command":"notify-send coinpeek \"$COINPEEK_MESSAGE\""},
+                    "slack":{"type":"webhook","url":"https://hooks.slack.com/services/T0/B0/X","format":"slack"},
+                    "phone":{"type":"webhook","url":"https://ntfy.sh","format":"ntfy","topic":"prices"},
+                    "log":{"type":"log","path":"alerts.log"}
+                },
+                "default_sinks":["desktop","log"]
+            },
+            "alerts":[{"rule":"ETHUSDT < 2000","sinks":["phone"]}]}"#,
+    )
+    .unwrap();
+    assert_eq!(config.notifications.retries, 2);
+    assert_eq!(config.notifications.sinks.len(), 4);
+    assert_eq!(
+        config.notifications.sinks["slack"],
+        SinkConfig::Webhook {
+            url: "https://hooks.slack.com/services/T0/B0/X".to_string(),
+            format: WebhookFormat::Slack,
+            topic: None,
+        }
+    );
+    assert_eq!(config.alerts[0].sinks, vec!["phone".to_string()]);
+    assert!(config.validate().is_ok());
+
+    let mut bad = config.clone();
+    bad.alerts[0].sinks = vec!["pager".to_string()];
+    assert!(bad.validate().unwrap_err().contains("Unknown notification sink: pager"));
+
+    let mut bad = config.clone();
+    bad.notifications.default_sinks.push("bell".to_string());
+    assert!(bad.validate().is_err(), "Configuring sinks replaces the default bell");
+
+    let mut bad = config.clone();
+    bad.notifications.sinks.insert("phone".to_string(), SinkConfig::Webhook {
+        url: "https://ntfy.sh".to_string(),
+        format: WebhookFormat::Ntfy,
+        topic: None,
+    });
+    assert!(bad.validate().unwrap_err().contains("needs an ntfy topic"));
+
+    let mut bad = config.clone();
+    bad.notifications.sinks.insert("bad name".to_string(), SinkConfig::Bell);
+    assert!(bad.validate().is_err());
+
+    let mut bad = config;
+    bad.notifications.retries = 11;
+    assert!(bad.validate().is_err());
+}
+
+fn symbol_info(symbol: &str, base: &str, status: &str) -> SymbolInfo {
+    SymbolInfo {
+        symbol: symbol.to_string(),
+        base_asset: base.to_string(),
+        quote_asset: "USDT".to_string(),
+        status: status.to_string(),
+        tick_size: dec!(0.01),
+        step_size: dec!(0.00001),
+        min_qty: dec!(0.00001),
+    }
+}
+
+#[test]
+fn test_symbols_are_checked_against_exchange_metadata() {
+    let registry = SymbolRegistry::new(
+        vec![
+            symbol_info("BTCUSDT", "BTC", "TRADING"),
+            symbol_info("1INCHUSDT", "1INCH", "TRADING"),
+            symbol_info("LUNAUSDT", "LUNA", "BREAK"),
+        ],
+        Utc::now(),
+    );
+
+    let mut config = Config {
+        symbols: vec!["BTCUSDT".to_string(), "1INCHUSDT".to_string()],
+        ..Config::default()
+    };
+    assert!(config.validate().is_ok(), "Digits are allowed in symbols");
+    assert!(config.retain_usable_symbols(&registry).unwrap().is_empty());
+    assert_eq!(config.symbols.len(), 2);
+
+    // Unknown and halted symbols are dropped, the rest kept
+    let mut config = Config {
+        symbols: vec!["BTCUSDT".to_string(), "LUNAUSDT".to_string(), "FOOUSDT".to_string()],
+        ..Config::default()
+    };
+    let dropped: Vec<String> = config.retain_usable_symbols(&registry).unwrap().iter().map(|e| e.to_string()).collect();
+    assert_eq!(config.symbols, vec!["BTCUSDT".to_string()]);
+    assert_eq!(dropped, vec![
+        "Invalid symbol LUNAUSDT: not currently trading (status BREAK)".to_string(),
+        "Invalid symbol FOOUSDT: not listed on the exchange".to_string(),
+    ]);
+
+    // Only running out of symbols stops startup
+    let mut config = Config {
+        symbols: vec!["LUNAUSDT".to_string(), "FOOUSDT".to_string()],
+        ..Config::default()
+    };
+    let err = config.retain_usable_symbols(&registry).unwrap_err();
+    assert!(err.contains("LUNAUSDT is not currently trading (status BREAK)"), "{}", err);
+    assert!(err.contains("FOOUSDT is not listed on the exchange"), "{}", err);
+
+    // Without metadata (e.g. first run offline) nothing can be ruled out
+    assert!(config.retain_usable_symbols(&SymbolRegistry::default()).unwrap().is_empty());
+    assert_eq!(config.symbols.len(), 2);
+}
+
+#[test]
+fn test_symbol_registry_staleness() {
+    let fetched = Utc::now() - chrono::Duration::hours(25);
+    let registry = SymbolRegistry::new(vec![symbol_info("BTCUSDT", "BTC", "TRADING")], fetched);
+    assert!(registry.is_stale(Utc::now()));
+    assert!(!registry.is_stale(fetched + chrono::Duration::hours(1)));
+    assert!(SymbolRegistry::default().is_stale(Utc::now()));
+    assert_eq!(registry.tick_size("BTCUSDT"), Some(dec!(0.01)));
+    assert_eq!(registry.tick_size("ETHUSDT"), None);
+}
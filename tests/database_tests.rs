@@ -1,9 +1,14 @@
+use rust_decimal_macros::dec;
 use std::sync::Arc;
 use tempfile::NamedTempFile;
 use tokio_test::block_on;
 
+use coinpeek::app::{AlertCondition, AlertMode, AlertTrigger, PriceAlert};
+use coinpeek::config::RetentionConfig;
 use coinpeek::database::{Database, DatabaseStats};
 use coinpeek::binance::{PriceInfo, Candle};
+use coinpeek::symbols::SymbolInfo;
+use coinpeek::timeframe::TimeFrame;
 
 #[test]
 fn test_database_initialization() {
@@ -27,12 +32,13 @@ fn test_store_and_retrieve_price_info() {
         // Create test price info
         let price_info = PriceInfo {
             symbol: "BTCUSDT".to_string(),
-            price: 50000.0,
+            price: dec!(50000.0),
             price_change_percent: 2.5,
             volume: 1000.0,
-            high_24h: 51000.0,
-            low_24h: 49000.0,
-            prev_close_price: 48750.0,
+            high_24h: dec!(51000.0),
+            low_24h: dec!(49000.0),
+            prev_close_price: dec!(48750.0),
+            ..Default::default()
         };
 
         // Store price info
@@ -45,7 +51,7 @@ fn test_store_and_retrieve_price_info() {
 
         let retrieved_price = retrieved.unwrap();
         assert_eq!(retrieved_price.symbol, "BTCUSDT");
-        assert_eq!(retrieved_price.price, 50000.0);
+        assert_eq!(retrieved_price.price, dec!(50000.0));
         assert_eq!(retrieved_price.price_change_percent, 2.5);
     });
 }
@@ -61,21 +67,23 @@ fn test_bulk_price_storage() {
         let price_infos = vec![
             PriceInfo {
                 symbol: "BTCUSDT".to_string(),
-                price: 50000.0,
+                price: dec!(50000.0),
                 price_change_percent: 2.5,
                 volume: 1000.0,
-                high_24h: 51000.0,
-                low_24h: 49000.0,
-                prev_close_price: 48750.0,
+                high_24h: dec!(51000.0),
+                low_24h: dec!(49000.0),
+                prev_close_price: dec!(48750.0),
+                ..Default::default()
             },
             PriceInfo {
                 symbol: "ETHUSDT".to_string(),
-                price: 3000.0,
+                price: dec!(3000.0),
                 price_change_percent: -1.2,
                 volume: 500.0,
-                high_24h: 3100.0,
-                low_24h: 2900.0,
-                prev_close_price: 3036.0,
+                high_24h: dec!(3100.0),
+                low_24h: dec!(2900.0),
+                prev_close_price: dec!(3036.0),
+                ..Default::default()
             },
         ];
 
@@ -87,8 +95,8 @@ fn test_bulk_price_storage() {
         let btc_price = db.get_latest_price("BTCUSDT").await.unwrap().unwrap();
         let eth_price = db.get_latest_price("ETHUSDT").await.unwrap().unwrap();
 
-        assert_eq!(btc_price.price, 50000.0);
-        assert_eq!(eth_price.price, 3000.0);
+        assert_eq!(btc_price.price, dec!(50000.0));
+        assert_eq!(eth_price.price, dec!(3000.0));
     });
 }
 
@@ -102,18 +110,18 @@ fn test_candle_storage_and_retrieval() {
 
         let candles = vec![
             Candle {
-                open: 50000.0,
-                high: 51000.0,
-                low: 49000.0,
-                close: 50500.0,
+                open: dec!(50000.0),
+                high: dec!(51000.0),
+                low: dec!(49000.0),
+                close: dec!(50500.0),
                 volume: 100.0,
                 timestamp: 1640995200000, // 2022-01-01 00:00:00 UTC
             },
             Candle {
-                open: 50500.0,
-                high: 51500.0,
-                low: 50000.0,
-                close: 51000.0,
+                open: dec!(50500.0),
+                high: dec!(51500.0),
+                low: dec!(50000.0),
+                close: dec!(51000.0),
                 volume: 120.0,
                 timestamp: 1640995260000, // 2022-01-01 00:01:00 UTC
             },
@@ -128,9 +136,9 @@ fn test_candle_storage_and_retrieval() {
         assert_eq!(retrieved.len(), 2, "Should retrieve both candles");
 
         // Verify data integrity
-        assert_eq!(retrieved[0].open, 50000.0);
-        assert_eq!(retrieved[0].close, 50500.0);
-        assert_eq!(retrieved[1].high, 51500.0);
+        assert_eq!(retrieved[0].open, dec!(50000.0));
+        assert_eq!(retrieved[0].close, dec!(50500.0));
+        assert_eq!(retrieved[1].high, dec!(51500.0));
         assert_eq!(retrieved[1].volume, 120.0);
     });
 }
@@ -151,21 +159,22 @@ fn test_database_statistics() {
         // Add some data
         let price_info = PriceInfo {
             symbol: "BTCUSDT".to_string(),
-            price: 50000.0,
+            price: dec!(50000.0),
             price_change_percent: 2.5,
             volume: 1000.0,
-            high_24h: 51000.0,
-            low_24h: 49000.0,
-            prev_close_price: 48750.0,
+            high_24h: dec!(51000.0),
+            low_24h: dec!(49000.0),
+            prev_close_price: dec!(48750.0),
+            ..Default::default()
         };
 
         db.store_price_info(&price_info).await.unwrap();
 
         let candle = Candle {
-            open: 50000.0,
-            high: 51000.0,
-            low: 49000.0,
-            close: 50500.0,
+            open: dec!(50000.0),
+            high: dec!(51000.0),
+            low: dec!(49000.0),
+            close: dec!(50500.0),
             volume: 100.0,
             timestamp: 1640995200000,
         };
@@ -222,21 +231,23 @@ fn test_get_active_symbols() {
         let price_infos = vec![
             PriceInfo {
                 symbol: "BTCUSDT".to_string(),
-                price: 50000.0,
+                price: dec!(50000.0),
                 price_change_percent: 2.5,
                 volume: 1000.0,
-                high_24h: 51000.0,
-                low_24h: 49000.0,
-                prev_close_price: 48750.0,
+                high_24h: dec!(51000.0),
+                low_24h: dec!(49000.0),
+                prev_close_price: dec!(48750.0),
+                ..Default::default()
             },
             PriceInfo {
                 symbol: "ETHUSDT".to_string(),
-                price: 3000.0,
+                price: dec!(3000.0),
                 price_change_percent: -1.2,
                 volume: 500.0,
-                high_24h: 3100.0,
-                low_24h: 2900.0,
-                prev_close_price: 3036.0,
+                high_24h: dec!(3100.0),
+                low_24h: dec!(2900.0),
+                prev_close_price: dec!(3036.0),
+                ..Default::default()
             },
         ];
 
@@ -279,3 +290,751 @@ fn test_empty_candle_storage() {
         assert!(retrieved.is_empty(), "Should return empty array");
     });
 }
+
+#[test]
+fn test_bid_ask_round_trip_and_legacy_prices_table() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let db_path = temp_db.path().to_str().unwrap();
+
+    // A prices table as created by earlier releases, without bid/ask columns
+    {
+        let conn = rusqlite::Connection::open(db_path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE prices (
+                id INTEGER PRIMARY KEY,
+                symbol TEXT NOT NULL,
+                price REAL NOT NULL,
+                price_change_percent REAL,
+                volume REAL,
+                high_24h REAL,
+                low_24h REAL,
+                prev_close_price REAL,
+                timestamp INTEGER NOT NULL,
+                exchange TEXT DEFAULT 'binance',
+                created_at INTEGER DEFAULT (strftime('%s', 'now'))
+            );
+            INSERT INTO prices (symbol, price, price_change_percent, volume, high_24h, low_24h, prev_close_price, timestamp)
+            VALUES ('ETHUSDT', 3000.0, 1.0, 10.0, 3100.0, 2900.0, 2970.0, 1640995200);"
+        ).unwrap();
+    }
+
+    block_on(async {
+        let db = Database::new(db_path).await.unwrap();
+
+        let legacy = db.get_latest_price("ETHUSDT").await.unwrap().unwrap();
+        assert_eq!(legacy.price, dec!(3000.0));
+        assert_eq!(legacy.bid_price, dec!(0.0));
+
+        let price_info = PriceInfo {
+            symbol: "BTCUSDT".to_string(),
+            price: dec!(50000.0),
+            bid_price: dec!(49999.5),
+            ask_price: dec!(50000.5),
+            quote_volume: 1_000_000.0,
+            ..Default::default()
+        };
+        db.store_price_infos(&[price_info]).await.unwrap();
+
+        let retrieved = db.get_latest_price("BTCUSDT").await.unwrap().unwrap();
+        assert_eq!(retrieved.bid_price, dec!(49999.5));
+        assert_eq!(retrieved.ask_price, dec!(50000.5));
+        assert_eq!(retrieved.quote_volume, 1_000_000.0);
+    });
+}
+
+#[test]
+fn test_decimal_prices_round_trip_exactly() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let db_path = temp_db.path().to_str().unwrap();
+
+    block_on(async {
+        let db = Database::new(db_path).await.unwrap();
+
+        // Values that have no exact binary floating point representation
+        let price_info = PriceInfo {
+            symbol: "SHIBUSDT".to_string(),
+            price: dec!(0.00001234),
+            high_24h: dec!(0.00001301),
+            low_24h: dec!(0.00001199),
+            prev_close_price: dec!(0.3),
+            bid_price: dec!(0.00001233),
+            ask_price: dec!(0.00001235),
+            ..Default::default()
+        };
+        db.store_price_info(&price_info).await.unwrap();
+
+        let retrieved = db.get_latest_price("SHIBUSDT").await.unwrap().unwrap();
+        assert_eq!(retrieved.price.to_string(), "0.00001234");
+        assert_eq!(retrieved.prev_close_price.to_string(), "0.3");
+        assert_eq!(retrieved.ask_price - retrieved.bid_price, dec!(0.00000002));
+
+        let candle = Candle {
+            open: dec!(0.1),
+            high: dec!(0.3),
+            low: dec!(0.1),
+            close: dec!(0.2),
+            volume: 1.0,
+            timestamp: 1640995200000,
+        };
+        db.store_candles("SHIBUSDT", "1m", &[candle]).await.unwrap();
+
+        let candles = db.get_candles("SHIBUSDT", "1m", 1).await.unwrap();
+        assert_eq!(candles[0].open + candles[0].close, candles[0].high);
+    });
+}
+
+#[test]
+fn test_legacy_real_candles_are_converted_to_text() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let db_path = temp_db.path().to_str().unwrap();
+
+    {
+        let conn = rusqlite::Connection::open(db_path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE candles (
+                id INTEGER PRIMARY KEY,
+                symbol TEXT NOT NULL,
+                timeframe TEXT NOT NULL,
+                open REAL NOT NULL,
+                high REAL NOT NULL,
+                low REAL NOT NULL,
+                close REAL NOT NULL,
+                volume REAL,
+                timestamp INTEGER NOT NULL,
+                exchange TEXT DEFAULT 'binance',
+                created_at INTEGER DEFAULT (strftime('%s', 'now'))
+            );
+            INSERT INTO candles (symbol, timeframe, open, high, low, close, volume, timestamp)
+            VALUES ('BTCUSDT', '1m', 50000.5, 50100.0, 49900.0, 50050.25, 1.5, 1640995200000);"
+        ).unwrap();
+    }
+
+    block_on(async {
+        let db = Database::new(db_path).await.unwrap();
+        let candles = db.get_candles("BTCUSDT", "1m", 10).await.unwrap();
+        assert_eq!(candles.len(), 1);
+        assert_eq!(candles[0].open, dec!(50000.5));
+        assert_eq!(candles[0].close, dec!(50050.25));
+    });
+
+    let conn = rusqlite::Connection::open(db_path).unwrap();
+    let declared: String = conn
+        .query_row("SELECT type FROM pragma_table_info('candles') WHERE name = 'open'", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(declared, "TEXT");
+}
+
+#[test]
+fn test_symbol_metadata_cache() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let db_path = temp_db.path().to_str().unwrap();
+
+    block_on(async {
+        let db = Database::new(db_path).await.unwrap();
+        assert!(db.load_symbol_registry().await.unwrap().is_empty());
+
+        let fetched_at = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let symbols = vec![SymbolInfo {
+            symbol: "VETUSDT".to_string(),
+            base_asset: "VET".to_string(),
+            quote_asset: "USDT".to_string(),
+            status: "TRADING".to_string(),
+            tick_size: dec!(0.00001),
+            step_size: dec!(0.1),
+            min_qty: dec!(0.1),
+        }];
+        db.store_symbols(&symbols, fetched_at).await.unwrap();
+
+        let registry = db.load_symbol_registry().await.unwrap();
+        assert_eq!(registry.len(), 1);
+        assert_eq!(registry.get("VETUSDT"), Some(&symbols[0]));
+        assert_eq!(registry.updated_at(), Some(fetched_at));
+
+        // A refresh replaces the old list, dropping delisted symbols
+        db.store_symbols(&[], fetched_at).await.unwrap();
+        assert!(db.load_symbol_registry().await.unwrap().is_empty());
+    });
+}
+
+#[test]
+fn test_storing_candles_again_updates_instead_of_duplicating() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let db_path = temp_db.path().to_str().unwrap();
+
+    block_on(async {
+        let db = Database::new(db_path).await.unwrap();
+
+        let mut candles: Vec<Candle> = (0..3)
+            .map(|i| Candle {
+                open: dec!(100),
+                high: dec!(101),
+                low: dec!(99),
+                close: dec!(100),
+                volume: 1.0,
+                timestamp: 1640995200000 + i * 300_000,
+            })
+            .collect();
+        db.store_candles("BTCUSDT", "5m", &candles).await.unwrap();
+
+        // The last bar was still open and has moved since
+        candles[2].close = dec!(102.5);
+        candles[2].high = dec!(103);
+        candles[2].volume = 4.0;
+        db.store_candles("BTCUSDT", "5m", &candles).await.unwrap();
+
+        let retrieved = db.get_candles("BTCUSDT", "5m", 10).await.unwrap();
+        assert_eq!(retrieved.len(), 3);
+        let last = retrieved.iter().find(|c| c.timestamp == candles[2].timestamp).unwrap();
+        assert_eq!(last.close, dec!(102.5));
+        assert_eq!(last.high, dec!(103));
+        assert_eq!(last.volume, 4.0);
+
+        // Same timestamp under another timeframe is a different bar
+        db.store_candles("BTCUSDT", "1m", &candles[..1]).await.unwrap();
+        assert_eq!(db.get_stats().await.unwrap().candle_records, 4);
+    });
+}
+
+#[test]
+fn test_existing_duplicate_candles_are_removed_on_open() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let db_path = temp_db.path().to_str().unwrap();
+
+    // A database written before candles were unique
+    {
+        let conn = rusqlite::Connection::open(db_path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE candles (
+                id INTEGER PRIMARY KEY,
+                symbol TEXT NOT NULL,
+                timeframe TEXT NOT NULL,
+                open TEXT NOT NULL,
+                high TEXT NOT NULL,
+                low TEXT NOT NULL,
+                close TEXT NOT NULL,
+                volume REAL,
+                timestamp INTEGER NOT NULL,
+                exchange TEXT DEFAULT 'binance',
+                created_at INTEGER DEFAULT (strftime('%s', 'now'))
+            );
+            INSERT INTO candles (symbol, timeframe, open, high, low, close, volume, timestamp) VALUES
+                ('BTCUSDT', '5m', '100', '101', '99', '100', 1.0, 1640995200000),
+                ('BTCUSDT', '5m', '100', '101', '99', '100.5', 2.0, 1640995200000),
+                ('BTCUSDT', '5m', '100', '102', '99', '101', 3.0, 1640995200000),
+                ('BTCUSDT', '5m', '101', '102', '100', '101', 1.0, 1640995500000);"
+        ).unwrap();
+    }
+
+    block_on(async {
+        let db = Database::new(db_path).await.unwrap();
+        let candles = db.get_candles("BTCUSDT", "5m", 10).await.unwrap();
+        assert_eq!(candles.len(), 2);
+
+        // The most recently written copy wins
+        let first = candles.iter().find(|c| c.timestamp == 1640995200000).unwrap();
+        assert_eq!(first.close, dec!(101));
+        assert_eq!(first.volume, 3.0);
+    });
+}
+
+#[test]
+fn test_candle_coverage_merges_ranges() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let db_path = temp_db.path().to_str().unwrap();
+
+    block_on(async {
+        let db = Database::new(db_path).await.unwrap();
+        assert!(db.get_candle_coverage("BTCUSDT", "1m").await.unwrap().is_empty());
+
+        db.record_candle_coverage("BTCUSDT", "1m", 100, 199).await.unwrap();
+        db.record_candle_coverage("BTCUSDT", "1m", 400, 499).await.unwrap();
+        db.record_candle_coverage("BTCUSDT", "1m", 0, 49).await.unwrap();
+        assert_eq!(
+            db.get_candle_coverage("BTCUSDT", "1m").await.unwrap(),
+            vec![(0, 49), (100, 199), (400, 499)]
+        );
+
+        // Touching the end of one range and overlapping the next joins all three
+        db.record_candle_coverage("BTCUSDT", "1m", 200, 450).await.unwrap();
+        assert_eq!(db.get_candle_coverage("BTCUSDT", "1m").await.unwrap(), vec![(0, 49), (100, 499)]);
+
+        // A range already inside the coverage changes nothing
+        db.record_candle_coverage("BTCUSDT", "1m", 120, 130).await.unwrap();
+        assert_eq!(db.get_candle_coverage("BTCUSDT", "1m").await.unwrap(), vec![(0, 49), (100, 499)]);
+
+        // Coverage is tracked per symbol and timeframe
+        assert!(db.get_candle_coverage("BTCUSDT", "5m").await.unwrap().is_empty());
+        assert!(db.get_candle_coverage("ETHUSDT", "1m").await.unwrap().is_empty());
+    });
+}
+
+#[test]
+fn test_higher_timeframes_are_rolled_up_from_stored_candles() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let db_path = temp_db.path().to_str().unwrap();
+
+    block_on(async {
+        let db = Database::new(db_path).await.unwrap();
+
+        // Two hours of 5m candles and the first hour of 1m candles
+        let candles = |step: u64, count: u64| -> Vec<Candle> {
+            (0..count)
+                .map(|i| Candle {
+                    open: dec!(100),
+                    high: dec!(101),
+                    low: dec!(99),
+                    close: dec!(100.5),
+                    volume: 1.0,
+                    timestamp: 1640995200000 + i * step,
+                })
+                .collect()
+        };
+        db.store_candles("BTCUSDT", "5m", &candles(300_000, 24)).await.unwrap();
+        db.store_candles("BTCUSDT", "1m", &candles(60_000, 60)).await.unwrap();
+
+        // 5m gives more complete hours than 1m, so it wins
+        let hourly = db.get_rolled_up_candles("BTCUSDT", TimeFrame::H1, 10).await.unwrap();
+        assert_eq!(hourly.len(), 2);
+        assert_eq!(hourly[0].volume, 12.0);
+
+        let last = db.get_rolled_up_candles("BTCUSDT", TimeFrame::H1, 1).await.unwrap();
+        assert_eq!(last.len(), 1);
+        assert_eq!(last[0].timestamp, 1640995200000 + 3_600_000);
+
+        assert!(db.get_rolled_up_candles("BTCUSDT", TimeFrame::D1, 10).await.unwrap().is_empty());
+        assert!(db.get_rolled_up_candles("BTCUSDT", TimeFrame::M5, 10).await.unwrap().is_empty());
+
+        // The exchange reported a different close for the second hour
+        let mut exchange = hourly.clone();
+        exchange[1].close = dec!(100.4);
+        db.store_candles("BTCUSDT", "1h", &exchange).await.unwrap();
+        let mismatches = db.check_rollup("BTCUSDT", TimeFrame::H1, 10).await.unwrap();
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].timestamp, exchange[1].timestamp);
+    });
+}
+
+#[test]
+fn test_tick_candles_from_recorded_prices() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let db_path = temp_db.path().to_str().unwrap();
+
+    block_on(async {
+        let db = Database::new(db_path).await.unwrap();
+        assert!(db.get_tick_candles("BTCUSDT", 10, 5).await.unwrap().is_empty());
+    });
+
+    // A minute of refreshes every 3 seconds, plus another symbol
+    {
+        let conn = rusqlite::Connection::open(db_path).unwrap();
+        for i in 0..20i64 {
+            conn.execute(
+                "INSERT INTO prices (symbol, price, timestamp) VALUES ('BTCUSDT', ?, ?)",
+                rusqlite::params![(50000 + i).to_string(), 1640995200 + i * 3],
+            )
+            .unwrap();
+        }
+        conn.execute("INSERT INTO prices (symbol, price, timestamp) VALUES ('ETHUSDT', '4000', 1640995260)", []).unwrap();
+    }
+
+    block_on(async {
+        let db = Database::new(db_path).await.unwrap();
+
+        let bars = db.get_tick_candles("BTCUSDT", 10, 100).await.unwrap();
+        assert_eq!(bars.len(), 6);
+        assert_eq!(bars[0].timestamp, 1640995200000);
+        assert_eq!(bars[0].open, dec!(50000));
+        assert_eq!(bars[0].close, dec!(50003));
+        assert_eq!(bars[0].high, dec!(50003));
+
+        // Only the newest bars are returned, oldest first
+        let bars = db.get_tick_candles("BTCUSDT", 10, 2).await.unwrap();
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[0].timestamp, 1640995240000);
+        assert_eq!(bars[1].close, dec!(50019));
+
+        let bars = db.get_tick_candles("BTCUSDT", 30, 10).await.unwrap();
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[1].open, dec!(50010));
+    });
+}
+
+#[test]
+fn test_cleanup_downsamples_and_prunes_by_retention() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let db_path = temp_db.path().to_str().unwrap();
+    // 2022-03-01 00:00 UTC
+    let now = chrono::DateTime::from_timestamp(1646092800, 0).unwrap();
+    let day_s = 24 * 60 * 60;
+    let old_s = 1646092800 - 40 * day_s;
+    let at = |seconds: i64| chrono::DateTime::from_timestamp(seconds, 0).unwrap();
+
+    block_on(async {
+        let db = Database::new(db_path).await.unwrap();
+        db.record_candle_coverage("BTCUSDT", "1m", (1646092800 - 70 * day_s) as u64 * 1000, 1646092800000).await.unwrap();
+    });
+
+    {
+        let conn = rusqlite::Connection::open(db_path).unwrap();
+        // Two minutes of old refreshes and one recent one
+        for (i, price) in ["100", "103", "99", "101", "102"].iter().enumerate() {
+            conn.execute(
+                "INSERT INTO prices (symbol, price, timestamp) VALUES ('BTCUSDT', ?, ?)",
+                rusqlite::params![price, old_s + i as i64 * 20],
+            )
+            .unwrap();
+        }
+        conn.execute("INSERT INTO prices (symbol, price, timestamp) VALUES ('BTCUSDT', '150', ?)", [1646092800 - day_s]).unwrap();
+
+        // The exchange already provided the first minute
+        for (timeframe, timestamp, close) in [
+            ("1m", old_s * 1000, "100.5"),
+            ("5m", (1646092800 - 100 * day_s) * 1000, "1"),
+            ("5m", (1646092800 - 10 * day_s) * 1000, "2"),
+            ("1d", (1646092800 - 400 * day_s) * 1000, "3"),
+        ] {
+            conn.execute(
+                "INSERT INTO candles (symbol, timeframe, open, high, low, close, volume, timestamp)
+                 VALUES ('BTCUSDT', ?, '1', '1', '1', ?, 1.0, ?)",
+                rusqlite::params![timeframe, close, timestamp],
+            )
+            .unwrap();
+        }
+    }
+
+    block_on(async {
+        let db = Database::new(db_path).await.unwrap();
+        let retention = RetentionConfig {
+            candle_days: [("1m".to_string(), 60), ("1d".to_string(), 0)].into(),
+            ..RetentionConfig::default()
+        };
+        let report = db.cleanup_old_data(&retention, now).await.unwrap();
+
+        assert_eq!(report.prices_pruned, 5);
+        assert_eq!(report.downsampled_candles, 2);
+        assert_eq!(report.candles_pruned, 1, "Only the 5m candle past the 90 day default goes; 1d is kept forever");
+
+        // Downsampled bars stay out of the exchange candles
+        let minutes = db.get_candles("BTCUSDT", "1m", 10).await.unwrap();
+        assert_eq!(minutes.len(), 1);
+        assert_eq!(minutes[0].close, dec!(100.5));
+        let downsampled = db.get_downsampled_candle_range("BTCUSDT", "1m", at(old_s), now).await.unwrap();
+        assert_eq!(downsampled.len(), 2);
+        assert_eq!((downsampled[0].open, downsampled[0].close), (dec!(100), dec!(99)));
+        assert_eq!((downsampled[1].open, downsampled[1].high, downsampled[1].close), (dec!(101), dec!(102), dec!(102)));
+        assert_eq!(db.get_candles("BTCUSDT", "1d", 10).await.unwrap().len(), 1);
+
+        // Coverage no longer claims the pruned range
+        let coverage = db.get_candle_coverage("BTCUSDT", "1m").await.unwrap();
+        assert_eq!(coverage, vec![((1646092800 - 60 * day_s) as u64 * 1000, 1646092800000)]);
+
+        let stats = db.get_stats().await.unwrap();
+        assert_eq!(stats.price_records, 1);
+        assert_eq!(stats.last_cleanup, Some(report));
+    });
+
+    let conn = rusqlite::Connection::open(db_path).unwrap();
+    let auto_vacuum: i64 = conn.query_row("PRAGMA auto_vacuum", [], |row| row.get(0)).unwrap();
+    assert_eq!(auto_vacuum, 2, "Incremental vacuum is enabled");
+}
+
+#[test]
+fn test_cleanup_with_default_retention_keeps_downsampled_candles() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let db_path = temp_db.path().to_str().unwrap();
+    // 2022-03-01 00:00 UTC
+    let now = chrono::DateTime::from_timestamp(1646092800, 0).unwrap();
+    let old_s = 1646092800 - 31 * 24 * 60 * 60;
+
+    block_on(Database::new(db_path)).unwrap();
+    {
+        let conn = rusqlite::Connection::open(db_path).unwrap();
+        for (i, price) in ["100", "103", "99", "101"].iter().enumerate() {
+            conn.execute(
+                "INSERT INTO prices (symbol, price, timestamp) VALUES ('BTCUSDT', ?, ?)",
+                rusqlite::params![price, old_s + i as i64 * 20],
+            )
+            .unwrap();
+        }
+    }
+
+    block_on(async {
+        let db = Database::new(db_path).await.unwrap();
+        let report = db.cleanup_old_data(&RetentionConfig::default(), now).await.unwrap();
+
+        assert_eq!(report.prices_pruned, 4);
+        assert_eq!(report.downsampled_candles, 2);
+        assert_eq!(report.candles_pruned, 0);
+        assert!(db.get_candles("BTCUSDT", "1m", 10).await.unwrap().is_empty());
+        let start = chrono::DateTime::from_timestamp(old_s, 0).unwrap();
+        let minutes = db.get_downsampled_candle_range("BTCUSDT", "1m", start, now).await.unwrap();
+        assert_eq!(minutes.len(), 2);
+        assert_eq!((minutes[0].open, minutes[0].high, minutes[0].low, minutes[0].close), (dec!(100), dec!(103), dec!(99), dec!(99)));
+        assert_eq!((minutes[1].open, minutes[1].close), (dec!(101), dec!(101)));
+    });
+}
+
+#[test]
+fn test_cleanup_downsamples_in_windows_without_blocking_klines() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let db_path = temp_db.path().to_str().unwrap();
+    // 2022-03-01 00:00 UTC
+    let now = chrono::DateTime::from_timestamp(1646092800, 0).unwrap();
+    let old_s = 1646092800 - 40 * 24 * 60 * 60;
+
+    block_on(Database::new(db_path)).unwrap();
+    {
+        let conn = rusqlite::Connection::open(db_path).unwrap();
+        // A refresh every 10 minutes for a day, across several windows, for two symbols
+        for symbol in ["BTCUSDT", "ETHUSDT"] {
+            for i in 0..144 {
+                conn.execute(
+                    "INSERT INTO prices (symbol, price, timestamp) VALUES (?, ?, ?)",
+                    rusqlite::params![symbol, (100 + i).to_string(), old_s + i * 600],
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    block_on(async {
+        let db = Database::new(db_path).await.unwrap();
+        let report = db.cleanup_old_data(&RetentionConfig::default(), now).await.unwrap();
+        assert_eq!(report.prices_pruned, 288);
+        assert_eq!(report.downsampled_candles, 288);
+
+        let start = chrono::DateTime::from_timestamp(old_s, 0).unwrap();
+        let minutes = db.get_downsampled_candle_range("ETHUSDT", "1m", start, now).await.unwrap();
+        assert_eq!(minutes.len(), 144);
+        assert_eq!(minutes[143].close, dec!(243));
+
+        // The exchange's bar for a downsampled minute is still stored
+        let kline = Candle { open: dec!(1), high: dec!(2), low: dec!(1), close: dec!(2), volume: 5.0, timestamp: old_s as u64 * 1000 };
+        db.store_candles("BTCUSDT", "1m", &[kline]).await.unwrap();
+        let klines = db.get_candles("BTCUSDT", "1m", 10).await.unwrap();
+        assert_eq!(klines.len(), 1);
+        assert_eq!(klines[0].volume, 5.0);
+    });
+}
+
+#[test]
+fn test_price_history_queries() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let db_path = temp_db.path().to_str().unwrap();
+    let at = |seconds: i64| chrono::DateTime::from_timestamp(1640995200 + seconds, 0).unwrap();
+
+    block_on(Database::new(db_path)).unwrap();
+    {
+        let conn = rusqlite::Connection::open(db_path).unwrap();
+        for (symbol, seconds, price) in [
+            ("BTCUSDT", 0, "100"),
+            ("BTCUSDT", 60, "90"),
+            ("BTCUSDT", 120, "120"),
+            ("BTCUSDT", 180, "110"),
+            ("ETHUSDT", 90, "10"),
+        ] {
+            conn.execute(
+                "INSERT INTO prices (symbol, price, timestamp) VALUES (?, ?, ?)",
+                rusqlite::params![symbol, price, 1640995200 + seconds],
+            )
+            .unwrap();
+        }
+        // An hour earlier only a pruned-down candle is left
+        conn.execute(
+            "INSERT INTO candles (symbol, timeframe, open, high, low, close, volume, timestamp)
+             VALUES ('BTCUSDT', '1m', '95', '96', '94', '95.5', 0, ?)",
+            [(1640995200i64 - 3600) * 1000],
+        )
+        .unwrap();
+    }
+
+    block_on(async {
+        let db = Database::new(db_path).await.unwrap();
+
+        let history = db.get_price_history("BTCUSDT", at(60), at(120)).await.unwrap();
+        assert_eq!(history.iter().map(|p| p.price).collect::<Vec<_>>(), vec![dec!(90), dec!(120)]);
+
+        // Between refreshes the previous one is the answer
+        let price = db.get_price_at("BTCUSDT", at(150)).await.unwrap().unwrap();
+        assert_eq!((price.timestamp, price.price), (at(120), dec!(120)));
+
+        // Inside the candle its open is the last known price, after it the close
+        let price = db.get_price_at("BTCUSDT", at(-3570)).await.unwrap().unwrap();
+        assert_eq!((price.timestamp, price.price), (at(-3600), dec!(95)));
+        let price = db.get_price_at("BTCUSDT", at(-1800)).await.unwrap().unwrap();
+        assert_eq!((price.timestamp, price.price), (at(-3540), dec!(95.5)));
+        assert!(db.get_price_at("BTCUSDT", at(-7200)).await.unwrap().is_none());
+
+        let stats = db.get_price_stats("BTCUSDT", at(0), at(180)).await.unwrap().unwrap();
+        assert_eq!((stats.min, stats.max, stats.average), (dec!(90), dec!(120), dec!(105)));
+        assert!((stats.return_percent - 10.0).abs() < 1e-9);
+        assert!(db.get_price_stats("BTCUSDT", at(600), at(900)).await.unwrap().is_none());
+
+        let aligned = db.get_aligned_series(&["BTCUSDT", "ETHUSDT"], at(30), at(150), chrono::Duration::seconds(60)).await.unwrap();
+        assert_eq!(aligned.timestamps, vec![at(30), at(90), at(150)]);
+        assert_eq!(aligned.series[0].symbol, "BTCUSDT");
+        assert_eq!(aligned.series[0].prices, vec![Some(dec!(100)), Some(dec!(90)), Some(dec!(120))]);
+        assert_eq!(aligned.series[1].prices, vec![None, Some(dec!(10)), Some(dec!(10))]);
+    });
+}
+
+#[test]
+fn test_alerts_are_saved_toggled_and_deleted() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let db_path = temp_db.path().to_str().unwrap();
+    let created_at = chrono::DateTime::from_timestamp(1640995200, 0).unwrap();
+    let alert = |id, symbol: &str, condition| PriceAlert {
+        id,
+        symbol: symbol.to_string(),
+        condition,
+        enabled: true,
+        created_at,
+        last_triggered: None,
+        trigger_count: 0,
+        message: Some("Breakout".to_string()),
+        cooldown_minutes: 60,
+        mode: AlertMode::Recurring,
+        armed: None,
+        sinks: vec!["desktop".to_string(), "slack".to_string()],
+    };
+
+    block_on(async {
+        let db = Database::new(db_path).await.unwrap();
+        assert!(db.get_alerts().await.unwrap().is_empty());
+
+        db.save_alert(&alert(1, "BTCUSDT", AlertCondition::PriceAbove(dec!(55000.25)))).await.unwrap();
+        db.save_alert(&alert(2, "ETHUSDT", AlertCondition::PercentChangeBelow(-5.5))).await.unwrap();
+        db.set_alert_enabled(2, false).await.unwrap();
+
+        // Reopening the file finds the same alerts
+        let db = Database::new(db_path).await.unwrap();
+        let alerts = db.get_alerts().await.unwrap();
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(alerts[0].condition, AlertCondition::PriceAbove(dec!(55000.25)));
+        assert_eq!(alerts[0].message.as_deref(), Some("Breakout"));
+        assert_eq!(alerts[0].sinks, vec!["desktop".to_string(), "slack".to_string()]);
+        assert_eq!(alerts[0].created_at, created_at);
+        assert!(alerts[0].enabled);
+        assert_eq!(alerts[1].condition, AlertCondition::PercentChangeBelow(-5.5));
+        assert!(!alerts[1].enabled);
+
+        // Saving again edits in place
+        db.save_alert(&alert(1, "BTCUSDT", AlertCondition::PriceBelow(dec!(40000)))).await.unwrap();
+        db.delete_alert(2).await.unwrap();
+        let alerts = db.get_alerts().await.unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].condition, AlertCondition::PriceBelow(dec!(40000)));
+    });
+}
+
+#[test]
+fn test_imported_alert_rules_are_remembered() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let db_path = temp_db.path().to_str().unwrap();
+
+    block_on(async {
+        let db = Database::new(db_path).await.unwrap();
+        assert!(db.get_imported_alert_rules().await.unwrap().is_empty());
+
+        db.record_alert_rule_import("ETHUSDT < 2000").await.unwrap();
+        db.record_alert_rule_import("ETHUSDT < 2000").await.unwrap();
+
+        let db = Database::new(db_path).await.unwrap();
+        assert_eq!(db.get_imported_alert_rules().await.unwrap(), vec!["ETHUSDT < 2000".to_string()]);
+    });
+}
+
+#[test]
+fn test_alert_triggers_are_recorded_in_history() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let db_path = temp_db.path().to_str().unwrap();
+    let at = |seconds: i64| chrono::DateTime::from_timestamp(1640995200 + seconds, 0).unwrap();
+    let trigger = |alert_id, seconds, price| AlertTrigger {
+        alert_id,
+        symbol: "BTCUSDT".to_string(),
+        price,
+        message: "BTC above $50000".to_string(),
+        triggered_at: at(seconds),
+    };
+
+    block_on(async {
+        let db = Database::new(db_path).await.unwrap();
+        for id in [1, 2] {
+            let alert = PriceAlert {
+                id,
+                symbol: "BTCUSDT".to_string(),
+                condition: AlertCondition::PriceAbove(dec!(50000)),
+                enabled: true,
+                created_at: at(0),
+                last_triggered: None,
+                trigger_count: 0,
+                message: None,
+                cooldown_minutes: 60,
+                mode: AlertMode::Recurring,
+                armed: None,
+                sinks: Vec::new(),
+            };
+            db.save_alert(&alert).await.unwrap();
+        }
+
+        db.record_alert_trigger(&trigger(1, 60, dec!(50000.01))).await.unwrap();
+        db.record_alert_trigger(&trigger(1, 3660, dec!(50250.5))).await.unwrap();
+        db.record_alert_trigger(&trigger(2, 120, dec!(50010))).await.unwrap();
+
+        let history = db.get_alert_history(1, 10).await.unwrap();
+        assert_eq!(history, vec![trigger(1, 3660, dec!(50250.5)), trigger(1, 60, dec!(50000.01))]);
+        assert_eq!(db.get_alert_history(1, 1).await.unwrap().len(), 1);
+
+        let alerts = db.get_alerts().await.unwrap();
+        assert_eq!(alerts[0].trigger_count, 2);
+        assert_eq!(alerts[0].last_triggered, Some(at(3660)));
+
+        // Deleting an alert drops its history too
+        db.delete_alert(1).await.unwrap();
+        assert!(db.get_alert_history(1, 10).await.unwrap().is_empty());
+        assert_eq!(db.get_alert_history(2, 10).await.unwrap().len(), 1);
+    });
+}
+
+#[test]
+fn test_one_shot_alert_is_disabled_when_triggered() {
+    let temp_db = NamedTempFile::new().unwrap();
+    let db_path = temp_db.path().to_str().unwrap();
+    let at = chrono::DateTime::from_timestamp(1640995200, 0).unwrap();
+
+    block_on(async {
+        let db = Database::new(db_path).await.unwrap();
+        let alert = PriceAlert {
+            id: 1,
+            symbol: "BTCUSDT".to_string(),
+            condition: AlertCondition::CrossesAbove { level: dec!(65000), hysteresis_percent: 1.5 },
+            enabled: true,
+            created_at: at,
+            last_triggered: None,
+            trigger_count: 0,
+            message: None,
+            cooldown_minutes: 15,
+            mode: AlertMode::Once,
+            armed: Some(true),
+            sinks: Vec::new(),
+        };
+        db.save_alert(&alert).await.unwrap();
+
+        let saved = db.get_alerts().await.unwrap();
+        assert_eq!(saved[0].condition, alert.condition);
+        assert_eq!(saved[0].cooldown_minutes, 15);
+        assert_eq!(saved[0].mode, AlertMode::Once);
+        // Crossing state is worked out again from live prices
+        assert_eq!(saved[0].armed, None);
+
+        let trigger = AlertTrigger {
+            alert_id: 1,
+            symbol: "BTCUSDT".to_string(),
+            price: dec!(65010),
+            message: "BTCUSDT crossed above $65000".to_string(),
+            triggered_at: at,
+        };
+        db.record_alert_trigger(&trigger).await.unwrap();
+        assert!(!db.get_alerts().await.unwrap()[0].enabled);
+    });
+}
@@ -0,0 +1,344 @@
+use std::cell::Cell;
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use tokio_test::block_on;
+
+use coinpeek::app::App;
+use coinpeek::binance::{Candle, PriceInfo};
+use coinpeek::config::Config;
+use coinpeek::app::{ErrorSeverity, ErrorType, error_severity, error_type};
+use coinpeek::error::{CoinPeekError, SymbolResult};
+use coinpeek::database::Database;
+use coinpeek::exchange::MarketDataSource;
+use coinpeek::sync::{backfill_candles, fill_candle_gaps, missing_ranges};
+use coinpeek::timeframe::TimeFrame;
+
+/// In-memory market data source used to drive the app without network access
+struct FakeSource {
+    fail: bool,
+    calls: Cell<u32>,
+    throttled_until: Option<DateTime<Utc>>,
+    delisted: Option<&'static str>,
+    price: Decimal,
+    rate_limit_next_page: Cell<bool>,
+}
+
+impl FakeSource {
+    fn new(fail: bool) -> Self {
+        Self { fail, calls: Cell::new(0), throttled_until: None, delisted: None, price: dec!(100), rate_limit_next_page: Cell::new(false) }
+    }
+
+    fn price_info(symbol: &str, price: Decimal) -> PriceInfo {
+        PriceInfo {
+            symbol: symbol.to_string(),
+            price,
+            price_change_percent: 1.0,
+            volume: 100.0,
+            high_24h: price * dec!(1.1),
+            low_24h: price * dec!(0.9),
+            prev_close_price: price,
+            ..Default::default()
+        }
+    }
+}
+
+impl MarketDataSource for FakeSource {
+    fn name(&self) -> &'static str {
+        "fake"
+    }
+
+    async fn fetch_price(&self, _symbol: &str) -> Result<Decimal, CoinPeekError> {
+        Ok(dec!(100))
+    }
+
+    async fn fetch_24hr_stats(&self, symbol: &str) -> Result<PriceInfo, CoinPeekError> {
+        Ok(Self::price_info(symbol, dec!(100)))
+    }
+
+    async fn fetch_price_infos(&self, symbols: &[&str]) -> Result<Vec<SymbolResult<PriceInfo>>, CoinPeekError> {
+        self.calls.set(self.calls.get() + 1);
+        if self.fail {
+            return Err(CoinPeekError::Network("exchange unavailable".to_string()));
+        }
+        Ok(symbols
+            .iter()
+            .map(|&s| SymbolResult {
+                symbol: s.to_string(),
+                result: match self.delisted {
+                    Some(delisted) if delisted == s => Err(CoinPeekError::InvalidSymbol {
+                        symbol: s.to_string(),
+                        reason: "Invalid symbol.".to_string(),
+                    }),
+                    _ => Ok(Self::price_info(s, self.price)),
+                },
+            })
+            .collect())
+    }
+
+    async fn fetch_candles(&self, _symbol: &str, _interval: &str, limit: u8) -> Result<Vec<Candle>, CoinPeekError> {
+        Ok((0..limit as u64)
+            .map(|i| Candle {
+                open: dec!(100.0),
+                high: dec!(101.0),
+                low: dec!(99.0),
+                close: dec!(100.5),
+                volume: 10.0,
+                timestamp: 1640995200000 + i * 300_000,
+            })
+            .collect())
+    }
+
+    /// One-minute candles, one per minute of the requested range
+    async fn fetch_candle_page(&self, _symbol: &str, _interval: &str, start_ms: u64, end_ms: u64, limit: u16) -> Result<Vec<Candle>, CoinPeekError> {
+        if self.rate_limit_next_page.replace(false) {
+            return Err(CoinPeekError::RateLimited { reason: "HTTP 429".to_string(), retry_at: Utc::now() });
+        }
+        let first = start_ms.div_ceil(60_000) * 60_000;
+        Ok((first..=end_ms)
+            .step_by(60_000)
+            .take(limit as usize)
+            .map(|timestamp| Candle {
+                open: dec!(100),
+                high: dec!(101),
+                low: dec!(99),
+                close: dec!(100),
+                volume: 1.0,
+                timestamp,
+            })
+            .collect())
+    }
+
+    fn throttled_until(&self) -> Option<DateTime<Utc>> {
+        self.throttled_until
+    }
+}
+
+fn test_config() -> Config {
+    Config {
+        symbols: vec!["BTCUSDT".to_string(), "ETHUSDT".to_string()],
+        refresh_interval_seconds: 30,
+        ..Config::default()
+    }
+}
+
+#[test]
+fn test_refresh_prices_from_source() {
+    let source = FakeSource::new(false);
+    let mut app = App::new(test_config());
+
+    let fetched = block_on(app.refresh_prices(&source)).unwrap();
+
+    assert_eq!(source.calls.get(), 1);
+    assert_eq!(fetched.len(), 2);
+    assert_eq!(app.all_price_infos.len(), 2);
+    assert_eq!(app.price_infos[0].symbol, "BTCUSDT");
+    assert_eq!(app.data_status.consecutive_failures, 0);
+    assert!(app.data_status.last_successful_sync.is_some());
+}
+
+#[test]
+fn test_refresh_prices_failure_records_sync_failure() {
+    let source = FakeSource::new(true);
+    let mut app = App::new(test_config());
+
+    for _ in 0..3 {
+        assert!(block_on(app.refresh_prices(&source)).is_err());
+    }
+
+    assert!(app.all_price_infos.is_empty());
+    assert_eq!(app.data_status.consecutive_failures, 3);
+    assert!(app.data_status.offline_mode, "Three failures should switch to offline mode");
+}
+
+#[test]
+fn test_source_candles_and_default_stream() {
+    let source = FakeSource::new(false);
+
+    let candles = block_on(source.fetch_candles("BTCUSDT", "5m", 5)).unwrap();
+    assert_eq!(candles.len(), 5);
+    assert_eq!(candles[1].timestamp - candles[0].timestamp, 300_000);
+
+    // Sources without streaming support report an error instead of a dead stream
+    let stream = source.subscribe_tickers(&["BTCUSDT".to_string()]);
+    assert!(matches!(stream, Err(CoinPeekError::Unsupported(_))));
+    assert!(matches!(block_on(source.fetch_symbols()), Err(CoinPeekError::Unsupported(_))));
+}
+
+#[test]
+fn test_throttled_refresh_is_not_counted_as_offline() {
+    let source = FakeSource {
+        throttled_until: Some(Utc::now() + Duration::seconds(30)),
+        ..FakeSource::new(true)
+    };
+    let mut app = App::new(test_config());
+
+    for _ in 0..3 {
+        assert!(block_on(app.refresh_prices(&source)).is_err());
+    }
+
+    assert!(app.is_throttled());
+    assert_eq!(app.data_status.consecutive_failures, 0);
+    assert!(!app.data_status.offline_mode, "Rate limits shouldn't flip the app offline");
+    assert!(app.get_offline_indicator().starts_with("🟡 throttled"));
+    assert_eq!(app.errors.len(), 1, "Throttling should be reported once, not per refresh");
+}
+
+#[test]
+fn test_partial_failure_keeps_last_known_price() {
+    let mut app = App::new(test_config());
+    block_on(app.refresh_prices(&FakeSource::new(false))).unwrap();
+
+    let source = FakeSource {
+        delisted: Some("ETHUSDT"),
+        price: dec!(200),
+        ..FakeSource::new(false)
+    };
+    for _ in 0..2 {
+        let fetched = block_on(app.refresh_prices(&source)).unwrap();
+        assert_eq!(fetched.len(), 1);
+        assert_eq!(fetched[0].symbol, "BTCUSDT");
+    }
+
+    let eth = app.all_price_infos.iter().find(|p| p.symbol == "ETHUSDT").unwrap();
+    assert_eq!(eth.price, dec!(100), "Failed symbols keep their last price instead of zero");
+    let btc = app.all_price_infos.iter().find(|p| p.symbol == "BTCUSDT").unwrap();
+    assert_eq!(btc.price, dec!(200));
+
+    // One error entry for the bad symbol, bumped on repeat rather than duplicated
+    assert_eq!(app.errors.len(), 1);
+    assert_eq!(app.errors[0].message, "Failed to fetch ETHUSDT");
+    assert_eq!(app.errors[0].error_type, ErrorType::Validation);
+    assert_eq!(app.errors[0].retry_count, 1);
+    assert_eq!(app.data_status.consecutive_failures, 0);
+}
+
+#[test]
+fn test_error_type_and_severity_mapping() {
+    let cases = [
+        (CoinPeekError::Network("timeout".to_string()), ErrorType::Network),
+        (CoinPeekError::HttpStatus { status: 503, message: String::new() }, ErrorType::Api),
+        (CoinPeekError::Decode("bad number".to_string()), ErrorType::Validation),
+        (
+            CoinPeekError::InvalidSymbol { symbol: "FOOUSDT".to_string(), reason: "Invalid symbol.".to_string() },
+            ErrorType::Validation,
+        ),
+        (CoinPeekError::RateLimited { reason: "HTTP 429".to_string(), retry_at: Utc::now() }, ErrorType::Api),
+        (CoinPeekError::Database("disk full".to_string()), ErrorType::Database),
+        (CoinPeekError::Io("permission denied".to_string()), ErrorType::Database),
+        (CoinPeekError::Unsupported("no live ticker streams".to_string()), ErrorType::Api),
+    ];
+
+    for (error, expected) in cases {
+        assert_eq!(error_type(&error), expected, "{}", error);
+        assert!(!error.recovery_suggestion().is_empty());
+    }
+
+    assert!(matches!(error_severity(&CoinPeekError::Database("locked".to_string())), ErrorSeverity::Critical));
+    assert!(matches!(error_severity(&CoinPeekError::Network("reset".to_string())), ErrorSeverity::Warning));
+    assert_eq!(
+        CoinPeekError::HttpStatus { status: 400, message: "Invalid symbol.".to_string() }.to_string(),
+        "HTTP 400: Invalid symbol."
+    );
+}
+
+#[test]
+fn test_backfill_stores_every_page() {
+    let temp_db = tempfile::NamedTempFile::new().unwrap();
+    let source = FakeSource::new(false);
+    // The first page is rate limited; the backfill waits and retries it
+    source.rate_limit_next_page.set(true);
+
+    let start = DateTime::from_timestamp(1_640_995_200, 0).unwrap();
+    let end = start + Duration::minutes(2_499);
+
+    block_on(async {
+        let db = Database::new(temp_db.path().to_str().unwrap()).await.unwrap();
+        let stored = backfill_candles(&source, &db, "BTCUSDT", TimeFrame::M1, start, end).await.unwrap();
+        assert_eq!(stored, 2_500);
+
+        let candles = db.get_candles("BTCUSDT", "1m", 5_000).await.unwrap();
+        assert_eq!(candles.len(), 2_500);
+    });
+}
+
+#[test]
+fn test_gap_fill_fetches_only_missing_candles() {
+    let temp_db = tempfile::NamedTempFile::new().unwrap();
+    let source = FakeSource::new(false);
+    let start = DateTime::from_timestamp(1_640_995_200, 0).unwrap();
+    // Half a minute into the 101st candle
+    let now = start + Duration::minutes(100) + Duration::seconds(30);
+
+    block_on(async {
+        let db = Database::new(temp_db.path().to_str().unwrap()).await.unwrap();
+        let stored = backfill_candles(&source, &db, "BTCUSDT", TimeFrame::M1, start, start + Duration::minutes(59)).await.unwrap();
+        assert_eq!(stored, 60);
+
+        // The first hour is already cached; only minutes 60..=100 are missing
+        let stored = fill_candle_gaps(&source, &db, "BTCUSDT", TimeFrame::M1, 100, now).await.unwrap();
+        assert_eq!(stored, 41);
+
+        // Nothing closed is missing now, but the open candle is fetched again
+        let stored = fill_candle_gaps(&source, &db, "BTCUSDT", TimeFrame::M1, 100, now).await.unwrap();
+        assert_eq!(stored, 1);
+
+        let start_ms = start.timestamp_millis() as u64;
+        assert_eq!(db.get_candle_coverage("BTCUSDT", "1m").await.unwrap(), vec![(start_ms, start_ms + 100 * 60_000 - 1)]);
+        assert_eq!(db.get_candles("BTCUSDT", "1m", 500).await.unwrap().len(), 101);
+    });
+}
+
+#[test]
+fn test_gap_fill_rolls_up_stored_lower_timeframes() {
+    let temp_db = tempfile::NamedTempFile::new().unwrap();
+    let source = FakeSource::new(false);
+    let start = DateTime::from_timestamp(1_640_995_200, 0).unwrap();
+    let start_ms = start.timestamp_millis() as u64;
+    // Half a minute into the fourth hour
+    let now = start + Duration::hours(3) + Duration::seconds(30);
+
+    block_on(async {
+        let db = Database::new(temp_db.path().to_str().unwrap()).await.unwrap();
+        backfill_candles(&source, &db, "BTCUSDT", TimeFrame::M1, start, start + Duration::minutes(179)).await.unwrap();
+        // An hourly bar stored while it was still open, never refreshed
+        let stale = Candle { open: dec!(1), high: dec!(1), low: dec!(1), close: dec!(1), volume: 1.0, timestamp: start_ms + 3_600_000 };
+        db.store_candles("BTCUSDT", "1h", &[stale]).await.unwrap();
+
+        // The three closed hours come from the minutes; only the open one is fetched
+        let stored = fill_candle_gaps(&source, &db, "BTCUSDT", TimeFrame::H1, 4, now).await.unwrap();
+        assert_eq!(stored, 1);
+
+        let candles = db.get_candles_with_rollup("BTCUSDT", TimeFrame::H1, 4).await.unwrap();
+        let timestamps: Vec<u64> = candles.iter().map(|c| c.timestamp).collect();
+        assert_eq!(timestamps, (0..4).map(|h| start_ms + h * 3_600_000).collect::<Vec<_>>());
+        assert_eq!(candles[1].volume, 60.0);
+        assert_eq!(candles[1].high, dec!(101));
+    });
+}
+
+#[test]
+fn test_gap_fill_reports_rate_limits_instead_of_waiting() {
+    let temp_db = tempfile::NamedTempFile::new().unwrap();
+    let source = FakeSource::new(false);
+    source.rate_limit_next_page.set(true);
+    let now = DateTime::from_timestamp(1_640_995_200, 0).unwrap();
+
+    block_on(async {
+        let db = Database::new(temp_db.path().to_str().unwrap()).await.unwrap();
+        let result = fill_candle_gaps(&source, &db, "BTCUSDT", TimeFrame::M1, 10, now).await;
+        assert!(matches!(result, Err(CoinPeekError::RateLimited { .. })));
+        assert!(db.get_candle_coverage("BTCUSDT", "1m").await.unwrap().is_empty());
+    });
+}
+
+#[test]
+fn test_missing_ranges() {
+    assert_eq!(missing_ranges(&[], 10, 20), vec![(10, 20)]);
+    assert_eq!(missing_ranges(&[(0, 30)], 10, 20), vec![]);
+    assert_eq!(missing_ranges(&[(0, 12), (15, 16), (25, 40)], 10, 30), vec![(13, 14), (17, 24)]);
+    assert_eq!(missing_ranges(&[(12, 14)], 10, 20), vec![(10, 11), (15, 20)]);
+    assert_eq!(missing_ranges(&[(0, 5), (30, 40)], 10, 20), vec![(10, 20)]);
+}
@@ -0,0 +1,132 @@
+use chrono::{DateTime, Utc};
+use rust_decimal_macros::dec;
+use tempfile::NamedTempFile;
+use tokio_test::block_on;
+
+use coinpeek::binance::Candle;
+use coinpeek::database::Database;
+use coinpeek::export::{export, ExportData, ExportFormat, ExportRequest};
+use coinpeek::timeframe::TimeFrame;
+
+fn args(line: &str) -> Vec<String> {
+    line.split_whitespace().map(str::to_string).collect()
+}
+
+fn at(seconds: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp(1_640_995_200 + seconds, 0).unwrap()
+}
+
+/// A database with two 5m candles, one 1h candle and two price refreshes for BTCUSDT
+fn sample_database(file: &NamedTempFile) -> Database {
+    let path = file.path().to_str().unwrap();
+    block_on(async {
+        let db = Database::new(path).await.unwrap();
+        let candle = |timestamp: u64, close| Candle { open: dec!(100), high: dec!(101.5), low: dec!(99), close, volume: 2.5, timestamp };
+        db.store_candles("BTCUSDT", "5m", &[candle(1640995200000, dec!(100.25)), candle(1640995500000, dec!(101))]).await.unwrap();
+        db.store_candles("BTCUSDT", "1h", &[candle(1640995200000, dec!(100.5))]).await.unwrap();
+        db.store_candles("ETHUSDT", "5m", &[candle(1640995200000, dec!(4000))]).await.unwrap();
+    });
+
+    let conn = rusqlite::Connection::open(path).unwrap();
+    for (seconds, price) in [(0, "50000.5"), (3, "50001")] {
+        conn.execute(
+            "INSERT INTO prices (symbol, price, timestamp) VALUES ('BTCUSDT', ?, ?)",
+            rusqlite::params![price, 1640995200 + seconds],
+        )
+        .unwrap();
+    }
+
+    block_on(Database::new(path)).unwrap()
+}
+
+fn export_to_string(db: &Database, request: &ExportRequest) -> (usize, String) {
+    let mut out = Vec::new();
+    let written = block_on(export(db, request, &mut out)).unwrap();
+    (written, String::from_utf8(out).unwrap())
+}
+
+#[test]
+fn test_export_args() {
+    let now = at(86_400 * 30);
+    let (request, output) = ExportRequest::from_args(
+        &args("candles btcusdt,ETHUSDT --timeframes 1m,1h --from 2022-01-01 --to 2022-01-02 --format jsonl --output out.jsonl"),
+        now,
+    )
+    .unwrap();
+    assert_eq!(request.data, ExportData::Candles(vec![TimeFrame::M1, TimeFrame::H1]));
+    assert_eq!(request.symbols, vec!["BTCUSDT", "ETHUSDT"]);
+    assert_eq!(request.start, at(0));
+    assert_eq!(request.end, at(2 * 86_400) - chrono::Duration::milliseconds(1));
+    assert_eq!(request.format, ExportFormat::JsonLines);
+    assert_eq!(output.as_deref(), Some("out.jsonl"));
+
+    let (request, output) = ExportRequest::from_args(&args("prices BTCUSDT"), now).unwrap();
+    assert_eq!(request.data, ExportData::Prices);
+    assert_eq!(request.end, now);
+    assert_eq!(request.format, ExportFormat::Csv);
+    assert!(output.is_none());
+
+    let (request, _) = ExportRequest::from_args(&args("downsampled BTCUSDT --timeframes 1m"), now).unwrap();
+    assert_eq!(request.data, ExportData::Downsampled(vec![TimeFrame::M1]));
+
+    assert!(ExportRequest::from_args(&args("prices"), now).is_err());
+    assert!(ExportRequest::from_args(&args("trades BTCUSDT"), now).is_err());
+    assert!(ExportRequest::from_args(&args("candles BTCUSDT --timeframes 2m"), now).is_err());
+    assert!(ExportRequest::from_args(&args("candles BTCUSDT --from yesterday"), now).is_err());
+    assert!(ExportRequest::from_args(&args("candles BTCUSDT --format"), now).is_err());
+}
+
+#[test]
+fn test_export_candles_as_csv() {
+    let file = NamedTempFile::new().unwrap();
+    let db = sample_database(&file);
+    let request = ExportRequest {
+        data: ExportData::Candles(vec![TimeFrame::M5, TimeFrame::H1]),
+        symbols: vec!["BTCUSDT".to_string()],
+        start: at(0),
+        end: at(3600),
+        format: ExportFormat::Csv,
+    };
+
+    let (written, csv) = export_to_string(&db, &request);
+    assert_eq!(written, 3);
+    assert_eq!(
+        csv,
+        "symbol,timeframe,timestamp,open,high,low,close,volume\n\
+         BTCUSDT,5m,1640995200000,100,101.5,99,100.25,2.5\n\
+         BTCUSDT,5m,1640995500000,100,101.5,99,101,2.5\n\
+         BTCUSDT,1h,1640995200000,100,101.5,99,100.5,2.5\n"
+    );
+
+    // The window is inclusive and limits what is written
+    let request = ExportRequest { end: at(299), ..request };
+    assert_eq!(export_to_string(&db, &request).0, 2);
+}
+
+#[test]
+fn test_export_prices_as_json_lines_and_columns() {
+    let file = NamedTempFile::new().unwrap();
+    let db = sample_database(&file);
+    let request = ExportRequest {
+        data: ExportData::Prices,
+        symbols: vec!["BTCUSDT".to_string(), "ETHUSDT".to_string()],
+        start: at(0),
+        end: at(60),
+        format: ExportFormat::JsonLines,
+    };
+
+    let (written, jsonl) = export_to_string(&db, &request);
+    assert_eq!(written, 2);
+    assert_eq!(
+        jsonl,
+        "{\"symbol\":\"BTCUSDT\",\"timestamp\":1640995200000,\"price\":50000.5}\n\
+         {\"symbol\":\"BTCUSDT\",\"timestamp\":1640995203000,\"price\":50001}\n"
+    );
+
+    let request = ExportRequest { format: ExportFormat::Columnar, ..request };
+    let (_, columns) = export_to_string(&db, &request);
+    let parsed: serde_json::Value = serde_json::from_str(&columns).unwrap();
+    assert_eq!(parsed["symbol"], serde_json::json!(["BTCUSDT", "BTCUSDT"]));
+    assert_eq!(parsed["timestamp"], serde_json::json!([1640995200000u64, 1640995203000u64]));
+    assert_eq!(parsed["price"], serde_json::json!([50000.5, 50001]));
+}
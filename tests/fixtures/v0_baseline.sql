@@ -0,0 +1,43 @@
+-- Schema written by the first release (REAL prices, no bid/ask, no versioning)
+CREATE TABLE prices (
+    id INTEGER PRIMARY KEY,
+    symbol TEXT NOT NULL,
+    price REAL NOT NULL,
+    price_change_percent REAL,
+    volume REAL,
+    high_24h REAL,
+    low_24h REAL,
+    prev_close_price REAL,
+    timestamp INTEGER NOT NULL,
+    exchange TEXT DEFAULT 'binance',
+    created_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+CREATE TABLE candles (
+    id INTEGER PRIMARY KEY,
+    symbol TEXT NOT NULL,
+    timeframe TEXT NOT NULL,
+    open REAL NOT NULL,
+    high REAL NOT NULL,
+    low REAL NOT NULL,
+    close REAL NOT NULL,
+    volume REAL,
+    timestamp INTEGER NOT NULL,
+    exchange TEXT DEFAULT 'binance',
+    created_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+CREATE TABLE sync_metadata (
+    key TEXT PRIMARY KEY,
+    value TEXT,
+    updated_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+CREATE INDEX idx_prices_symbol_timestamp ON prices(symbol, timestamp);
+CREATE INDEX idx_candles_symbol_timeframe_timestamp ON candles(symbol, timeframe, timestamp);
+CREATE INDEX idx_prices_timestamp ON prices(timestamp);
+
+INSERT INTO prices (symbol, price, price_change_percent, volume, high_24h, low_24h, prev_close_price, timestamp)
+VALUES ('BTCUSDT', 50000.5, 2.5, 1000.0, 51000.0, 49000.0, 48780.0, 1640995200);
+INSERT INTO candles (symbol, timeframe, open, high, low, close, volume, timestamp) VALUES
+    ('BTCUSDT', '5m', 50000.0, 50100.0, 49900.0, 50050.0, 1.0, 1640995200000),
+    ('BTCUSDT', '5m', 50000.0, 50200.0, 49900.0, 50150.0, 2.0, 1640995200000),
+    ('BTCUSDT', '5m', 50150.0, 50300.0, 50100.0, 50250.0, 1.5, 1640995500000);
+INSERT INTO sync_metadata (key, value) VALUES ('last_sync', '1640995200');
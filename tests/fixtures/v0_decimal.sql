@@ -0,0 +1,46 @@
+-- Schema after exact decimal prices: price columns TEXT, still unversioned
+CREATE TABLE prices (
+    id INTEGER PRIMARY KEY,
+    symbol TEXT NOT NULL,
+    price TEXT NOT NULL,
+    price_change_percent REAL,
+    volume REAL,
+    high_24h TEXT,
+    low_24h TEXT,
+    prev_close_price TEXT,
+    bid_price TEXT,
+    ask_price TEXT,
+    quote_volume REAL,
+    timestamp INTEGER NOT NULL,
+    exchange TEXT DEFAULT 'binance',
+    created_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+CREATE TABLE candles (
+    id INTEGER PRIMARY KEY,
+    symbol TEXT NOT NULL,
+    timeframe TEXT NOT NULL,
+    open TEXT NOT NULL,
+    high TEXT NOT NULL,
+    low TEXT NOT NULL,
+    close TEXT NOT NULL,
+    volume REAL,
+    timestamp INTEGER NOT NULL,
+    exchange TEXT DEFAULT 'binance',
+    created_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+CREATE TABLE sync_metadata (
+    key TEXT PRIMARY KEY,
+    value TEXT,
+    updated_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+CREATE INDEX idx_prices_symbol_timestamp ON prices(symbol, timestamp);
+CREATE INDEX idx_candles_symbol_timeframe_timestamp ON candles(symbol, timeframe, timestamp);
+CREATE INDEX idx_prices_timestamp ON prices(timestamp);
+
+INSERT INTO prices (symbol, price, price_change_percent, volume, high_24h, low_24h, prev_close_price, bid_price, ask_price, quote_volume, timestamp)
+VALUES ('BTCUSDT', '50000.5', 2.5, 1000.0, '51000', '49000', '48780', '50000.25', '50000.75', 50000000.0, 1640995200);
+INSERT INTO candles (symbol, timeframe, open, high, low, close, volume, timestamp) VALUES
+    ('BTCUSDT', '5m', '50000', '50100', '49900', '50050', 1.0, 1640995200000),
+    ('BTCUSDT', '5m', '50000', '50200', '49900', '50150', 2.0, 1640995200000),
+    ('BTCUSDT', '5m', '50150', '50300', '50100', '50250', 1.5, 1640995500000);
+INSERT INTO sync_metadata (key, value) VALUES ('last_sync', '1640995200');
@@ -0,0 +1,61 @@
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal_macros::dec;
+
+use coinpeek::history::{align_to_grid, price_stats, time_grid, window_range, PricePoint};
+
+fn at(seconds: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp(1_640_995_200 + seconds, 0).unwrap()
+}
+
+#[test]
+fn test_price_stats() {
+    let points = [
+        PricePoint { timestamp: at(0), price: dec!(100) },
+        PricePoint { timestamp: at(3), price: dec!(90) },
+        PricePoint { timestamp: at(6), price: dec!(120) },
+        PricePoint { timestamp: at(9), price: dec!(110) },
+    ];
+    let stats = price_stats("BTCUSDT", &points).unwrap();
+    assert_eq!(stats.samples, 4);
+    assert_eq!(stats.first.price, dec!(100));
+    assert_eq!(stats.last.timestamp, at(9));
+    assert_eq!(stats.min, dec!(90));
+    assert_eq!(stats.max, dec!(120));
+    assert_eq!(stats.average, dec!(105));
+    assert!((stats.return_percent - 10.0).abs() < 1e-9);
+
+    assert!(price_stats("BTCUSDT", &[]).is_none());
+}
+
+#[test]
+fn test_align_to_grid_carries_prices_forward() {
+    let grid = time_grid(at(0), at(40), Duration::seconds(10));
+    assert_eq!(grid, vec![at(0), at(10), at(20), at(30), at(40)]);
+
+    let points = [
+        PricePoint { timestamp: at(5), price: dec!(1) },
+        PricePoint { timestamp: at(10), price: dec!(2) },
+        PricePoint { timestamp: at(12), price: dec!(3) },
+        PricePoint { timestamp: at(35), price: dec!(4) },
+    ];
+    assert_eq!(
+        align_to_grid(&points, &grid),
+        vec![None, Some(dec!(2)), Some(dec!(3)), Some(dec!(3)), Some(dec!(4))]
+    );
+
+    assert!(time_grid(at(0), at(10), Duration::zero()).is_empty());
+}
+
+#[test]
+fn test_window_range_counts_price_in_effect_at_start() {
+    let points = [
+        PricePoint { timestamp: at(0), price: dec!(50) },
+        PricePoint { timestamp: at(5), price: dec!(100) },
+        PricePoint { timestamp: at(12), price: dec!(90) },
+        PricePoint { timestamp: at(18), price: dec!(120) },
+    ];
+    assert_eq!(window_range(&points, at(10)), Some((dec!(90), dec!(120))));
+    assert_eq!(window_range(&points, at(20)), Some((dec!(120), dec!(120))));
+    assert_eq!(window_range(&points, at(-5)), Some((dec!(50), dec!(120))));
+    assert_eq!(window_range(&[], at(0)), None);
+}
@@ -0,0 +1,202 @@
+use std::io::Write;
+use std::path::Path;
+
+use rust_decimal_macros::dec;
+use tempfile::{NamedTempFile, TempDir};
+use tokio_test::block_on;
+
+use coinpeek::database::Database;
+use coinpeek::import::{binance_file_info, import_file, parse_candles, ColumnMapping, CsvLayout, ImportProgress};
+use coinpeek::timeframe::TimeFrame;
+
+const BINANCE_ROWS: &str = "\
+1640995200000,46216.93,46271.08,46208.37,46250.00,40.57574,1640995259999,1876475.37,1213,19.47,900544.99,0
+1640995260000,46250.00,46344.23,46234.39,46312.76,42.38106,1640995319999,1962620.93,1344,21.42,991937.20,0
+";
+
+#[test]
+fn test_parse_binance_klines() {
+    let parsed = parse_candles(BINANCE_ROWS.as_bytes(), &CsvLayout::BinanceKlines, TimeFrame::M1).unwrap();
+    assert!(parsed.errors.is_empty());
+    assert_eq!(parsed.candles.len(), 2);
+    assert_eq!(parsed.candles[0].timestamp, 1640995200000);
+    assert_eq!(parsed.candles[0].open, dec!(46216.93));
+    assert_eq!(parsed.candles[1].close, dec!(46312.76));
+    assert_eq!(parsed.candles[1].volume, 42.38106);
+}
+
+#[test]
+fn test_parse_binance_klines_with_header_and_microseconds() {
+    let csv = "open_time,open,high,low,close,volume,close_time,quote_volume,count,taker_buy_volume,taker_buy_quote_volume,ignore\n\
+        1735689600000000,93576.00,93610.93,93537.50,93610.93,8.21827,1735689659999999,768968.31,1386,3.95,369695.28,0\n";
+    let parsed = parse_candles(csv.as_bytes(), &CsvLayout::BinanceKlines, TimeFrame::M1).unwrap();
+    assert!(parsed.errors.is_empty());
+    assert_eq!(parsed.candles.len(), 1);
+    assert_eq!(parsed.candles[0].timestamp, 1735689600000);
+}
+
+#[test]
+fn test_parse_mapped_csv() {
+    let csv = "Date,Open,High,Low,Close,Adj Close\n\
+        2022-01-01,46216.93,47954.63,46208.37,47722.65,47722.65\n\
+        \"2022-01-02T00:00:00Z\",47722.66,47990.00,46654.00,47286.18,47286.18\n";
+    let mapping = ColumnMapping::parse("timestamp=Date,open=Open,high=High,low=Low,close=Adj Close,volume=").unwrap();
+    let parsed = parse_candles(csv.as_bytes(), &CsvLayout::Mapped(mapping), TimeFrame::D1).unwrap();
+    assert!(parsed.errors.is_empty());
+    assert_eq!(parsed.candles.len(), 2);
+    assert_eq!(parsed.candles[0].timestamp, 1640995200000);
+    assert_eq!(parsed.candles[1].timestamp, 1641081600000);
+    assert_eq!(parsed.candles[1].close, dec!(47286.18));
+    assert_eq!(parsed.candles[1].volume, 0.0);
+}
+
+#[test]
+fn test_parse_mapped_csv_missing_column() {
+    let csv = "time,open,high,low,close\n1640995200,1,1,1,1\n";
+    let result = parse_candles(csv.as_bytes(), &CsvLayout::Mapped(ColumnMapping::default()), TimeFrame::M1);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_parse_reports_invalid_rows() {
+    let csv = "\
+1640995200000,100,101,99,100.5,1
+1640995230000,100,101,99,100.5,1
+1640995320000,100,99,101,100.5,1
+1640995380000,100,101,99,102,1
+1640995440000,100,101,99,100.5,-1
+1640995500000,abc,101,99,100.5,1
+1640995560000,0,0,0,0,0
+";
+    let parsed = parse_candles(csv.as_bytes(), &CsvLayout::BinanceKlines, TimeFrame::M1).unwrap();
+    assert_eq!(parsed.candles.len(), 1);
+    let lines: Vec<usize> = parsed.errors.iter().map(|e| e.line).collect();
+    assert_eq!(lines, vec![2, 3, 4, 5, 6, 7]);
+    assert!(parsed.errors[0].reason.contains("boundary"));
+    assert!(parsed.errors[4].reason.contains("invalid open"));
+}
+
+#[test]
+fn test_parse_keeps_last_duplicate_and_sorts() {
+    let csv = "\
+1640995260000,100,101,99,100,1
+1640995200000,100,101,99,100,1
+1640995260000,100,102,99,101,2
+";
+    let parsed = parse_candles(csv.as_bytes(), &CsvLayout::BinanceKlines, TimeFrame::M1).unwrap();
+    assert_eq!(parsed.candles.len(), 2);
+    assert_eq!(parsed.candles[0].timestamp, 1640995200000);
+    assert_eq!(parsed.candles[1].close, dec!(101));
+    assert_eq!(parsed.candles[1].volume, 2.0);
+}
+
+#[test]
+fn test_column_mapping_parse() {
+    let mapping = ColumnMapping::parse("timestamp=Date, close = Close").unwrap();
+    assert_eq!(mapping.timestamp, "Date");
+    assert_eq!(mapping.close, "Close");
+    assert_eq!(mapping.open, "open");
+    assert_eq!(mapping.volume.as_deref(), Some("volume"));
+
+    assert!(ColumnMapping::parse("timestamp").is_err());
+    assert!(ColumnMapping::parse("price=Close").is_err());
+}
+
+#[test]
+fn test_binance_file_info() {
+    assert_eq!(
+        binance_file_info(Path::new("data/BTCUSDT-1m-2024-01.csv")),
+        Some(("BTCUSDT".to_string(), TimeFrame::M1))
+    );
+    assert_eq!(
+        binance_file_info(Path::new("ethusdt-1h-2024-01-15.csv")),
+        Some(("ETHUSDT".to_string(), TimeFrame::H1))
+    );
+    assert_eq!(binance_file_info(Path::new("prices.csv")), None);
+    assert_eq!(binance_file_info(Path::new("BTCUSDT-2m-2024-01.csv")), None);
+}
+
+#[test]
+fn test_import_file() {
+    let db_file = NamedTempFile::new().unwrap();
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("BTCUSDT-1m-2022-01-01.csv");
+    let mut csv = std::fs::File::create(&path).unwrap();
+    writeln!(csv, "{}1640995320000,1,1,2,1,1", BINANCE_ROWS).unwrap();
+
+    block_on(async {
+        let db = Database::new(db_file.path().to_str().unwrap()).await.unwrap();
+        let mut updates = Vec::new();
+        let mut progress = |p: &ImportProgress| updates.push(p.clone());
+        let report = import_file(&db, &path, "BTCUSDT", TimeFrame::M1, &CsvLayout::BinanceKlines, &mut progress).await.unwrap();
+
+        assert_eq!(report.stored, 2);
+        assert_eq!(report.skipped, 1);
+        assert_eq!(report.errors[0].line, 3);
+        assert_eq!(report.first, Some(1640995200000));
+        assert_eq!(report.last, Some(1640995260000));
+        assert_eq!(updates, vec![ImportProgress { stored: 2, total: 2 }]);
+
+        let candles = db.get_candles("BTCUSDT", "1m", 10).await.unwrap();
+        assert_eq!(candles.len(), 2);
+        let coverage = db.get_candle_coverage("BTCUSDT", "1m").await.unwrap();
+        assert_eq!(coverage, vec![(1640995200000, 1640995319999)]);
+
+        // Importing the same file again replaces the stored candles
+        let mut ignore = |_: &ImportProgress| {};
+        import_file(&db, &path, "BTCUSDT", TimeFrame::M1, &CsvLayout::BinanceKlines, &mut ignore).await.unwrap();
+        assert_eq!(db.get_candles("BTCUSDT", "1m", 10).await.unwrap().len(), 2);
+    });
+}
+
+#[test]
+fn test_import_skipped_row_is_not_covered() {
+    let db_file = NamedTempFile::new().unwrap();
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("BTCUSDT-1m-2022-01-01.csv");
+    let mut csv = std::fs::File::create(&path).unwrap();
+    // The middle bar's high is below its low
+    writeln!(csv, "1640995200000,1,2,1,1,1").unwrap();
+    writeln!(csv, "1640995260000,1,1,2,1,1").unwrap();
+    writeln!(csv, "1640995320000,1,2,1,1,1").unwrap();
+    writeln!(csv, "1640995380000,1,2,1,1,1").unwrap();
+
+    block_on(async {
+        let db = Database::new(db_file.path().to_str().unwrap()).await.unwrap();
+        let mut ignore = |_: &ImportProgress| {};
+        let report = import_file(&db, &path, "BTCUSDT", TimeFrame::M1, &CsvLayout::BinanceKlines, &mut ignore).await.unwrap();
+        assert_eq!(report.stored, 3);
+        assert_eq!(report.skipped, 1);
+
+        let coverage = db.get_candle_coverage("BTCUSDT", "1m").await.unwrap();
+        assert_eq!(coverage, vec![(1640995200000, 1640995259999), (1640995320000, 1640995439999)]);
+    });
+}
+
+#[test]
+fn test_import_mapped_file_records_no_coverage() {
+    let db_file = NamedTempFile::new().unwrap();
+    let mut csv = NamedTempFile::new().unwrap();
+    writeln!(csv, "timestamp,open,high,low,close,volume\n1640995200,100,101,99,100,5").unwrap();
+
+    block_on(async {
+        let db = Database::new(db_file.path().to_str().unwrap()).await.unwrap();
+        let layout = CsvLayout::Mapped(ColumnMapping::default());
+        let mut ignore = |_: &ImportProgress| {};
+        let report = import_file(&db, csv.path(), "ETHUSDT", TimeFrame::M5, &layout, &mut ignore).await.unwrap();
+        assert_eq!(report.stored, 1);
+        assert_eq!(db.get_candles("ETHUSDT", "5m", 10).await.unwrap()[0].volume, 5.0);
+        assert!(db.get_candle_coverage("ETHUSDT", "5m").await.unwrap().is_empty());
+    });
+}
+
+#[test]
+fn test_import_missing_file() {
+    let db_file = NamedTempFile::new().unwrap();
+    block_on(async {
+        let db = Database::new(db_file.path().to_str().unwrap()).await.unwrap();
+        let mut ignore = |_: &ImportProgress| {};
+        let result = import_file(&db, Path::new("/nonexistent/BTCUSDT-1m.csv"), "BTCUSDT", TimeFrame::M1, &CsvLayout::BinanceKlines, &mut ignore).await;
+        assert!(result.is_err());
+    });
+}
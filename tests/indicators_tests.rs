@@ -0,0 +1,66 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use coinpeek::binance::Candle;
+use coinpeek::indicators::{bollinger, rsi, sma, volume_ratio};
+
+/// Hourly candles closing at `closes`, oldest first
+fn closes(closes: &[Decimal]) -> Vec<Candle> {
+    closes
+        .iter()
+        .enumerate()
+        .map(|(i, &close)| Candle {
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: 1.0,
+            timestamp: i as u64 * 3_600_000,
+        })
+        .collect()
+}
+
+#[test]
+fn test_sma_of_last_closes() {
+    let candles = closes(&[dec!(1), dec!(2), dec!(3), dec!(4)]);
+    assert_eq!(sma(&candles, 2), Some(dec!(3.5)));
+    assert_eq!(sma(&candles, 4), Some(dec!(2.5)));
+    assert_eq!(sma(&candles, 5), None);
+    assert_eq!(sma(&candles, 0), None);
+}
+
+#[test]
+fn test_rsi_uses_wilder_smoothing() {
+    let rising = closes(&[dec!(1), dec!(2), dec!(3), dec!(4)]);
+    assert_eq!(rsi(&rising, 3), Some(100.0));
+    let flat = closes(&[dec!(5), dec!(5), dec!(5)]);
+    assert_eq!(rsi(&flat, 2), Some(50.0));
+
+    // Changes +1, -1, +1, -1: averages 0.5/0.5, then 0.75/0.25, then 0.375/0.625
+    let choppy = closes(&[dec!(10), dec!(11), dec!(10), dec!(11), dec!(10)]);
+    assert!((rsi(&choppy, 2).unwrap() - 37.5).abs() < 1e-9);
+    assert_eq!(rsi(&choppy[..2], 2), None);
+}
+
+#[test]
+fn test_bollinger_bands() {
+    let candles = closes(&[dec!(2), dec!(4), dec!(4), dec!(4), dec!(5), dec!(5), dec!(7), dec!(9)]);
+    let bands = bollinger(&candles, 8, 2.0).unwrap();
+    assert_eq!(bands.middle, dec!(5));
+    assert_eq!(bands.lower, dec!(1));
+    assert_eq!(bands.upper, dec!(9));
+    assert!(bollinger(&candles, 9, 2.0).is_none());
+}
+
+#[test]
+fn test_volume_ratio_against_earlier_candles() {
+    let mut candles = closes(&[dec!(1), dec!(1), dec!(1), dec!(1)]);
+    for (candle, volume) in candles.iter_mut().zip([10.0, 20.0, 30.0, 60.0]) {
+        candle.volume = volume;
+    }
+    assert_eq!(volume_ratio(&candles, 3), Some(3.0));
+    assert_eq!(volume_ratio(&candles, 4), None);
+
+    candles.iter_mut().for_each(|c| c.volume = 0.0);
+    assert_eq!(volume_ratio(&candles, 3), None);
+}
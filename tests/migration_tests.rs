@@ -0,0 +1,156 @@
+use rust_decimal_macros::dec;
+use tempfile::NamedTempFile;
+use tokio_test::block_on;
+
+use coinpeek::database::Database;
+use coinpeek::migrations::{schema_version, LATEST_VERSION};
+
+/// Databases as written by each release before schema versioning
+const FIXTURES: [(&str, &str); 5] = [
+    ("v0_baseline", include_str!("fixtures/v0_baseline.sql")),
+    ("v0_bid_ask", include_str!("fixtures/v0_bid_ask.sql")),
+    ("v0_decimal", include_str!("fixtures/v0_decimal.sql")),
+    ("v0_symbols", include_str!("fixtures/v0_symbols.sql")),
+    ("v0_unique", include_str!("fixtures/v0_unique.sql")),
+];
+
+fn fixture_database(sql: &str) -> NamedTempFile {
+    let file = NamedTempFile::new().unwrap();
+    let conn = rusqlite::Connection::open(file.path()).unwrap();
+    conn.execute_batch(sql).unwrap();
+    file
+}
+
+fn column_type(conn: &rusqlite::Connection, table: &str, column: &str) -> String {
+    conn.query_row(
+        &format!("SELECT type FROM pragma_table_info('{}') WHERE name = ?", table),
+        [column],
+        |row| row.get(0),
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_new_database_is_created_at_latest_version() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap();
+
+    block_on(async {
+        Database::new(path).await.unwrap();
+        // Reopening finds nothing to do
+        Database::new(path).await.unwrap();
+    });
+
+    let conn = rusqlite::Connection::open(path).unwrap();
+    assert_eq!(schema_version(&conn).unwrap(), LATEST_VERSION);
+    let applied: u32 = conn.query_row("SELECT COUNT(*) FROM schema_version", [], |row| row.get(0)).unwrap();
+    assert_eq!(applied, LATEST_VERSION);
+    assert_eq!(column_type(&conn, "prices", "price"), "TEXT");
+}
+
+#[test]
+fn test_every_past_schema_upgrades_to_latest() {
+    for (name, sql) in FIXTURES {
+        let file = fixture_database(sql);
+        let path = file.path().to_str().unwrap();
+
+        block_on(async {
+            let db = Database::new(path).await.unwrap_or_else(|e| panic!("{}: {}", name, e));
+
+            let btc = db.get_latest_price("BTCUSDT").await.unwrap().unwrap();
+            assert_eq!(btc.price, dec!(50000.5), "{}", name);
+            assert_eq!(btc.high_24h, dec!(51000), "{}", name);
+
+            // Duplicate bars are gone and the newest copy was kept
+            let candles = db.get_candles("BTCUSDT", "5m", 10).await.unwrap();
+            assert_eq!(candles.len(), 2, "{}", name);
+            let first = candles.iter().find(|c| c.timestamp == 1640995200000).unwrap();
+            assert_eq!(first.close, dec!(50150), "{}", name);
+
+            assert_eq!(db.get_sync_metadata("last_sync").await.unwrap().as_deref(), Some("1640995200"), "{}", name);
+        });
+
+        let conn = rusqlite::Connection::open(path).unwrap();
+        assert_eq!(schema_version(&conn).unwrap(), LATEST_VERSION, "{}", name);
+        assert_eq!(column_type(&conn, "prices", "bid_price"), "TEXT", "{}", name);
+        assert_eq!(column_type(&conn, "candles", "open"), "TEXT", "{}", name);
+        let indexes: u32 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE type = 'index' AND name IN
+                 ('idx_prices_symbol_timestamp', 'idx_candles_symbol_timeframe_timestamp', 'idx_prices_timestamp', 'idx_candles_unique')",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(indexes, 4, "{}", name);
+        let auto_vacuum: i64 = conn.query_row("PRAGMA auto_vacuum", [], |row| row.get(0)).unwrap();
+        assert_eq!(auto_vacuum, 2, "{} switches to incremental vacuum", name);
+    }
+}
+
+#[test]
+fn test_bid_ask_survive_decimal_conversion() {
+    let file = fixture_database(FIXTURES[1].1);
+
+    block_on(async {
+        let db = Database::new(file.path().to_str().unwrap()).await.unwrap();
+        let btc = db.get_latest_price("BTCUSDT").await.unwrap().unwrap();
+        assert_eq!(btc.bid_price, dec!(50000.25));
+        assert_eq!(btc.ask_price, dec!(50000.75));
+        assert_eq!(btc.quote_volume, 50000000.0);
+    });
+}
+
+#[test]
+fn test_downsampled_candles_move_out_of_exchange_candles() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap();
+    block_on(Database::new(path)).unwrap();
+
+    {
+        // As left by a cleanup before downsampled candles had their own table
+        let conn = rusqlite::Connection::open(path).unwrap();
+        conn.execute_batch(
+            "DROP TABLE downsampled_candles;
+            DELETE FROM schema_version WHERE version = 11;
+            INSERT INTO candle_coverage (symbol, timeframe, start_ms, end_ms) VALUES ('BTCUSDT', '1m', 1640995260000, 1640995260000);
+            INSERT INTO candles (symbol, timeframe, open, high, low, close, volume, timestamp) VALUES
+                ('BTCUSDT', '1m', '100', '103', '99', '99', 0.0, 1640995200000),
+                ('BTCUSDT', '1m', '99', '99', '99', '99', 0.0, 1640995260000),
+                ('BTCUSDT', '1m', '99', '101', '98', '100', 2.5, 1640995320000);",
+        )
+        .unwrap();
+    }
+
+    block_on(async {
+        let db = Database::new(path).await.unwrap();
+        let at = |seconds: i64| chrono::DateTime::from_timestamp(seconds, 0).unwrap();
+
+        // Fetched bars stay, even without volume
+        let klines = db.get_candles("BTCUSDT", "1m", 10).await.unwrap();
+        assert_eq!(klines.iter().map(|c| c.timestamp).collect::<Vec<_>>(), vec![1640995260000, 1640995320000]);
+
+        let downsampled = db.get_downsampled_candle_range("BTCUSDT", "1m", at(1640995200), at(1640995400)).await.unwrap();
+        assert_eq!(downsampled.len(), 1);
+        assert_eq!(downsampled[0].high, dec!(103));
+    });
+}
+
+#[test]
+fn test_newer_schema_is_refused() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap();
+    block_on(Database::new(path)).unwrap();
+
+    {
+        let conn = rusqlite::Connection::open(path).unwrap();
+        conn.execute(
+            "INSERT INTO schema_version (version, description) VALUES (?, 'from the future')",
+            [LATEST_VERSION + 1],
+        )
+        .unwrap();
+    }
+
+    let err = block_on(Database::new(path)).err().expect("newer schema must not open");
+    assert!(err.to_string().contains("newer than this build supports"), "{}", err);
+}
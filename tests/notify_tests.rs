@@ -0,0 +1,219 @@
+use coinpeek::app::AlertTrigger;
+use coinpeek::config::{NotificationConfig, SinkConfig, WebhookFormat};
+use coinpeek::error::CoinPeekError;
+use coinpeek::notify::{CommandNotifier, LogFileNotifier, Notifier, Notifiers, WebhookNotifier};
+use futures::future::{BoxFuture, FutureExt};
+use mockito::{Matcher, Server};
+use rust_decimal_macros::dec;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio_test::block_on;
+
+fn trigger() -> AlertTrigger {
+    AlertTrigger {
+        alert_id: 7,
+        symbol: "BTCUSDT".to_string(),
+        price: dec!(65000.5),
+        message: "BTCUSDT crossed above $65000".to_string(),
+        triggered_at: chrono::DateTime::from_timestamp(1640995200, 0).unwrap(),
+    }
+}
+
+/// Records what it was sent, failing the first `failures` deliveries
+struct Recorder {
+    received: Arc<Mutex<Vec<String>>>,
+    failures: Mutex<u32>,
+}
+
+impl Recorder {
+    fn new(failures: u32) -> (Self, Arc<Mutex<Vec<String>>>) {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        (Self { received: Arc::clone(&received), failures: Mutex::new(failures) }, received)
+    }
+}
+
+impl Notifier for Recorder {
+    fn notify<'a>(&'a self, trigger: &'a AlertTrigger) -> BoxFuture<'a, Result<(), CoinPeekError>> {
+        async move {
+            self.received.lock().unwrap().push(trigger.message.clone());
+            let mut failures = self.failures.lock().unwrap();
+            if *failures > 0 {
+                *failures -= 1;
+                return Err(CoinPeekError::Network("connection refused".to_string()));
+            }
+            Ok(())
+        }
+        .boxed()
+    }
+}
+
+#[test]
+fn test_webhook_payload_formats() {
+    let mut server = Server::new();
+    let cases = [
+        ("/json", WebhookFormat::Json, None, json!({
+            "alert_id": 7,
+            "symbol": "BTCUSDT",
+            "price": "65000.5",
+            "message": "BTCUSDT crossed above $65000",
+            "triggered_at": "2022-01-01T00:00:00+00:00",
+        })),
+        ("/slack", WebhookFormat::Slack, None, json!({ "text": "BTCUSDT crossed above $65000" })),
+        ("/discord", WebhookFormat::Discord, None, json!({ "content": "BTCUSDT crossed above $65000" })),
+        ("/", WebhookFormat::Ntfy, Some("prices".to_string()), json!({
+            "topic": "prices",
+            "title": "BTCUSDT alert",
+            "message": "BTCUSDT crossed above $65000",
+            "tags": ["chart_with_upwards_trend"],
+        })),
+    ];
+
+    for (path, format, topic, body) in cases {
+        let mock = server
+            .mock("POST", path)
+            .match_header("content-type", "application/json")
+            .match_body(Matcher::Json(body))
+            .with_status(200)
+            .expect(1)
+            .create();
+
+        let webhook = WebhookNotifier::new(format!("{}{}", server.url(), path), format, topic).unwrap();
+        block_on(webhook.notify(&trigger())).unwrap();
+        mock.assert();
+    }
+}
+
+#[test]
+fn test_webhook_error_status_is_reported() {
+    let mut server = Server::new();
+    let _mock = server
+        .mock("POST", "/hook")
+        .with_status(404)
+        .with_body("no such webhook")
+        .create();
+
+    let webhook = WebhookNotifier::new(format!("{}/hook", server.url()), WebhookFormat::Slack, None).unwrap();
+    match block_on(webhook.notify(&trigger())) {
+        Err(CoinPeekError::HttpStatus { status, message }) => {
+            assert_eq!(status, 404);
+            assert_eq!(message, "no such webhook");
+        }
+        other => panic!("expected an HTTP error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_failed_webhook_is_retried_then_reported() {
+    let mut server = Server::new();
+    let mock = server
+        .mock("POST", "/hook")
+        .with_status(503)
+        .expect(3)
+        .create();
+
+    let mut notifiers = Notifiers::new(2, Duration::from_millis(1));
+    let url = format!("{}/hook", server.url());
+    notifiers.add("slack", Box::new(WebhookNotifier::new(url, WebhookFormat::Slack, None).unwrap()));
+
+    let failures = block_on(notifiers.deliver(&trigger(), &["slack".to_string()]));
+    mock.assert();
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].0, "slack");
+    assert!(matches!(failures[0].1, CoinPeekError::HttpStatus { status: 503, .. }));
+}
+
+#[test]
+fn test_delivery_succeeds_after_retries() {
+    block_on(async {
+        let (recorder, received) = Recorder::new(2);
+        let mut notifiers = Notifiers::new(2, Duration::from_millis(1));
+        notifiers.add("flaky", Box::new(recorder));
+
+        assert!(notifiers.deliver(&trigger(), &["flaky".to_string()]).await.is_empty());
+        assert_eq!(received.lock().unwrap().len(), 3);
+    });
+}
+
+#[test]
+fn test_alerts_are_routed_to_their_sinks_or_the_defaults() {
+    block_on(async {
+        let (desktop, desktop_received) = Recorder::new(0);
+        let (slack, slack_received) = Recorder::new(0);
+        let mut notifiers = Notifiers::new(0, Duration::from_millis(1));
+        notifiers.add("desktop", Box::new(desktop));
+        notifiers.add("slack", Box::new(slack));
+        notifiers.set_default_sinks(vec!["desktop".to_string()]);
+
+        // No sinks of its own goes to the defaults
+        assert!(notifiers.deliver(&trigger(), &[]).await.is_empty());
+        assert_eq!(desktop_received.lock().unwrap().len(), 1);
+        assert!(slack_received.lock().unwrap().is_empty());
+
+        // Chosen sinks replace the defaults
+        assert!(notifiers.deliver(&trigger(), &["slack".to_string()]).await.is_empty());
+        assert_eq!(desktop_received.lock().unwrap().len(), 1);
+        assert_eq!(slack_received.lock().unwrap().len(), 1);
+
+        let failures = notifiers.deliver(&trigger(), &["slack".to_string(), "pager".to_string()]).await;
+        assert_eq!(slack_received.lock().unwrap().len(), 2);
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, "pager");
+    });
+}
+
+#[test]
+fn test_log_file_notifier_appends_lines() {
+    block_on(async {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("alerts.log");
+        let log = LogFileNotifier::new(path.to_str().unwrap());
+
+        let mut second = trigger();
+        second.message = "Multi\nline".to_string();
+        log.notify(&trigger()).await.unwrap();
+        log.notify(&second).await.unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            contents,
+            "2022-01-01T00:00:00+00:00\tBTCUSDT\t65000.5\tBTCUSDT crossed above $65000\n\
+             2022-01-01T00:00:00+00:00\tBTCUSDT\t65000.5\tMulti line\n"
+        );
+    });
+}
+
+#[cfg(unix)]
+#[test]
+fn test_command_notifier_passes_alert_in_environment() {
+    block_on(async {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("out.txt");
+        let command = format!("printf '%s|%s|%s' \"$COINPEEK_SYMBOL\" \"$COINPEEK_PRICE\" \"$COINPEEK_MESSAGE\" > '{}'", path.display());
+
+        CommandNotifier::new(command).notify(&trigger()).await.unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "BTCUSDT|65000.5|BTCUSDT crossed above $65000");
+
+        assert!(matches!(CommandNotifier::new("exit 3").notify(&trigger()).await, Err(CoinPeekError::Io(_))));
+    });
+}
+
+#[test]
+fn test_notifiers_from_config() {
+    block_on(async {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("alerts.log");
+        let config = NotificationConfig {
+            sinks: BTreeMap::from([("log".to_string(), SinkConfig::Log { path: path.to_str().unwrap().to_string() })]),
+            default_sinks: vec!["log".to_string()],
+            retries: 0,
+            retry_delay_ms: 0,
+        };
+
+        let notifiers = Notifiers::from_config(&config).unwrap();
+        assert!(notifiers.deliver(&trigger(), &[]).await.is_empty());
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 1);
+    });
+}
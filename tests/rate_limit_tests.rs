@@ -0,0 +1,83 @@
+use chrono::{Duration, TimeZone, Utc};
+
+use coinpeek::rate_limit::{backoff_delay, RateLimiter};
+
+fn at(seconds: u32) -> chrono::DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap() + Duration::seconds(seconds as i64)
+}
+
+#[test]
+fn test_weight_budget_blocks_until_next_minute() {
+    let limiter = RateLimiter::new(100);
+
+    // 90% of the limit is usable before requests are held back
+    for _ in 0..9 {
+        assert!(limiter.acquire(10, at(5)).is_ok());
+    }
+    let err = limiter.acquire(10, at(6)).unwrap_err();
+    assert_eq!(err.retry_at(), Some(at(60)));
+    assert_eq!(limiter.throttled_until(at(6)), Some(at(60)));
+
+    // Weight resets at the minute boundary
+    assert!(limiter.acquire(10, at(61)).is_ok());
+    assert_eq!(limiter.used_weight(at(61)), 10);
+    assert_eq!(limiter.throttled_until(at(61)), None);
+}
+
+#[test]
+fn test_used_weight_header_overrides_local_estimate() {
+    let limiter = RateLimiter::new(1000);
+
+    assert!(limiter.acquire(2, at(1)).is_ok());
+    // Another process on the same IP has been busy
+    limiter.record_used_weight(899, at(2));
+    assert_eq!(limiter.used_weight(at(2)), 899);
+    assert!(limiter.acquire(2, at(3)).is_err());
+}
+
+#[test]
+fn test_retry_after_is_honoured() {
+    let limiter = RateLimiter::new(6000);
+
+    let err = limiter.record_rate_limited(429, Some(30), at(0));
+    assert_eq!(err.retry_at(), Some(at(30)));
+    assert!(err.to_string().contains("429"));
+
+    assert!(limiter.acquire(1, at(29)).is_err());
+    assert!(limiter.acquire(1, at(30)).is_ok());
+}
+
+#[test]
+fn test_ban_is_not_shortened_by_later_failures() {
+    let limiter = RateLimiter::new(6000);
+
+    limiter.record_rate_limited(418, Some(120), at(0));
+    limiter.record_failure(at(1));
+
+    let err = limiter.acquire(1, at(10)).unwrap_err();
+    assert_eq!(err.retry_at(), Some(at(120)));
+    assert!(err.to_string().contains("418"));
+}
+
+#[test]
+fn test_backoff_grows_with_jitter_and_resets_on_success() {
+    for seed in [0, 1, 12345, u32::MAX] {
+        let first = backoff_delay(1, seed);
+        assert!(first >= Duration::milliseconds(500) && first <= Duration::seconds(1));
+
+        let fourth = backoff_delay(4, seed);
+        assert!(fourth >= Duration::seconds(4) && fourth <= Duration::seconds(8));
+
+        let capped = backoff_delay(50, seed);
+        assert!(capped <= Duration::minutes(5));
+    }
+
+    let limiter = RateLimiter::new(6000);
+    limiter.record_failure(at(0));
+    assert!(limiter.throttled_until(at(0)).is_some());
+    assert!(limiter.acquire(1, at(2)).is_ok(), "First backoff is at most one second");
+
+    limiter.record_success();
+    limiter.record_failure(at(10));
+    assert!(limiter.throttled_until(at(12)).is_none(), "Success should reset the backoff");
+}
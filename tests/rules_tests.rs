@@ -0,0 +1,114 @@
+use rust_decimal_macros::dec;
+
+use coinpeek::binance::PriceInfo;
+use coinpeek::rules::{AlertRule, Comparison, Expr, Metric, Rule};
+
+fn ticker(symbol: &str, price: rust_decimal::Decimal, change: f64, volume: f64) -> PriceInfo {
+    PriceInfo { symbol: symbol.to_string(), price, price_change_percent: change, volume, ..Default::default() }
+}
+
+fn holds(rule: &str, tickers: &[PriceInfo]) -> bool {
+    AlertRule::parse(rule).unwrap().holds(tickers)
+}
+
+#[test]
+fn test_parse_comparison_with_fields() {
+    let rule = AlertRule::parse("ETHUSDT.volume   >= 500000").unwrap();
+    assert_eq!(rule.text(), "ETHUSDT.volume >= 500000");
+    assert_eq!(
+        rule.rule(),
+        &Rule::Compare {
+            left: Expr::Metric { symbol: "ETHUSDT".to_string(), metric: Metric::Volume },
+            comparison: Comparison::AtLeast,
+            right: Expr::Number(dec!(500000)),
+        }
+    );
+}
+
+#[test]
+fn test_and_binds_tighter_than_or() {
+    let tickers = [ticker("ETHUSDT", dec!(1900), 0.0, 100.0), ticker("BTCUSDT", dec!(60000), 0.0, 10.0)];
+    assert!(holds("ETHUSDT < 2000 and ETHUSDT.volume > 50", &tickers));
+    assert!(!holds("ETHUSDT < 2000 and ETHUSDT.volume > 500", &tickers));
+    assert!(holds("BTCUSDT > 70000 or ETHUSDT < 2000 and ETHUSDT.volume > 50", &tickers));
+    assert!(!holds("(BTCUSDT > 70000 or ETHUSDT < 2000) and ETHUSDT.volume > 500", &tickers));
+    assert!(holds("not (BTCUSDT > 70000)", &tickers));
+}
+
+#[test]
+fn test_ratio_and_arithmetic() {
+    let tickers = [ticker("ETHUSDT", dec!(3000), 0.0, 0.0), ticker("BTCUSDT", dec!(60000), 0.0, 0.0)];
+    assert!(holds("ETHUSDT / BTCUSDT <= 0.05", &tickers));
+    assert!(!holds("ETHUSDT / BTCUSDT < 0.05", &tickers));
+    assert!(holds("(ETHUSDT + BTCUSDT) * 2 > 120000", &tickers));
+    assert!(holds("ETHUSDT - BTCUSDT < -50000", &tickers));
+}
+
+#[test]
+fn test_parentheses_hold_rules_or_values() {
+    let tickers = [ticker("ETHUSDT", dec!(3000), 0.0, 0.0), ticker("BTCUSDT", dec!(60000), 0.0, 0.0)];
+    assert!(holds("((ETHUSDT + BTCUSDT)) / 3 > 20000", &tickers));
+    assert!(holds("((ETHUSDT > 2000) and (BTCUSDT < 70000))", &tickers));
+    assert!(holds("(ETHUSDT) < (BTCUSDT)", &tickers));
+    assert!(!holds("not ((ETHUSDT > 2000))", &tickers));
+    assert!(AlertRule::parse("(ETHUSDT and BTCUSDT > 1)").unwrap_err().starts_with("Expected <, <=, > or >= but found 'and'"));
+
+    // Each parenthesis is parsed once, so deep nesting stays quick
+    let depth = 200;
+    let rule = format!("{}ETHUSDT > 2000{}", "(".repeat(depth), ")".repeat(depth));
+    assert!(holds(&rule, &tickers));
+    let rule = format!("{}ETHUSDT{} > 2000", "(".repeat(depth), ")".repeat(depth));
+    assert!(holds(&rule, &tickers));
+}
+
+#[test]
+fn test_any_and_all_groups() {
+    let tickers = [
+        ticker("BTCUSDT", dec!(60000), -1.0, 0.0),
+        ticker("ETHUSDT", dec!(3000), -6.0, 0.0),
+        ticker("SOLUSDT", dec!(150), -2.0, 0.0),
+    ];
+    assert!(holds("any(BTCUSDT, ETHUSDT, SOLUSDT).change <= -5", &tickers));
+    assert!(!holds("all(BTCUSDT, ETHUSDT, SOLUSDT).change <= -5", &tickers));
+    assert!(holds("all(BTCUSDT, ETHUSDT).change < 0", &tickers));
+
+    let rule = AlertRule::parse("any(BTCUSDT, ETHUSDT).change <= -5").unwrap();
+    assert_eq!(rule.symbols(), vec!["BTCUSDT", "ETHUSDT"]);
+    assert!(matches!(rule.rule(), Rule::Any(rules) if rules.len() == 2));
+}
+
+#[test]
+fn test_missing_tickers_never_fire() {
+    let tickers = [ticker("BTCUSDT", dec!(60000), 0.0, 0.0)];
+    assert!(!holds("ETHUSDT < 2000", &tickers));
+    assert!(!holds("not (ETHUSDT < 2000)", &tickers));
+    assert!(holds("BTCUSDT > 50000 or ETHUSDT < 2000", &tickers));
+    assert!(!holds("BTCUSDT / ETHUSDT > 10", &tickers));
+    assert!(!holds("BTCUSDT / 0 > 10", &tickers));
+}
+
+#[test]
+fn test_parse_errors() {
+    for (rule, error) in [
+        ("", "The rule is empty"),
+        ("ETHUSDT", "Expected <, <=, > or >= at the end of the rule"),
+        ("ETHUSDT.spread > 1", "Unknown field 'spread'"),
+        ("ethusdt > 1", "Invalid symbol 'ethusdt'"),
+        ("1 < 2", "The rule doesn't read any symbol"),
+        ("any(BTCUSDT) > all(ETHUSDT)", "Only one any(...) or all(...) per comparison"),
+        ("BTCUSDT > 1 and", "Expected a symbol or number at the end of the rule"),
+        ("BTCUSDT > 1 $", "Unexpected '$' in rule"),
+    ] {
+        let message = AlertRule::parse(rule).unwrap_err();
+        assert!(message.starts_with(error), "{}: {}", rule, message);
+    }
+}
+
+#[test]
+fn test_rule_serializes_as_text() {
+    let rule = AlertRule::parse("ETHUSDT / BTCUSDT > 0.05").unwrap();
+    let json = serde_json::to_string(&rule).unwrap();
+    assert_eq!(json, "\"ETHUSDT / BTCUSDT > 0.05\"");
+    assert_eq!(serde_json::from_str::<AlertRule>(&json).unwrap(), rule);
+    assert!(serde_json::from_str::<AlertRule>("\"ETHUSDT >\"").is_err());
+}
@@ -0,0 +1,118 @@
+use std::time::Duration;
+
+use futures::{SinkExt, StreamExt};
+use rust_decimal_macros::dec;
+use tokio::net::TcpListener;
+use tokio_test::block_on;
+use tokio_tungstenite::tungstenite::Message;
+
+use coinpeek::binance::{combined_stream_url, BinanceClient};
+use coinpeek::config::ApiConfig;
+use coinpeek::exchange::{MarketDataSource, StreamEvent, TickerStream};
+
+/// Build a combined-stream ticker message the way Binance sends it
+fn ticker_message(symbol: &str, price: &str) -> String {
+    serde_json::json!({
+        "stream": format!("{}@ticker", symbol.to_lowercase()),
+        "data": {
+            "e": "24hrTicker", "E": 1640995200000u64, "s": symbol,
+            "p": "100.0", "P": "2.50", "w": price, "x": "48750.00",
+            "c": price, "Q": "0.01", "b": price, "B": "1.0", "a": price, "A": "1.0",
+            "o": "48750.00", "h": "51000.00", "l": "49000.00",
+            "v": "1000.0", "q": "50000000.0",
+            "O": 1640908800000u64, "C": 1640995200000u64, "F": 1u64, "L": 2u64, "n": 2u64
+        }
+    })
+    .to_string()
+}
+
+async fn next_event(stream: &mut TickerStream) -> StreamEvent {
+    tokio::time::timeout(Duration::from_secs(10), stream.next())
+        .await
+        .expect("timed out waiting for stream event")
+        .expect("stream ended unexpectedly")
+}
+
+#[test]
+fn test_combined_stream_url() {
+    let symbols = vec!["BTCUSDT".to_string(), "ETHUSDT".to_string()];
+    assert_eq!(
+        combined_stream_url("wss://stream.binance.com:9443/", &symbols),
+        "wss://stream.binance.com:9443/stream?streams=btcusdt@ticker/ethusdt@ticker"
+    );
+}
+
+#[test]
+fn test_ticker_stream_against_local_server() {
+    block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (pong_tx, pong_rx) = tokio::sync::oneshot::channel();
+
+        let server = tokio::spawn(async move {
+            // First connection: ping the client, push a ticker, then drop it
+            let (tcp, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+            ws.send(Message::Ping(b"heartbeat".to_vec())).await.unwrap();
+            ws.send(Message::Text(ticker_message("BTCUSDT", "50000.50"))).await.unwrap();
+
+            while let Some(Ok(message)) = ws.next().await {
+                if let Message::Pong(payload) = message {
+                    let _ = pong_tx.send(payload);
+                    break;
+                }
+            }
+            ws.close(None).await.unwrap();
+            drop(ws);
+
+            // Second connection: the client should reconnect on its own
+            let (tcp, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+            ws.send(Message::Text("{\"stream\":\"ethusdt@ticker\"}".to_string())).await.unwrap();
+            ws.send(Message::Text(ticker_message("ETHUSDT", "3000.25"))).await.unwrap();
+            while ws.next().await.is_some() {}
+        });
+
+        let config = ApiConfig {
+            stream_url: format!("ws://{}", addr),
+            ..ApiConfig::default()
+        };
+        let client = BinanceClient::new(&config).unwrap();
+        let mut stream = client
+            .subscribe_tickers(&["BTCUSDT".to_string(), "ETHUSDT".to_string()])
+            .unwrap();
+
+        assert!(matches!(next_event(&mut stream).await, StreamEvent::Connected));
+        match next_event(&mut stream).await {
+            StreamEvent::Ticker(info) => {
+                assert_eq!(info.symbol, "BTCUSDT");
+                assert_eq!(info.price, dec!(50000.5));
+                assert_eq!(info.price_change_percent, 2.5);
+            }
+            other => panic!("expected ticker, got {:?}", other),
+        }
+
+        let pong = tokio::time::timeout(Duration::from_secs(10), pong_rx).await.unwrap().unwrap();
+        assert_eq!(pong, b"heartbeat".to_vec());
+
+        assert!(matches!(next_event(&mut stream).await, StreamEvent::Disconnected(_)));
+        assert!(matches!(next_event(&mut stream).await, StreamEvent::Connected));
+        // A message that can't be read is reported without dropping the connection
+        assert!(matches!(next_event(&mut stream).await, StreamEvent::Skipped(_)));
+        match next_event(&mut stream).await {
+            StreamEvent::Ticker(info) => assert_eq!(info.symbol, "ETHUSDT"),
+            other => panic!("expected ticker after reconnect, got {:?}", other),
+        }
+
+        drop(stream);
+        server.abort();
+    });
+}
+
+#[test]
+fn test_subscribe_rejects_invalid_symbols() {
+    block_on(async {
+        let client = BinanceClient::new(&ApiConfig::default()).unwrap();
+        assert!(client.subscribe_tickers(&["btc/usdt".to_string()]).is_err());
+    });
+}
@@ -0,0 +1,28 @@
+use coinpeek::timeframe::TimeFrame;
+
+#[test]
+fn test_timeframe_round_trips_through_interval_name() {
+    for timeframe in TimeFrame::ALL {
+        assert_eq!(TimeFrame::parse(timeframe.as_str()), Some(timeframe));
+    }
+    assert_eq!(TimeFrame::parse("2m"), None);
+}
+
+#[test]
+fn test_bar_start() {
+    // 2022-01-01 00:07:30 UTC, a Saturday
+    let ts = 1_640_995_650_000;
+    assert_eq!(TimeFrame::M1.bar_start(ts), 1_640_995_620_000);
+    assert_eq!(TimeFrame::M5.bar_start(ts), 1_640_995_500_000);
+    assert_eq!(TimeFrame::H1.bar_start(ts), 1_640_995_200_000);
+    assert_eq!(TimeFrame::D1.bar_start(ts), 1_640_995_200_000);
+    // Weekly bars open on Monday 2021-12-27
+    assert_eq!(TimeFrame::W1.bar_start(ts), 1_640_563_200_000);
+    assert_eq!(TimeFrame::W1.bar_start(1_640_563_200_000), 1_640_563_200_000);
+}
+
+#[test]
+fn test_timeframe_serializes_as_interval_name() {
+    assert_eq!(serde_json::to_string(&TimeFrame::H4).unwrap(), "\"4h\"");
+    assert_eq!(serde_json::from_str::<TimeFrame>("\"1w\"").unwrap(), TimeFrame::W1);
+}
@@ -0,0 +1,32 @@
+use rust_decimal_macros::dec;
+
+use coinpeek::utils::{default_price_decimals, format_price, round_to_tick, tick_decimals};
+
+#[test]
+fn test_small_prices_keep_significant_digits() {
+    // Without a tick size, sub-dollar prices must not collapse to 0.02
+    assert_eq!(format_price(dec!(0.02345), None), "0.02345");
+    assert_eq!(format_price(dec!(0.00001234), None), "0.00001234");
+    assert_eq!(format_price(dec!(50000.456), None), "50000.46");
+    assert_eq!(format_price(dec!(0), None), "0.00");
+
+    assert_eq!(default_price_decimals(dec!(1.5)), 2);
+    assert_eq!(default_price_decimals(dec!(0.5)), 4);
+    assert_eq!(default_price_decimals(dec!(0.000000001)), 8);
+}
+
+#[test]
+fn test_prices_follow_tick_size() {
+    assert_eq!(tick_decimals(dec!(0.01000000)), 2);
+    assert_eq!(tick_decimals(dec!(0.00001000)), 5);
+    assert_eq!(tick_decimals(dec!(1.00000000)), 0);
+
+    assert_eq!(round_to_tick(dec!(0.023456), dec!(0.00001)), dec!(0.02346));
+    assert_eq!(round_to_tick(dec!(101.3), dec!(0.5)), dec!(101.5));
+
+    assert_eq!(format_price(dec!(0.023456), Some(dec!(0.00001000))), "0.02346");
+    assert_eq!(format_price(dec!(50000.456), Some(dec!(0.01000000))), "50000.46");
+    assert_eq!(format_price(dec!(101.3), Some(dec!(0.5))), "101.5");
+    // A zero tick size is treated as unknown
+    assert_eq!(format_price(dec!(0.02345), Some(dec!(0))), "0.02345");
+}